@@ -7,18 +7,26 @@
 // (pattern shares bind group 0 with FFT pipelines).
 struct FftSettings {
     size: vec2<u32>,
-    orders: u32,
+    orders: vec2<u32>,
+    fft_size: vec2<u32>,
     padding: vec2<u32>,
     schedule: u32,
     pattern_target: u32,
     window_type: u32,
     window_strength: f32,
-    radial_falloff: f32,
+    window_param: f32,
     normalization: f32,
+    radix: u32,
+    dimension: u32,
+    depth: u32,
+    fft_normalization: u32,
+    window_axes: u32,
+    storage_precision: u32,
+    channels: u32,
 }
 
 struct FftRoots {
-    roots: array<c32, 8192>,
+    roots: array<c32>,
 }
 
 @group(0) @binding(0) var<uniform> globals: Globals;
@@ -14,6 +14,8 @@
     },
     bindings::{
         settings,
+        bluestein_kernel,
+        bluestein_workspace,
     },
     buffer::{
         read_buffer_a,
@@ -22,8 +24,9 @@
         write_buffer_a,
         write_buffer_b,
         write_buffer_c,
-        write_shifted_d_re,
-        write_shifted_d_im,
+        write_buffer_d,
+        fft_lanes,
+        fft_lanes_vec4,
     },
     plot::{
         apply_window
@@ -31,6 +34,7 @@
     fft_common::{
         fft_reverse_lower_bits,
         get_fft_root,
+        get_fft_twiddle,
     },
 };
 
@@ -52,53 +56,153 @@ struct FftPushConstants {
     src_buffer: u32,
     dst_buffer: u32,
     flags: u32,
+    // Batched Bluestein passes cover `line_span` lines across `axis` from `line_base`; a zero span
+    // covers every line.
+    line_base: u32,
+    line_span: u32,
 }
 
+// Devices without push constants (WebGPU) bind one uniform slot per dispatch instead.
+#ifdef FFT_UNIFORM_CONSTANTS
+@group(1) @binding(0) var<uniform> pc: FftPushConstants;
+#else
 var<push_constant> pc: FftPushConstants;
+#endif
 
 const FLAG_INVERSE_FINALIZE: u32 = 1u;
 const FLAG_FORWARD_ALPHA: u32 = 2u;
+// Stockham stages fold the bit-reverse pass work into their loads and stores.
+const FLAG_FORWARD_WINDOW: u32 = 4u;
+const FLAG_INVERSE_CONJ_INPUT: u32 = 8u;
+// Last forward pass on an axis: apply the forward half of `FftNormalization`.
+const FLAG_FORWARD_SCALE: u32 = 16u;
 const WG: u32 = 256u;
+const PI: f32 = 3.141592653589793;
+
+// Bluestein workspace slots (buffer ids 3 and 4) hold one transform-length line per grid line
+// of the current batch along `pc.axis`, so positions past the texture edge stay addressable.
+fn bluestein_index(slot: u32, pos: vec3<u32>) -> u32 {
+    let across = fft_extent()[fft_cross_axes(pc.axis).x];
+    let span = select(across, pc.line_span, pc.line_span != 0u);
+    let line = fft_line_of(pc.axis, pos);
+    let slot_line = line % across - pc.line_base + (line / across) * span;
+    return ((slot_line * fft_transform_len(pc.axis) + pos[pc.axis]) * 2u + slot) * 2u;
+}
 
-fn read_fft_buf(buf_id: u32, pos: vec2<u32>) -> c32_n {
+fn read_fft_buf(buf_id: u32, pos: vec3<u32>) -> c32_n {
     switch buf_id {
         case 0u: { return read_buffer_a(pos); }
         case 1u: { return read_buffer_b(pos); }
         case 2u: { return read_buffer_c(pos); }
+        case 3u, 4u: {
+            let i = bluestein_index(buf_id - 3u, pos);
+            return c32_n(fft_lanes(bluestein_workspace[i]), fft_lanes(bluestein_workspace[i + 1u]));
+        }
         default: { return splat_c32_n(c32(0.0, 0.0)); }
     }
 }
 
-fn write_fft_buf(buf_id: u32, pos: vec2<u32>, value: c32_n) {
+fn write_fft_buf(buf_id: u32, pos: vec3<u32>, value: c32_n) {
     switch buf_id {
         case 0u: { write_buffer_a(pos, value); }
         case 1u: { write_buffer_b(pos, value); }
         case 2u: { write_buffer_c(pos, value); }
+        case 3u, 4u: {
+            let i = bluestein_index(buf_id - 3u, pos);
+            bluestein_workspace[i] = fft_lanes_vec4(value.re);
+            bluestein_workspace[i + 1u] = fft_lanes_vec4(value.im);
+        }
         default: { }
     }
 }
 
+// Only four-channel workspaces carry an alpha lane; narrower ones pass through unchanged.
 fn mark_opaque_alpha(vs: c32_n) -> c32_n {
     var out = vs;
+#if CHANNELS == 4
     out.re.w = 1.0;
     out.im.w = 1.0;
+#endif
     return out;
 }
 
-fn dit_butterfly_writes(pos_u: vec2<u32>, pos_v: vec2<u32>, j: u32) {
-    let N = settings.size.x;
-    let inv_scale = 1.0 / f32(N);
-    let root = get_fft_root(pc.stage + 1u, j);
+// Logical grid extent. `z` is the volume depth under `FFT_VOLUME`, the layer count under
+// `FFT_LAYERS`, and 1 otherwise.
+fn fft_extent() -> vec3<u32> {
+    return vec3<u32>(settings.size, settings.depth);
+}
 
-    let a = read_fft_buf(pc.src_buffer, pos_u);
-    let b = mul_c32_n(read_fft_buf(pc.src_buffer, pos_v), splat_c32_n(root));
-    var out_u = add_c32_n(a, b);
-    var out_v = add_c32_n(a, neg_c32_n(b));
+// The two axes across `axis`, in the order `fft_line_of` packs them into a line index.
+fn fft_cross_axes(axis: u32) -> vec2<u32> {
+    return vec2<u32>(select(0u, 1u, axis == 0u), select(2u, 1u, axis == 2u));
+}
+
+// Samples along `axis` (0 rows, 1 columns, 2 depth).
+fn fft_axis_len(axis: u32) -> u32 {
+    return fft_extent()[axis];
+}
+
+// Samples along `axis` that hold signal rather than zero padding.
+fn fft_signal_len(axis: u32) -> u32 {
+    let pad = select(0u, settings.padding[min(axis, 1u)], axis < 2u);
+    return fft_axis_len(axis) - pad;
+}
+
+// Lines across `axis`, one transform each.
+fn fft_line_count(axis: u32) -> u32 {
+    let extent = fft_extent();
+    let cross = fft_cross_axes(axis);
+    return extent[cross.x] * extent[cross.y];
+}
+
+// Line index of a line-kernel invocation. The host spreads lines over the `y` and `z` workgroup
+// counts so volume axes stay under the per-dimension dispatch limit; batches start `y` at
+// `pc.line_base`.
+fn fft_line_id(id: vec3<u32>) -> u32 {
+    return id.y + pc.line_base + id.z * fft_extent()[fft_cross_axes(pc.axis).x];
+}
+
+// Power-of-two transform length along `axis`, padded past `fft_axis_len` on Bluestein axes.
+// Volume depths are always powers of two.
+fn fft_transform_len(axis: u32) -> u32 {
+    return vec3<u32>(settings.fft_size, settings.depth)[axis];
+}
+
+fn fft_axis_order(axis: u32) -> u32 {
+    return vec3<u32>(settings.orders, countTrailingZeros(settings.depth))[axis];
+}
 
+// `FftNormalization` factor for the last pass on `pc.axis`, in the direction the flags select.
+fn fft_output_scale() -> f32 {
+    let n = f32(fft_axis_len(pc.axis));
+    let inverse = (pc.flags & FLAG_INVERSE_FINALIZE) != 0u;
+    switch settings.fft_normalization {
+        case 0u: { return select(1.0, 1.0 / n, inverse); }
+        case 1u: { return select(1.0 / n, 1.0, inverse); }
+        case 2u: { return inverseSqrt(n); }
+        default: { return 1.0; }
+    }
+}
+
+// Undoes the conjugate trick on the last inverse pass and applies the normalization scale.
+fn fft_finish_output(value: c32_n) -> c32_n {
+    var out = value;
     if ((pc.flags & FLAG_INVERSE_FINALIZE) != 0u) {
-        out_u = mul_c32_n(conj_c32_n(out_u), splat_c32_n(c32(inv_scale, 0.0)));
-        out_v = mul_c32_n(conj_c32_n(out_v), splat_c32_n(c32(inv_scale, 0.0)));
+        out = conj_c32_n(out);
+    }
+    if ((pc.flags & (FLAG_INVERSE_FINALIZE | FLAG_FORWARD_SCALE)) != 0u) {
+        out = mul_c32_n(out, splat_c32_n(c32(fft_output_scale(), 0.0)));
     }
+    return out;
+}
+
+fn dit_butterfly_writes(pos_u: vec3<u32>, pos_v: vec3<u32>, j: u32) {
+    let root = get_fft_root(pc.stage + 1u, j);
+
+    let a = read_fft_buf(pc.src_buffer, pos_u);
+    let b = mul_c32_n(read_fft_buf(pc.src_buffer, pos_v), splat_c32_n(root));
+    var out_u = fft_finish_output(add_c32_n(a, b));
+    var out_v = fft_finish_output(add_c32_n(a, neg_c32_n(b)));
 
     if ((pc.flags & FLAG_FORWARD_ALPHA) != 0u) {
         out_u = mark_opaque_alpha(out_u);
@@ -109,62 +213,21 @@ fn dit_butterfly_writes(pos_u: vec2<u32>, pos_v: vec2<u32>, j: u32) {
     write_fft_buf(pc.dst_buffer, pos_v, out_v);
 
     if ((pc.flags & FLAG_FORWARD_ALPHA) != 0u) {
-        write_shifted_d_re(pos_u, out_u.re);
-        write_shifted_d_im(pos_u, out_u.im);
-        write_shifted_d_re(pos_v, out_v.re);
-        write_shifted_d_im(pos_v, out_v.im);
-    }
-}
-
-/// Bit-reverse permute along rows, optional Tukey window on spatial data, natural order in X.
-@compute @workgroup_size(8, 8, 1)
-fn fft_forward_br_horizontal(@builtin(global_invocation_id) gid: vec3<u32>) {
-    let p = gid.xy;
-    let dims = settings.size;
-    if (p.x >= dims.x || p.y >= dims.y) {
-        return;
-    }
-    let order = settings.orders;
-    let rx = fft_reverse_lower_bits(p.x, order);
-    var v = read_buffer_a(p);
-    let window_type = settings.window_type;
-    let window_strength = settings.window_strength;
-    let w = apply_window(p, dims, window_type, window_strength);
-    v = mul_c32_n(v, splat_c32_n(c32(w, 0.0)));
-    v = mark_opaque_alpha(v);
-    let dst = vec2<u32>(rx, p.y);
-    write_buffer_b(dst, v);
-    write_shifted_d_re(p, v.re);
-    write_shifted_d_im(p, v.im);
-}
-
-/// Bit-reverse along columns after row FFT; reads B, writes C.
-@compute @workgroup_size(8, 8, 1)
-fn fft_forward_br_vertical(@builtin(global_invocation_id) gid: vec3<u32>) {
-    let p = gid.xy;
-    let dims = settings.size;
-    if (p.x >= dims.x || p.y >= dims.y) {
-        return;
+        write_buffer_d(pos_u, out_u);
+        write_buffer_d(pos_v, out_v);
     }
-    let order = settings.orders;
-    let ry = fft_reverse_lower_bits(p.y, order);
-    var v = read_buffer_b(p);
-    v = mark_opaque_alpha(v);
-    let dst = vec2<u32>(p.x, ry);
-    write_buffer_c(dst, v);
-    write_shifted_d_re(p, v.re);
-    write_shifted_d_im(p, v.im);
 }
 
-/// One radix-2 DIT stage; horizontal axis if pc.axis == 0, else vertical.
+/// One radix-2 DIT stage along `pc.axis`: rows, columns or, under `FFT_VOLUME`, depth.
+/// Every axis uses its own length, so rectangular grids work.
 @compute @workgroup_size(256, 1, 1)
 fn fft_radix2_dit(@builtin(global_invocation_id) gid: vec3<u32>) {
-    let dims = settings.size;
-    let N = dims.x;
+    let N = fft_transform_len(pc.axis);
+    let lines = fft_line_count(pc.axis);
     let half_n = N >> 1u;
     let butterfly = gid.x;
-    let line = gid.y;
-    if (butterfly >= half_n || line >= N) {
+    let line = fft_line_id(gid);
+    if (butterfly >= half_n || line >= lines) {
         return;
     }
 
@@ -176,25 +239,255 @@ fn fft_radix2_dit(@builtin(global_invocation_id) gid: vec3<u32>) {
     let u = k + j;
     let v = u + m2;
 
-    if (pc.axis == 0u) {
-        let pos_u = vec2<u32>(u, line);
-        let pos_v = vec2<u32>(v, line);
-        dit_butterfly_writes(pos_u, pos_v, j);
-    } else {
-        let pos_u = vec2<u32>(line, u);
-        let pos_v = vec2<u32>(line, v);
-        dit_butterfly_writes(pos_u, pos_v, j);
+    dit_butterfly_writes(fft_axis_pos(pc.axis, line, u), fft_axis_pos(pc.axis, line, v), j);
+}
+
+// `fft_axis_pos` over the whole extent, for volumes and layered grids.
+fn fft_grid_axis_pos(axis: u32, line: u32, i: u32) -> vec3<u32> {
+    let cross = fft_cross_axes(axis);
+    let stride = fft_extent()[cross.x];
+    var pos = vec3<u32>(0u);
+    pos[axis] = i;
+    pos[cross.x] = line % stride;
+    pos[cross.y] = line / stride;
+    return pos;
+}
+
+// Grid position of element `i` on `line` along `axis`. Under `FFT_LAYERS` the lines of every
+// layer follow each other, so one dispatch covers the whole stack.
+fn fft_axis_pos(axis: u32, line: u32, i: u32) -> vec3<u32> {
+#ifdef FFT_VOLUME
+    return fft_grid_axis_pos(axis, line, i);
+#else ifdef FFT_LAYERS
+    return fft_grid_axis_pos(axis, line, i);
+#else
+    return select(vec3<u32>(line, i, 0u), vec3<u32>(i, line, 0u), axis == 0u);
+#endif
+}
+
+// Inverse of `fft_axis_pos`: the line through `pos` along `axis`.
+fn fft_line_of(axis: u32, pos: vec3<u32>) -> u32 {
+#ifdef FFT_VOLUME
+    let cross = fft_cross_axes(axis);
+    return pos[cross.x] + pos[cross.y] * fft_extent()[cross.x];
+#else ifdef FFT_LAYERS
+    let cross = fft_cross_axes(axis);
+    return pos[cross.x] + pos[cross.y] * fft_extent()[cross.x];
+#else
+    return select(pos.x, pos.y, axis == 0u);
+#endif
+}
+
+fn fft_line_load(line: u32, i: u32) -> c32_n {
+    let pos = fft_axis_pos(pc.axis, line, i);
+    var v = read_fft_buf(pc.src_buffer, pos);
+    if ((pc.flags & FLAG_FORWARD_WINDOW) != 0u) {
+        let w = apply_window(
+            i,
+            fft_signal_len(pc.axis),
+            settings.window_type,
+            settings.window_param,
+            settings.window_strength,
+        );
+        v = mul_c32_n(v, splat_c32_n(c32(w, 0.0)));
+    }
+    if ((pc.flags & FLAG_INVERSE_CONJ_INPUT) != 0u) {
+        v = conj_c32_n(v);
     }
+    return v;
 }
 
-@compute @workgroup_size(16, 16, 1)
+fn fft_line_store(line: u32, i: u32, value: c32_n) {
+    let pos = fft_axis_pos(pc.axis, line, i);
+    var out = fft_finish_output(value);
+    if ((pc.flags & FLAG_FORWARD_ALPHA) != 0u) {
+        out = mark_opaque_alpha(out);
+        write_buffer_d(pos, out);
+    }
+    write_fft_buf(pc.dst_buffer, pos, out);
+}
+
+/// Bit-reverse permute along `pc.axis` from `pc.src_buffer` into `pc.dst_buffer`, the first step
+/// of a radix-2 DIT axis. Load and store flags apply here like in the other line kernels, which
+/// also makes it the identity pass for length-one axes.
+@compute @workgroup_size(256, 1, 1)
+fn fft_bit_reverse(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let i = gid.x;
+    let line = fft_line_id(gid);
+    if (i >= fft_transform_len(pc.axis) || line >= fft_line_count(pc.axis)) {
+        return;
+    }
+    let value = fft_line_load(line, i);
+    fft_line_store(line, fft_reverse_lower_bits(i, fft_axis_order(pc.axis)), value);
+}
+
+/// Copies whole lines along `pc.axis`, used when a radix-2 chain ends in the wrong buffer.
+@compute @workgroup_size(256, 1, 1)
 fn fft_copy_buffer(@builtin(global_invocation_id) gid: vec3<u32>) {
-    let p64 = vec2<u32>(gid.xy);
-    let dims = settings.size;
-    if (p64.x >= dims.x || p64.y >= dims.y) {
+    let i = gid.x;
+    let line = fft_line_id(gid);
+    if (i >= fft_transform_len(pc.axis) || line >= fft_line_count(pc.axis)) {
+        return;
+    }
+    let pos = fft_axis_pos(pc.axis, line, i);
+    write_fft_buf(pc.dst_buffer, pos, read_fft_buf(pc.src_buffer, pos));
+}
+
+// Multiplies by `-i`, the quarter-turn inside a forward radix-4 butterfly.
+fn mul_neg_i(v: c32_n) -> c32_n {
+    return c32_n(v.im, -v.re);
+}
+
+/// One Stockham autosort radix-2 stage. `pc.stage` is log2 of `ns`, the sub-transform length that
+/// earlier stages already finished. Input and output stay in natural order.
+@compute @workgroup_size(256, 1, 1)
+fn fft_stockham_radix2(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let N = fft_transform_len(pc.axis);
+    let lines = fft_line_count(pc.axis);
+    let half_n = N >> 1u;
+    let j = gid.x;
+    let line = fft_line_id(gid);
+    if (j >= half_n || line >= lines) {
+        return;
+    }
+
+    let ns = 1u << pc.stage;
+    let k = j & (ns - 1u);
+    let order = pc.stage + 1u;
+
+    let v0 = fft_line_load(line, j);
+    let v1 = mul_c32_n(fft_line_load(line, j + half_n), splat_c32_n(get_fft_twiddle(order, k)));
+
+    let base = (j - k) * 2u + k;
+    fft_line_store(line, base, add_c32_n(v0, v1));
+    fft_line_store(line, base + ns, add_c32_n(v0, neg_c32_n(v1)));
+}
+
+/// One Stockham autosort radix-4 stage, same `pc.stage` meaning as [`fft_stockham_radix2`].
+/// Each invocation reads four samples a quarter-length apart and writes four outputs `ns` apart.
+@compute @workgroup_size(256, 1, 1)
+fn fft_stockham_radix4(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let N = fft_transform_len(pc.axis);
+    let lines = fft_line_count(pc.axis);
+    let quarter = N >> 2u;
+    let j = gid.x;
+    let line = fft_line_id(gid);
+    if (j >= quarter || line >= lines) {
+        return;
+    }
+
+    let ns = 1u << pc.stage;
+    let k = j & (ns - 1u);
+    let order = pc.stage + 2u;
+
+    let v0 = fft_line_load(line, j);
+    let v1 = mul_c32_n(fft_line_load(line, j + quarter), splat_c32_n(get_fft_twiddle(order, k)));
+    let v2 = mul_c32_n(fft_line_load(line, j + 2u * quarter), splat_c32_n(get_fft_twiddle(order, 2u * k)));
+    let v3 = mul_c32_n(fft_line_load(line, j + 3u * quarter), splat_c32_n(get_fft_twiddle(order, 3u * k)));
+
+    let a0 = add_c32_n(v0, v2);
+    let a1 = add_c32_n(v0, neg_c32_n(v2));
+    let a2 = add_c32_n(v1, v3);
+    let a3 = mul_neg_i(add_c32_n(v1, neg_c32_n(v3)));
+
+    let base = (j - k) * 4u + k;
+    fft_line_store(line, base, add_c32_n(a0, a2));
+    fft_line_store(line, base + ns, add_c32_n(a1, a3));
+    fft_line_store(line, base + 2u * ns, add_c32_n(a0, neg_c32_n(a2)));
+    fft_line_store(line, base + 3u * ns, add_c32_n(a1, neg_c32_n(a3)));
+}
+
+#ifdef FFT_SHARED_LINE_LEN
+var<workgroup> shared_line: array<c32_n, #{FFT_SHARED_LINE_LEN}>;
+
+/// Whole-line radix-2 FFT in workgroup memory: one workgroup per line along `pc.axis`, lines of
+/// up to `FFT_SHARED_LINE_LEN` samples. The bit-reverse permute happens on load and every stage
+/// stays on chip, so an axis costs a single dispatch. Flags apply as in the Stockham kernels.
+@compute @workgroup_size(256, 1, 1)
+fn fft_shared_line(
+    @builtin(workgroup_id) wid: vec3<u32>,
+    @builtin(local_invocation_index) lid: u32,
+) {
+    let N = fft_transform_len(pc.axis);
+    let order = fft_axis_order(pc.axis);
+    let line = wid.x + pc.line_base + wid.y * fft_extent()[fft_cross_axes(pc.axis).x];
+    let half_n = N >> 1u;
+
+    for (var i = lid; i < N; i += WG) {
+        shared_line[fft_reverse_lower_bits(i, order)] = fft_line_load(line, i);
+    }
+    workgroupBarrier();
+
+    for (var stage = 0u; stage < order; stage++) {
+        let m2 = 1u << stage;
+        for (var butterfly = lid; butterfly < half_n; butterfly += WG) {
+            let j = butterfly & (m2 - 1u);
+            let u = (butterfly >> stage) * (m2 << 1u) + j;
+            let v = u + m2;
+            let a = shared_line[u];
+            let b = mul_c32_n(shared_line[v], splat_c32_n(get_fft_root(stage + 1u, j)));
+            shared_line[u] = add_c32_n(a, b);
+            shared_line[v] = add_c32_n(a, neg_c32_n(b));
+        }
+        workgroupBarrier();
+    }
+
+    for (var i = lid; i < N; i += WG) {
+        fft_line_store(line, i, shared_line[i]);
+    }
+}
+#endif
+
+// `exp(-iπ m² / n)`, with `m²` reduced mod `2n` first so the angle stays small on long lines.
+fn bluestein_chirp(m: u32, n: u32) -> c32 {
+    let t = (m * m) % (2u * n);
+    let angle = -PI * f32(t) / f32(n);
+    return c32(cos(angle), sin(angle));
+}
+
+/// Bluestein step one: chirp-modulates the `N` samples of each line in `pc.src_buffer` and
+/// zero-pads them to the transform length in `pc.dst_buffer`. Load flags apply to the samples.
+@compute @workgroup_size(256, 1, 1)
+fn fft_bluestein_premultiply(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let N = fft_axis_len(pc.axis);
+    let m = gid.x;
+    let line = fft_line_id(gid);
+    if (m >= fft_transform_len(pc.axis) || line >= fft_line_count(pc.axis)) {
+        return;
+    }
+    var value = splat_c32_n(c32(0.0, 0.0));
+    if (m < N) {
+        value = mul_c32_n(fft_line_load(line, m), splat_c32_n(bluestein_chirp(m, N)));
+    }
+    write_fft_buf(pc.dst_buffer, fft_axis_pos(pc.axis, line, m), value);
+}
+
+/// Bluestein step three, between two forward transforms: multiplies by the chirp spectrum and
+/// conjugates, so the second forward transform yields the conjugated circular convolution.
+@compute @workgroup_size(256, 1, 1)
+fn fft_bluestein_multiply(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let m = gid.x;
+    let line = fft_line_id(gid);
+    if (m >= fft_transform_len(pc.axis) || line >= fft_line_count(pc.axis)) {
+        return;
+    }
+    let rows_are_bluestein = (settings.size.x & (settings.size.x - 1u)) != 0u;
+    let offset = select(0u, settings.fft_size.x, pc.axis == 1u && rows_are_bluestein);
+    let pos = fft_axis_pos(pc.axis, line, m);
+    let value = mul_c32_n(read_fft_buf(pc.src_buffer, pos), splat_c32_n(bluestein_kernel[offset + m]));
+    write_fft_buf(pc.dst_buffer, pos, conj_c32_n(value));
+}
+
+/// Bluestein step five: conjugates the convolution back, demodulates it with the chirp and crops
+/// to the first `N` bins. Store flags apply, so this is where alpha, **D** and finalize land.
+@compute @workgroup_size(256, 1, 1)
+fn fft_bluestein_postmultiply(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let N = fft_axis_len(pc.axis);
+    let k = gid.x;
+    let line = fft_line_id(gid);
+    if (k >= N || line >= fft_line_count(pc.axis)) {
         return;
     }
-    let p32 = vec2<u32>(p64.x, p64.y);
-    let v = read_fft_buf(pc.src_buffer, p32);
-    write_fft_buf(pc.dst_buffer, p32, v);
+    let z = conj_c32_n(read_fft_buf(pc.src_buffer, fft_axis_pos(pc.axis, line, k)));
+    fft_line_store(line, k, mul_c32_n(z, splat_c32_n(bluestein_chirp(k, N))));
 }
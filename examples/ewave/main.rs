@@ -56,7 +56,7 @@ fn main() {
                 ..default()
             }),
             FreeCameraPlugin,
-            FftPlugin,
+            FftPlugin::default(),
             EwavePlugin,
             EguiPlugin::default(),
         ))
@@ -10,6 +10,7 @@ use bevy::{
     prelude::*,
     render::{
         Render, RenderApp,
+        diagnostic::RecordDiagnostics,
         extract_component::{
             ComponentUniforms, ExtractComponent, ExtractComponentPlugin, UniformComponentPlugin,
         },
@@ -200,21 +201,25 @@ impl Node for BandPassNode {
             return Ok(());
         };
 
+        let diagnostics = render_context.diagnostic_recorder();
         let encoder = render_context.command_encoder();
         let mut pass = encoder.begin_compute_pass(&ComputePassDescriptor {
             label: Some("fft_demo_band_pass_pass"),
             timestamp_writes: None,
         });
+        // One span for every entity, reported under `render/fft/band_pass`.
+        let span = diagnostics.pass_span(&mut pass, "fft/band_pass");
         pass.set_pipeline(pipeline);
 
         let wg = 16u32;
         for (fft_bg, bp_bg, settings) in self.query.iter_manual(world) {
-            pass.set_bind_group(0, &fft_bg.common, &[]);
+            fft_bg.set(&mut pass, 0);
             pass.set_bind_group(1, &bp_bg.group, &[]);
             let nx = settings.size.x.div_ceil(wg);
             let ny = settings.size.y.div_ceil(wg);
             pass.dispatch_workgroups(nx, ny, 1);
         }
+        span.end(&mut pass);
 
         Ok(())
     }
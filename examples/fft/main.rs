@@ -20,7 +20,7 @@ fn main() {
     App::new()
         .add_plugins((
             DefaultPlugins,
-            FftPlugin,
+            FftPlugin::default(),
             PatternPlugin,
             BandPassPlugin,
             BandPassUiPlugin,
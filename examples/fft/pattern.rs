@@ -313,7 +313,7 @@ impl Node for ExamplePatternNode {
                 timestamp_writes: None,
             });
             pass.set_pipeline(pipeline);
-            pass.set_bind_group(0, &bind_groups.common, &[]);
+            bind_groups.set(&mut pass, 0);
             let nx = settings.size.x.div_ceil(WG);
             let ny = settings.size.y.div_ceil(WG);
             pass.dispatch_workgroups(nx, ny, 1);
@@ -65,7 +65,7 @@ fn main() {
         .add_plugins((
             DefaultPlugins,
             FreeCameraPlugin,
-            FftPlugin,
+            FftPlugin::default(),
             OceanPlugin,
             EguiPlugin::default(),
         ))
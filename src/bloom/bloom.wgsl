@@ -0,0 +1,57 @@
+#import bevy_fft::{
+    complex::c32_3,
+    bindings::settings,
+    buffer::{
+        read_buffer_b,
+        write_buffer_a,
+    },
+}
+
+struct FftBloomUniform {
+    center: f32,
+    scatter: f32,
+    downsample: u32,
+    _pad: u32,
+}
+
+@group(1) @binding(0) var view_texture: texture_2d<f32>;
+@group(1) @binding(1) var<uniform> bloom: FftBloomUniform;
+@group(1) @binding(2) var bloom_texture: texture_storage_2d<rgba16float, write>;
+
+// Box-filters `downsample`² view pixels into each signal sample of **A** and zeroes the padding,
+// which the forward transform may have used as scratch last frame.
+@compute
+@workgroup_size(16, 16, 1)
+fn fft_bloom_downsample(@builtin(global_invocation_id) gid: vec3<u32>) {
+    if (gid.x >= settings.size.x || gid.y >= settings.size.y) {
+        return;
+    }
+    let signal = settings.size - settings.padding;
+    var sum = vec3<f32>(0.0);
+    if (gid.x < signal.x && gid.y < signal.y) {
+        let view_size = textureDimensions(view_texture);
+        let origin = gid.xy * bloom.downsample;
+        for (var y = 0u; y < bloom.downsample; y++) {
+            for (var x = 0u; x < bloom.downsample; x++) {
+                let pixel = origin + vec2<u32>(x, y);
+                if (all(pixel < view_size)) {
+                    sum += textureLoad(view_texture, pixel, 0).rgb;
+                }
+            }
+        }
+        sum /= f32(bloom.downsample * bloom.downsample);
+    }
+    write_buffer_a(vec3<u32>(gid.xy, 0u), c32_3(sum, vec3<f32>(0.0)));
+}
+
+// Copies the scattered light from **B** into the bloom texture, dropping the negative ringing of
+// the truncated kernel.
+@compute
+@workgroup_size(16, 16, 1)
+fn fft_bloom_extract(@builtin(global_invocation_id) gid: vec3<u32>) {
+    if (any(gid.xy >= textureDimensions(bloom_texture))) {
+        return;
+    }
+    let scattered = read_buffer_b(vec3<u32>(gid.xy, 0u)).re;
+    textureStore(bloom_texture, gid.xy, vec4<f32>(max(scattered, vec3<f32>(0.0)), 1.0));
+}
@@ -0,0 +1,24 @@
+#import bevy_core_pipeline::fullscreen_vertex_shader::FullscreenVertexOutput
+
+struct FftBloomUniform {
+    center: f32,
+    scatter: f32,
+    downsample: u32,
+    _pad: u32,
+}
+
+@group(0) @binding(0) var source: texture_2d<f32>;
+@group(0) @binding(1) var bloom_texture: texture_2d<f32>;
+@group(0) @binding(2) var bloom_sampler: sampler;
+@group(0) @binding(3) var<uniform> bloom: FftBloomUniform;
+
+// `center * color + scatter * bloom`, with the bloom texture stretched back over the view. Each
+// bloom texel covers `downsample`² pixels, so the last row and column may overhang the view.
+@fragment
+fn fft_bloom_composite(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+    let color = textureLoad(source, vec2<u32>(in.position.xy), 0);
+    let view_size = vec2<f32>(textureDimensions(source));
+    let covered = vec2<f32>(textureDimensions(bloom_texture) * bloom.downsample);
+    let scattered = textureSample(bloom_texture, bloom_sampler, in.uv * view_size / covered).rgb;
+    return vec4<f32>(bloom.center * color.rgb + bloom.scatter * scattered, color.a);
+}
@@ -0,0 +1,474 @@
+//! Physically based bloom: the HDR view is convolved with a wide scatter kernel through the FFT
+//! pipeline instead of a chain of blurred mips.
+//!
+//! Register [`crate::fft::FftPlugin`] **before** [`FftBloomPlugin`] and add [`FftBloom`] to a
+//! camera. For each such camera the plugin keeps one [`FftBloomWorkspace`] entity: a
+//! three-channel [`FftSource`] sized to the view divided by [`FftBloom::downsample`], padded for
+//! linear convolution, with an [`FftConvolution`] whose kernel spectrum is rebuilt only when the
+//! kernel changes or the view settles on a new size. Its [`FftTrigger::Manual`] keeps the stock
+//! graph idle; the camera's post-processing node runs the transforms itself between the main pass
+//! and tonemapping.
+//!
+//! Each frame the node box-filters the HDR target into buffer **A**, runs the forward FFT,
+//! multiplies by the kernel spectrum, runs the inverse FFT, and composites
+//! `center * color + scatter * bloom` back onto the view. The scatter kernel sums to one, so
+//! [`FftBloom::center`] `+` [`FftBloom::scatter`] `= 1` keeps the total energy of the frame.
+
+mod render;
+
+use bevy::{
+    asset::{RenderAssetUsages, load_internal_asset},
+    core_pipeline::{
+        core_2d::graph::{Core2d, Node2d},
+        core_3d::graph::{Core3d, Node3d},
+    },
+    ecs::{query::QueryItem, system::lifetimeless::Read},
+    platform::collections::HashMap,
+    prelude::*,
+    render::{
+        Render, RenderApp, RenderSystems,
+        extract_component::{ExtractComponent, ExtractComponentPlugin, UniformComponentPlugin},
+        render_graph::{RenderGraphExt, ViewNodeRunner},
+        render_resource::{Extent3d, ShaderType, TextureDimension, TextureFormat},
+        view::Hdr,
+    },
+    shader::Shader,
+    utils::once,
+};
+
+pub use render::FftBloomLabel;
+
+use render::{
+    FftBloomNode, FftBloomPipelines, prepare_fft_bloom_pipelines, prepare_fft_bloom_views,
+};
+
+use crate::fft::{
+    FftConvolution, FftPlugin, FftSource, FftSystemSet, FftTrigger, prepare_fft_textures,
+};
+
+/// Shape of the light an [`FftBloom`] spreads around each pixel.
+#[derive(Clone, Debug, PartialEq, Reflect)]
+pub enum FftBloomKernel {
+    /// `(1 + (r / radius)²)^-falloff`, the long-tailed glare of a lens and eye, normalized to
+    /// unit sum. `radius` is the core width as a fraction of the view height; larger `falloff`
+    /// shortens the tail.
+    PowerLaw { radius: f32, falloff: f32 },
+    /// An `Rgba32Float` image laid out like an [`FftConvolution`] kernel, centred on its middle
+    /// texel and no larger than the FFT grid. Used as is, so it should sum to one per channel
+    /// to conserve energy.
+    Image(Handle<Image>),
+}
+
+impl Default for FftBloomKernel {
+    fn default() -> Self {
+        Self::PowerLaw {
+            radius: 0.005,
+            falloff: 1.5,
+        }
+    }
+}
+
+/// FFT bloom for an HDR camera. See the [module docs](self).
+#[derive(Component, Clone, Debug, Reflect)]
+#[require(Hdr)]
+pub struct FftBloom {
+    /// Weight of the light each pixel keeps.
+    pub center: f32,
+    /// Weight of the light spread by [`Self::kernel`]. `center + scatter = 1` conserves energy;
+    /// larger sums brighten the frame.
+    pub scatter: f32,
+    /// View pixels per FFT sample along each axis. `1` transforms at full resolution; every
+    /// doubling quarters the cost at the price of a softer bloom core.
+    pub downsample: u32,
+    pub kernel: FftBloomKernel,
+}
+
+impl Default for FftBloom {
+    fn default() -> Self {
+        Self::energy_conserving(0.04)
+    }
+}
+
+impl FftBloom {
+    /// Scatters `scatter` of the light and keeps the rest in place.
+    pub fn energy_conserving(scatter: f32) -> Self {
+        Self {
+            center: 1.0 - scatter,
+            scatter,
+            downsample: 4,
+            kernel: FftBloomKernel::default(),
+        }
+    }
+}
+
+/// Per-view copy of the [`FftBloom`] weights read by `bloom.wgsl` and `composite.wgsl`.
+#[derive(Component, Clone, Copy, ShaderType)]
+pub struct FftBloomUniform {
+    pub center: f32,
+    pub scatter: f32,
+    pub downsample: u32,
+    pub _pad: u32,
+}
+
+impl ExtractComponent for FftBloom {
+    type QueryData = (Read<FftBloom>, Read<Camera>);
+    type QueryFilter = ();
+    type Out = FftBloomUniform;
+
+    fn extract_component((bloom, camera): QueryItem<'_, '_, Self::QueryData>) -> Option<Self::Out> {
+        camera.is_active.then_some(FftBloomUniform {
+            center: bloom.center,
+            scatter: bloom.scatter,
+            downsample: bloom.downsample.max(1),
+            _pad: 0,
+        })
+    }
+}
+
+/// FFT workspace of an [`FftBloom`] camera, spawned and despawned by [`FftBloomPlugin`]. Its
+/// [`FftTextures`](crate::fft::FftTextures) are allocated as usual, but only `buffer_*` are
+/// written.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct FftBloomWorkspace {
+    /// Main-world camera entity.
+    pub camera: Entity,
+}
+
+impl ExtractComponent for FftBloomWorkspace {
+    type QueryData = Read<FftBloomWorkspace>;
+    type QueryFilter = ();
+    type Out = Self;
+
+    fn extract_component(item: QueryItem<'_, '_, Self::QueryData>) -> Option<Self::Out> {
+        Some(*item)
+    }
+}
+
+/// Kernel image a [`FftBloomWorkspace`] generates for [`FftBloomKernel::PowerLaw`], the grid
+/// and kernel it was last built for, and the grid a resize is waiting to settle on.
+#[derive(Component)]
+struct FftBloomKernelImage {
+    image: Handle<Image>,
+    built: Option<(UVec2, FftBloomKernel)>,
+    resize: Option<FftBloomResize>,
+}
+
+/// Seconds a view must keep its size before [`FftBloomWorkspace`] follows it. Every resize
+/// rebuilds and retransforms the kernel, so a window being dragged keeps the previous workspace,
+/// whose bloom still lines up with the view, until the drag stops.
+const FFT_BLOOM_RESIZE_DELAY: f64 = 0.25;
+
+/// Grid a [`FftBloomWorkspace`] will resize to, and when the view first asked for it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct FftBloomResize {
+    size: UVec2,
+    since: f64,
+}
+
+impl FftBloomResize {
+    /// Tracks a request for `size` at `now`; `true` once it has held for
+    /// [`FFT_BLOOM_RESIZE_DELAY`].
+    fn settled(resize: &mut Option<Self>, size: UVec2, now: f64) -> bool {
+        let since = match *resize {
+            Some(pending) if pending.size == size => pending.since,
+            _ => {
+                *resize = Some(Self { size, since: now });
+                now
+            }
+        };
+        now - since >= FFT_BLOOM_RESIZE_DELAY
+    }
+}
+
+/// Workspace for a `view`-sized target: `view / downsample` samples, padded so a kernel as large
+/// as the grid convolves linearly. `None` when that grid is too large to transform.
+fn fft_bloom_source(view: UVec2, downsample: u32) -> Option<FftSource> {
+    let downsample = downsample.max(1);
+    let signal = UVec2::new(view.x.div_ceil(downsample), view.y.div_ceil(downsample));
+    let source = FftSource::try_forward_then_inverse(signal).ok()?;
+    FftSource {
+        channels: 3,
+        ..source
+    }
+    .try_with_convolution_padding(signal)
+    .ok()
+}
+
+/// Samples [`FftBloomKernel::PowerLaw`] over a `size` grid centred on `size / 2`, normalized to
+/// unit sum, as `Rgba32Float` texels with opaque alpha.
+fn power_law_kernel(size: UVec2, radius: f32, falloff: f32) -> Vec<f32> {
+    let radius = (radius * size.y as f32).max(f32::EPSILON);
+    let centre = (size / 2).as_vec2();
+    let weights: Vec<f32> = (0..size.y)
+        .flat_map(|y| (0..size.x).map(move |x| UVec2::new(x, y)))
+        .map(|texel| {
+            let r = texel.as_vec2().distance(centre) / radius;
+            (1.0 + r * r).powf(-falloff)
+        })
+        .collect();
+    let total: f32 = weights.iter().sum();
+    weights
+        .iter()
+        .flat_map(|w| {
+            let w = w / total;
+            [w, w, w, 1.0]
+        })
+        .collect()
+}
+
+fn kernel_image(size: UVec2, texels: &[f32]) -> Image {
+    Image::new(
+        Extent3d {
+            width: size.x,
+            height: size.y,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        bytemuck::cast_slice(texels).to_vec(),
+        TextureFormat::Rgba32Float,
+        RenderAssetUsages::MAIN_WORLD,
+    )
+}
+
+type FftBloomWorkspaceQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static FftBloomWorkspace,
+        &'static mut FftSource,
+        &'static mut FftConvolution,
+        &'static mut FftBloomKernelImage,
+    ),
+>;
+
+/// Spawns, resizes and despawns the [`FftBloomWorkspace`] of every [`FftBloom`] camera, and
+/// rebuilds its kernel when the kernel or the grid changes. Resizes wait for the view to settle.
+fn sync_fft_bloom_workspaces(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    time: Res<Time<Real>>,
+    cameras: Query<(Entity, &FftBloom, &Camera)>,
+    mut workspaces: FftBloomWorkspaceQuery,
+) {
+    let mut existing = HashMap::new();
+    for (entity, workspace, ..) in &workspaces {
+        if cameras.contains(workspace.camera) {
+            existing.insert(workspace.camera, entity);
+        } else {
+            commands.entity(entity).despawn();
+        }
+    }
+    for (camera_entity, bloom, camera) in &cameras {
+        let Some(view) = camera.physical_viewport_size() else {
+            continue;
+        };
+        let Some(source) = fft_bloom_source(view, bloom.downsample) else {
+            once!(warn!(
+                "FftBloom: a {view} view is too large to transform; raise `downsample`"
+            ));
+            continue;
+        };
+        let signal = source.size;
+        let built = Some((signal, bloom.kernel.clone()));
+        let Some(workspace) = existing.get(&camera_entity) else {
+            let image = images.reserve_handle();
+            let kernel = fft_bloom_kernel(&mut images, &image, signal, &bloom.kernel);
+            commands.spawn((
+                FftBloomWorkspace {
+                    camera: camera_entity,
+                },
+                source,
+                FftTrigger::Manual,
+                FftConvolution::linear(kernel),
+                FftBloomKernelImage {
+                    image,
+                    built,
+                    resize: None,
+                },
+            ));
+            continue;
+        };
+        let Ok((_, _, mut current, mut convolution, mut kernel)) = workspaces.get_mut(*workspace)
+        else {
+            continue;
+        };
+        if current.size != source.size || current.padding != source.padding {
+            if !FftBloomResize::settled(&mut kernel.resize, signal, time.elapsed_secs_f64()) {
+                continue;
+            }
+            *current = source;
+        }
+        if kernel.resize.is_some() {
+            kernel.resize = None;
+        }
+        if kernel.built == built {
+            continue;
+        }
+        let handle = fft_bloom_kernel(&mut images, &kernel.image, signal, &bloom.kernel);
+        if convolution.kernel != handle {
+            convolution.kernel = handle;
+        }
+        kernel.built = built;
+    }
+}
+
+/// Kernel image for `kernel` over a `signal` grid, writing [`FftBloomKernel::PowerLaw`] samples
+/// into `generated`.
+fn fft_bloom_kernel(
+    images: &mut Assets<Image>,
+    generated: &Handle<Image>,
+    signal: UVec2,
+    kernel: &FftBloomKernel,
+) -> Handle<Image> {
+    match kernel {
+        FftBloomKernel::PowerLaw { radius, falloff } => {
+            let texels = power_law_kernel(signal, *radius, *falloff);
+            let _ = images.insert(generated, kernel_image(signal, &texels));
+            generated.clone()
+        }
+        FftBloomKernel::Image(image) => image.clone(),
+    }
+}
+
+/// Stable handles for WGSL registered by [`FftBloomPlugin`].
+pub mod shaders {
+    use bevy::asset::{Handle, uuid_handle};
+    use bevy::shader::Shader;
+
+    pub const BLOOM: Handle<Shader> = uuid_handle!("0d6f2a8e-5c41-4b7a-9e3d-81f4c2a6b790");
+    pub const COMPOSITE: Handle<Shader> = uuid_handle!("7a3c9e15-2b84-4d06-a1f7-5e9b0c3d8f26");
+}
+
+/// Adds [`FftBloom`] to the 2D and 3D camera graphs, between the main pass and tonemapping.
+pub struct FftBloomPlugin;
+
+impl Plugin for FftBloomPlugin {
+    fn build(&self, app: &mut App) {
+        load_internal_asset!(app, shaders::BLOOM, "bloom.wgsl", Shader::from_wgsl);
+        load_internal_asset!(app, shaders::COMPOSITE, "composite.wgsl", Shader::from_wgsl);
+
+        app.register_type::<FftBloom>()
+            .register_type::<FftBloomKernel>()
+            .add_plugins((
+                ExtractComponentPlugin::<FftBloom>::default(),
+                UniformComponentPlugin::<FftBloomUniform>::default(),
+                ExtractComponentPlugin::<FftBloomWorkspace>::default(),
+            ))
+            .add_systems(
+                Update,
+                sync_fft_bloom_workspaces
+                    .before(prepare_fft_textures)
+                    .in_set(FftSystemSet::PrepareTextures),
+            );
+    }
+
+    fn finish(&self, app: &mut App) {
+        assert!(
+            app.is_plugin_added::<FftPlugin>(),
+            "FftBloomPlugin requires FftPlugin to be registered first (e.g. add_plugins((FftPlugin::default(), FftBloomPlugin)))."
+        );
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+
+        render_app
+            .init_resource::<FftBloomPipelines>()
+            .add_systems(
+                Render,
+                (
+                    prepare_fft_bloom_pipelines.in_set(RenderSystems::PrepareResources),
+                    prepare_fft_bloom_views.in_set(RenderSystems::PrepareResources),
+                ),
+            )
+            .add_render_graph_node::<ViewNodeRunner<FftBloomNode>>(Core3d, FftBloomLabel)
+            .add_render_graph_edges(
+                Core3d,
+                (
+                    Node3d::StartMainPassPostProcessing,
+                    FftBloomLabel,
+                    Node3d::Tonemapping,
+                ),
+            )
+            .add_render_graph_node::<ViewNodeRunner<FftBloomNode>>(Core2d, FftBloomLabel)
+            .add_render_graph_edges(
+                Core2d,
+                (
+                    Node2d::StartMainPassPostProcessing,
+                    FftBloomLabel,
+                    Node2d::Tonemapping,
+                ),
+            );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{ecs::system::RunSystemOnce, prelude::*};
+
+    use super::{FFT_BLOOM_RESIZE_DELAY, FftBloomResize, fft_bloom_source, power_law_kernel};
+    use crate::fft::{
+        FftBackend, FftSettings, FftSource, FftTexelSupport, FftTextures, prepare_fft_textures,
+    };
+
+    #[test]
+    fn workspace_fits_a_grid_sized_kernel_linearly() {
+        let source = fft_bloom_source(UVec2::new(1920, 1080), 4).unwrap();
+        assert_eq!((source.size, source.channels), (UVec2::new(480, 270), 3));
+        assert_eq!(source.size + source.padding, UVec2::new(1024, 1024));
+        let settings = FftSettings::from_fft_source(&source);
+        assert!(settings.padding.cmpge(source.size / 2).all());
+        assert!(fft_bloom_source(UVec2::new(1 << 16, 8), 1).is_none());
+    }
+
+    #[test]
+    fn power_law_kernel_sums_to_one_and_peaks_at_the_centre() {
+        let size = UVec2::new(9, 6);
+        let texels = power_law_kernel(size, 0.2, 1.5);
+        for lane in 0..3 {
+            let total: f32 = texels.chunks_exact(4).map(|t| t[lane]).sum();
+            assert!((total - 1.0).abs() < 1e-5);
+        }
+        let centre = ((size.y / 2) * size.x + size.x / 2) as usize * 4;
+        let peak = texels.chunks_exact(4).map(|t| t[0]).fold(0.0, f32::max);
+        assert_eq!(texels[centre], peak);
+        assert!(texels.chunks_exact(4).all(|t| t[3] == 1.0));
+    }
+
+    #[test]
+    fn resizes_wait_for_the_view_to_settle() {
+        let mut resize = None;
+        let (small, large) = (UVec2::new(480, 270), UVec2::new(640, 360));
+        assert!(!FftBloomResize::settled(&mut resize, small, 1.0));
+        assert!(!FftBloomResize::settled(&mut resize, large, 1.1));
+        let later = 1.1 + FFT_BLOOM_RESIZE_DELAY;
+        assert!(!FftBloomResize::settled(&mut resize, small, later));
+        assert!(FftBloomResize::settled(
+            &mut resize,
+            small,
+            later + FFT_BLOOM_RESIZE_DELAY
+        ));
+    }
+
+    #[test]
+    fn resized_workspaces_get_new_textures() {
+        let mut world = World::new();
+        world.init_resource::<Assets<Image>>();
+        world.insert_resource(FftTexelSupport::default());
+        world.insert_resource(FftBackend::default());
+        let small = fft_bloom_source(UVec2::new(1920, 1080), 16).unwrap();
+        let large = fft_bloom_source(UVec2::new(3840, 2160), 16).unwrap();
+        assert_ne!(small.size + small.padding, large.size + large.padding);
+        let workspace = world.spawn(small).id();
+        world.run_system_once(prepare_fft_textures).unwrap();
+        *world.get_mut::<FftSource>(workspace).unwrap() = large.clone();
+        world.run_system_once(prepare_fft_textures).unwrap();
+        let textures = world.get::<FftTextures>(workspace).unwrap();
+        let images = world.resource::<Assets<Image>>();
+        let size = |handle: &Handle<Image>| images.get(handle).unwrap().size();
+        assert_eq!(size(&textures.buffer_a_re), large.size + large.padding);
+        assert_eq!(size(&textures.buffer_d_im), large.size + large.padding);
+        assert_eq!(size(&textures.power_spectrum), large.size + large.padding);
+        assert_eq!(size(&textures.spatial_output), large.size);
+    }
+}
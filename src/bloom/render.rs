@@ -0,0 +1,309 @@
+//! Render-world side of [`FftBloom`](super::FftBloom): per-view bloom textures, the downsample,
+//! extract and composite pipelines, and the post-processing node.
+
+use bevy::{
+    core_pipeline::FullscreenShader,
+    ecs::query::QueryItem,
+    platform::collections::HashMap,
+    prelude::*,
+    render::{
+        extract_component::{ComponentUniforms, DynamicUniformIndex},
+        render_graph::{NodeRunError, RenderGraphContext, RenderLabel, ViewNode},
+        render_resource::{
+            binding_types::{sampler, texture_2d, texture_storage_2d, uniform_buffer},
+            *,
+        },
+        renderer::{RenderContext, RenderDevice},
+        sync_world::MainEntity,
+        texture::{CachedTexture, TextureCache},
+        view::ViewTarget,
+    },
+};
+
+use super::{FftBloomUniform, FftBloomWorkspace, shaders};
+use crate::fft::{
+    FftSettings, record_fft_convolution,
+    resources::{FftBindGroups, FftPipelines, FftWorkspaceFormat},
+    run_forward_fft, run_inverse_fft,
+};
+
+/// Runs [`FftBloom`](super::FftBloom) in the `Core2d` and `Core3d` graphs, after
+/// `StartMainPassPostProcessing` and before `Tonemapping`.
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Hash, RenderLabel)]
+pub struct FftBloomLabel;
+
+/// Scattered light at FFT grid resolution, sampled by the composite pass.
+const BLOOM_TEXTURE_FORMAT: TextureFormat = TextureFormat::Rgba16Float;
+
+#[derive(Resource)]
+pub(super) struct FftBloomPipelines {
+    /// View target, [`FftBloomUniform`] and bloom texture for the compute kernels.
+    io_layout: BindGroupLayoutDescriptor,
+    composite_layout: BindGroupLayoutDescriptor,
+    sampler: Sampler,
+    composite: CachedRenderPipelineId,
+    /// `fft_bloom_downsample` and `fft_bloom_extract` per workspace format.
+    kernels: HashMap<FftWorkspaceFormat, [CachedComputePipelineId; 2]>,
+}
+
+impl FromWorld for FftBloomPipelines {
+    fn from_world(world: &mut World) -> Self {
+        let io_entries = BindGroupLayoutEntries::sequential(
+            ShaderStages::COMPUTE,
+            (
+                texture_2d(TextureSampleType::Float { filterable: false }),
+                uniform_buffer::<FftBloomUniform>(true),
+                texture_storage_2d(BLOOM_TEXTURE_FORMAT, StorageTextureAccess::WriteOnly),
+            ),
+        );
+        let io_layout = BindGroupLayoutDescriptor::new("fft_bloom_io_layout", &io_entries);
+        let composite_entries = BindGroupLayoutEntries::sequential(
+            ShaderStages::FRAGMENT,
+            (
+                texture_2d(TextureSampleType::Float { filterable: false }),
+                texture_2d(TextureSampleType::Float { filterable: true }),
+                sampler(SamplerBindingType::Filtering),
+                uniform_buffer::<FftBloomUniform>(true),
+            ),
+        );
+        let composite_layout =
+            BindGroupLayoutDescriptor::new("fft_bloom_composite_layout", &composite_entries);
+
+        let sampler = world
+            .resource::<RenderDevice>()
+            .create_sampler(&SamplerDescriptor {
+                label: Some("fft_bloom_sampler"),
+                mag_filter: FilterMode::Linear,
+                min_filter: FilterMode::Linear,
+                ..default()
+            });
+        let composite =
+            world
+                .resource::<PipelineCache>()
+                .queue_render_pipeline(RenderPipelineDescriptor {
+                    label: Some("fft_bloom_composite_pipeline".into()),
+                    layout: vec![composite_layout.clone()],
+                    vertex: world.resource::<FullscreenShader>().to_vertex_state(),
+                    fragment: Some(FragmentState {
+                        shader: shaders::COMPOSITE.clone(),
+                        entry_point: Some("fft_bloom_composite".into()),
+                        targets: vec![Some(ColorTargetState {
+                            format: ViewTarget::TEXTURE_FORMAT_HDR,
+                            blend: None,
+                            write_mask: ColorWrites::ALL,
+                        })],
+                        ..default()
+                    }),
+                    ..default()
+                });
+
+        Self {
+            io_layout,
+            composite_layout,
+            sampler,
+            composite,
+            kernels: HashMap::default(),
+        }
+    }
+}
+
+/// Queues the bloom kernels for workspace formats no earlier camera used.
+pub(super) fn prepare_fft_bloom_pipelines(
+    mut pipelines: ResMut<FftBloomPipelines>,
+    fft_pipelines: Res<FftPipelines>,
+    pipeline_cache: Res<PipelineCache>,
+    query: Query<&FftSettings, With<FftBloomWorkspace>>,
+) {
+    let pipelines = &mut *pipelines;
+    for settings in &query {
+        let format = fft_pipelines.format(settings);
+        pipelines.kernels.entry(format).or_insert_with(|| {
+            let kernel = |entry_point: &'static str| {
+                pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
+                    label: Some(format!("{entry_point}_pipeline").into()),
+                    layout: vec![format.layout(), pipelines.io_layout.clone()],
+                    push_constant_ranges: vec![],
+                    shader: shaders::BLOOM.clone(),
+                    shader_defs: format.shader_defs(),
+                    entry_point: Some(entry_point.into()),
+                    zero_initialize_workgroup_memory: false,
+                })
+            };
+            [kernel("fft_bloom_downsample"), kernel("fft_bloom_extract")]
+        });
+    }
+}
+
+/// Render-world [`FftBloomWorkspace`] of a view and the texture its scattered light lands in.
+#[derive(Component)]
+pub(super) struct FftBloomView {
+    workspace: Entity,
+    texture: CachedTexture,
+}
+
+/// Pairs each bloom view with its workspace and allocates the bloom texture at grid size.
+pub(super) fn prepare_fft_bloom_views(
+    mut commands: Commands,
+    device: Res<RenderDevice>,
+    mut texture_cache: ResMut<TextureCache>,
+    workspaces: Query<(Entity, &FftBloomWorkspace, &FftSettings)>,
+    views: Query<(Entity, &MainEntity), With<FftBloomUniform>>,
+) {
+    let workspaces: HashMap<Entity, (Entity, UVec2)> = workspaces
+        .iter()
+        .map(|(entity, workspace, settings)| (workspace.camera, (entity, settings.signal_size())))
+        .collect();
+    for (view, main_entity) in &views {
+        let Some(&(workspace, signal)) = workspaces.get(&main_entity.id()) else {
+            commands.entity(view).remove::<FftBloomView>();
+            continue;
+        };
+        let texture = texture_cache.get(
+            &device,
+            TextureDescriptor {
+                label: Some("fft_bloom_texture"),
+                size: Extent3d {
+                    width: signal.x,
+                    height: signal.y,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: TextureDimension::D2,
+                format: BLOOM_TEXTURE_FORMAT,
+                usage: TextureUsages::STORAGE_BINDING | TextureUsages::TEXTURE_BINDING,
+                view_formats: &[],
+            },
+        );
+        commands
+            .entity(view)
+            .insert(FftBloomView { workspace, texture });
+    }
+}
+
+#[derive(Default)]
+pub(super) struct FftBloomNode;
+
+impl ViewNode for FftBloomNode {
+    type ViewQuery = (
+        &'static ViewTarget,
+        &'static FftBloomView,
+        &'static DynamicUniformIndex<FftBloomUniform>,
+    );
+
+    fn run<'w>(
+        &self,
+        _graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext<'w>,
+        (target, view, uniform_index): QueryItem<'w, '_, Self::ViewQuery>,
+        world: &'w World,
+    ) -> Result<(), NodeRunError> {
+        let pipelines = world.resource::<FftBloomPipelines>();
+        let fft_pipelines = world.resource::<FftPipelines>();
+        let pipeline_cache = world.resource::<PipelineCache>();
+        let (Some(bind_groups), Some(settings), Some(uniform)) = (
+            world.get::<FftBindGroups>(view.workspace),
+            world.get::<FftSettings>(view.workspace),
+            world
+                .resource::<ComponentUniforms<FftBloomUniform>>()
+                .binding(),
+        ) else {
+            return Ok(());
+        };
+        let kernels = pipelines
+            .kernels
+            .get(&fft_pipelines.format(settings))
+            .map(|ids| ids.map(|id| pipeline_cache.get_compute_pipeline(id)));
+        let (Some([Some(downsample), Some(extract)]), Some(composite)) = (
+            kernels,
+            pipeline_cache.get_render_pipeline(pipelines.composite),
+        ) else {
+            return Ok(());
+        };
+
+        let device = render_context.render_device().clone();
+        let offsets = [uniform_index.index()];
+        let io = device.create_bind_group(
+            "fft_bloom_io_bind_group",
+            &pipeline_cache.get_bind_group_layout(&pipelines.io_layout),
+            &BindGroupEntries::sequential((
+                target.main_texture_view(),
+                uniform.clone(),
+                &view.texture.default_view,
+            )),
+        );
+
+        let wg = 16u32;
+        {
+            let mut pass =
+                render_context
+                    .command_encoder()
+                    .begin_compute_pass(&ComputePassDescriptor {
+                        label: Some("fft_bloom_pass"),
+                        timestamp_writes: None,
+                    });
+            pass.set_pipeline(downsample);
+            bind_groups.set(&mut pass, 0);
+            pass.set_bind_group(1, &io, &offsets);
+            pass.dispatch_workgroups(
+                settings.size.x.div_ceil(wg),
+                settings.size.y.div_ceil(wg),
+                1,
+            );
+            if !run_forward_fft(
+                fft_pipelines,
+                pipeline_cache,
+                &mut pass,
+                bind_groups,
+                settings,
+            ) || !record_fft_convolution(world, &mut pass, view.workspace, bind_groups, settings)
+                || !run_inverse_fft(
+                    fft_pipelines,
+                    pipeline_cache,
+                    &mut pass,
+                    bind_groups,
+                    settings,
+                )
+            {
+                // Kernels or the kernel spectrum are not ready; leave the view as rendered.
+                return Ok(());
+            }
+            let signal = settings.signal_size();
+            pass.set_pipeline(extract);
+            bind_groups.set(&mut pass, 0);
+            pass.set_bind_group(1, &io, &offsets);
+            pass.dispatch_workgroups(signal.x.div_ceil(wg), signal.y.div_ceil(wg), 1);
+        }
+
+        let post_process = target.post_process_write();
+        let composite_group = device.create_bind_group(
+            "fft_bloom_composite_bind_group",
+            &pipeline_cache.get_bind_group_layout(&pipelines.composite_layout),
+            &BindGroupEntries::sequential((
+                post_process.source,
+                &view.texture.default_view,
+                &pipelines.sampler,
+                uniform,
+            )),
+        );
+        let mut pass = render_context
+            .command_encoder()
+            .begin_render_pass(&RenderPassDescriptor {
+                label: Some("fft_bloom_composite_pass"),
+                color_attachments: &[Some(RenderPassColorAttachment {
+                    view: post_process.destination,
+                    depth_slice: None,
+                    resolve_target: None,
+                    ops: Operations::default(),
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+        pass.set_pipeline(composite);
+        pass.set_bind_group(0, &composite_group, &offsets);
+        pass.draw(0..3, 0..1);
+
+        Ok(())
+    }
+}
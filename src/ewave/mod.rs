@@ -38,7 +38,7 @@ use bevy::{
     shader::{Shader, ShaderRef},
 };
 
-use crate::fft::{FftPlugin, FftSkipStockPipeline};
+use crate::fft::{FftBackend, FftPlugin, FftSkipStockPipeline};
 
 /// Marks the main-world entity that carries the eWave grid ([`FftSource`](crate::fft::FftSource),
 /// [`FftTextures`](crate::fft::resources::FftTextures), and [`EwaveGridImages`]).
@@ -313,7 +313,11 @@ impl Plugin for EwavePlugin {
     fn finish(&self, app: &mut App) {
         assert!(
             app.is_plugin_added::<FftPlugin>(),
-            "EwavePlugin requires FftPlugin to be registered first (e.g. add_plugins((FftPlugin, EwavePlugin)))."
+            "EwavePlugin requires FftPlugin to be registered first (e.g. add_plugins((FftPlugin::default(), EwavePlugin)))."
+        );
+        assert!(
+            app.world().get_resource::<FftBackend>() == Some(&FftBackend::Textures),
+            "EwavePlugin writes the FFT workspace textures and requires FftBackend::Textures."
         );
         if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
             plug_ewave_render_app(render_app);
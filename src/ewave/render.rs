@@ -28,8 +28,12 @@ use bevy::{
 };
 
 use crate::ewave::{EwaveController, EwaveGridImages, EwaveSimRoot};
-use crate::fft::resources::{FftBindGroupLayouts, FftPipelines, FftTextures};
-use crate::fft::{FftNode, FftSettings, run_forward_fft, run_inverse_fft};
+use crate::fft::resources::{
+    FftBindGroupLayouts, FftBluesteinBuffers, FftPipelines, FftRootsBuffer, FftTextures,
+};
+use crate::fft::{
+    FftNode, FftSettings, resources::FftBindGroups, run_forward_fft, run_inverse_fft,
+};
 
 #[repr(C)]
 #[derive(Copy, Clone, Default, ShaderType)]
@@ -126,7 +130,8 @@ pub struct EwaveTimestamp(pub AtomicU32);
 pub struct EwaveGpuResources {
     pub fft_settings_buffer: Option<Buffer>,
     pub ewave_sim_buffer: Option<Buffer>,
-    pub fft_bind_group: Option<BindGroup>,
+    /// FFT bindings over [`Self::fft_settings_buffer`], which holds a single [`FftSettings`].
+    pub fft_bind_group: Option<FftBindGroups>,
     pub ewave_bind_group: Option<BindGroup>,
     pub last_n: u32,
     pub last_apply_serial: u32,
@@ -169,13 +174,13 @@ impl EwaveGpuResources {
         self.last_n = n;
         self.fft_settings_buffer = Some(device.create_buffer(&BufferDescriptor {
             label: Some("ewave_fft_settings"),
-            size: FftSettings::min_size().get() as u64,
+            size: FftSettings::min_size().get(),
             usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
             mapped_at_creation: false,
         }));
         self.ewave_sim_buffer = Some(device.create_buffer(&BufferDescriptor {
             label: Some("ewave_sim_uniform"),
-            size: <EwaveSimUniform as ShaderType>::min_size().get() as u64,
+            size: <EwaveSimUniform as ShaderType>::min_size().get(),
             usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
             mapped_at_creation: false,
         }));
@@ -193,9 +198,17 @@ pub fn prepare_ewave_gpu(
     pipeline_cache: Res<PipelineCache>,
     layouts: Res<FftBindGroupLayouts>,
     globals: Res<GlobalsBuffer>,
-    roots: Res<crate::fft::resources::FftRootsBuffer>,
     gpu_imgs: Res<RenderAssets<GpuImage>>,
-    grid_query: Query<(&FftTextures, &EwaveGridImages, &FftSettings), With<EwaveSimRoot>>,
+    grid_query: Query<
+        (
+            &FftTextures,
+            &EwaveGridImages,
+            &FftSettings,
+            &FftBluesteinBuffers,
+            &FftRootsBuffer,
+        ),
+        With<EwaveSimRoot>,
+    >,
 ) {
     let n = controller.n;
     let n_changed = gpu.last_n != n;
@@ -215,7 +228,7 @@ pub fn prepare_ewave_gpu(
         return;
     };
 
-    let Ok((textures, t, fs_comp)) = grid_query.single() else {
+    let Ok((textures, t, fs_comp, bluestein, roots)) = grid_query.single() else {
         return;
     };
 
@@ -302,6 +315,8 @@ pub fn prepare_ewave_gpu(
             &c_im.texture_view,
             &d_re.texture_view,
             &d_im.texture_view,
+            bluestein.kernel_binding(),
+            bluestein.workspace.as_entire_binding(),
         )),
     );
     let ed = render_device.create_bind_group(
@@ -316,7 +331,10 @@ pub fn prepare_ewave_gpu(
             &pi.texture_view,
         )),
     );
-    gpu.fft_bind_group = Some(fft_bg);
+    gpu.fft_bind_group = Some(FftBindGroups {
+        common: fft_bg,
+        settings_offset: 0,
+    });
     gpu.ewave_bind_group = Some(ed);
 }
 
@@ -330,13 +348,13 @@ fn dispatch_ewave(
     pass: &mut bevy::render::render_resource::ComputePass,
     cache: &PipelineCache,
     id: CachedComputePipelineId,
-    fft: &BindGroup,
+    fft: &FftBindGroups,
     ew: &BindGroup,
     wg: (u32, u32),
 ) {
     if let Some(p) = cache.get_compute_pipeline(id) {
         pass.set_pipeline(p);
-        pass.set_bind_group(0, fft, &[]);
+        fft.set(pass, 0);
         pass.set_bind_group(1, ew, &[]);
         pass.dispatch_workgroups(wg.0, wg.1, 1);
     }
@@ -397,28 +415,28 @@ impl Node for EwaveSimNode {
                 ..default()
             });
             if apply_clear {
-                dispatch_ewave(&mut pass, &cache, pl_ew.clear_spatial, fft, ew, wgn);
+                dispatch_ewave(&mut pass, cache, pl_ew.clear_spatial, fft, ew, wgn);
                 gpu_res
                     .last_cleared_apply_serial
                     .store(controller.sim_apply_serial, Ordering::Relaxed);
             }
 
             if !controller.paused {
-                dispatch_ewave(&mut pass, &cache, pl_ew.pack_h, fft, ew, wgn);
-                run_forward_fft(pl_fft, &cache, &mut pass, fft, settings);
-                dispatch_ewave(&mut pass, &cache, pl_ew.copy_c_h, fft, ew, wgn);
-                dispatch_ewave(&mut pass, &cache, pl_ew.pack_phi, fft, ew, wgn);
-                run_forward_fft(pl_fft, &cache, &mut pass, fft, settings);
-                dispatch_ewave(&mut pass, &cache, pl_ew.copy_c_p, fft, ew, wgn);
-                dispatch_ewave(&mut pass, &cache, pl_ew.ewave_k, fft, ew, wgn);
-                dispatch_ewave(&mut pass, &cache, pl_ew.copy_h_c, fft, ew, wgn);
-                run_inverse_fft(pl_fft, &cache, &mut pass, fft, settings);
-                dispatch_ewave(&mut pass, &cache, pl_ew.extract_b_h, fft, ew, wgn);
-                dispatch_ewave(&mut pass, &cache, pl_ew.copy_p_c, fft, ew, wgn);
-                run_inverse_fft(pl_fft, &cache, &mut pass, fft, settings);
-                dispatch_ewave(&mut pass, &cache, pl_ew.extract_b_phi, fft, ew, wgn);
+                dispatch_ewave(&mut pass, cache, pl_ew.pack_h, fft, ew, wgn);
+                run_forward_fft(pl_fft, cache, &mut pass, fft, settings);
+                dispatch_ewave(&mut pass, cache, pl_ew.copy_c_h, fft, ew, wgn);
+                dispatch_ewave(&mut pass, cache, pl_ew.pack_phi, fft, ew, wgn);
+                run_forward_fft(pl_fft, cache, &mut pass, fft, settings);
+                dispatch_ewave(&mut pass, cache, pl_ew.copy_c_p, fft, ew, wgn);
+                dispatch_ewave(&mut pass, cache, pl_ew.ewave_k, fft, ew, wgn);
+                dispatch_ewave(&mut pass, cache, pl_ew.copy_h_c, fft, ew, wgn);
+                run_inverse_fft(pl_fft, cache, &mut pass, fft, settings);
+                dispatch_ewave(&mut pass, cache, pl_ew.extract_b_h, fft, ew, wgn);
+                dispatch_ewave(&mut pass, cache, pl_ew.copy_p_c, fft, ew, wgn);
+                run_inverse_fft(pl_fft, cache, &mut pass, fft, settings);
+                dispatch_ewave(&mut pass, cache, pl_ew.extract_b_phi, fft, ew, wgn);
             }
-            dispatch_ewave(&mut pass, &cache, pl_ew.brush, fft, ew, wgn);
+            dispatch_ewave(&mut pass, cache, pl_ew.brush, fft, ew, wgn);
         }
         if !controller.paused {
             ts.0.fetch_add(1, Ordering::Relaxed);
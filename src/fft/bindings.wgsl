@@ -5,28 +5,183 @@
 // Keep this struct byte-for-byte identical to the Rust `FftSettings` uniform.
 struct FftSettings {
     size: vec2<u32>,
-    orders: u32,
-    // Keeps `orders` on a 16-byte boundary before the next fields.
+    // Row (x) and column (y) stage counts, log2 of `fft_size`.
+    orders: vec2<u32>,
+    // Power-of-two transform length per axis; larger than `size` on Bluestein axes.
+    fft_size: vec2<u32>,
     padding: vec2<u32>,
     // Same numeric encoding as `FftSchedule` on the Rust side.
     schedule: u32,
     // Same numeric encoding as `FftPatternTarget` on the Rust side.
     pattern_target: u32,
-    window_type: u32, // 0 none, 1 Tukey, 2 Blackman, 3 Kaiser
+    // Same numeric encoding as `FftWindow` on the Rust side.
+    window_type: u32,
     window_strength: f32,
-    radial_falloff: f32,
+    window_param: f32,
     normalization: f32,
+    // Same numeric encoding as `FftRadix` on the Rust side.
+    radix: u32,
+    // Same numeric encoding as `FftDimension` on the Rust side.
+    dimension: u32,
+    // Volume depth (`z` edge) for `FftDimension::ThreeD`, otherwise the layer count of
+    // `FftSource::layers`.
+    depth: u32,
+    // Same numeric encoding as `FftNormalization` on the Rust side.
+    fft_normalization: u32,
+    // Bit `a` set: the window tapers axis `a` on its first forward pass.
+    window_axes: u32,
+    // Same numeric encoding as `FftPrecision` on the Rust side.
+    storage_precision: u32,
+    channels: u32,
 }
 
+// Twiddle table sized per entity: `2^(order + 1)` entries for its longest axis.
 struct FftRoots {
-    roots: array<c32, 8192>,
+    roots: array<c32>,
 }
 
 @group(0) @binding(0) var<uniform> globals: Globals;
 @group(0) @binding(1) var<uniform> settings: FftSettings;
 @group(0) @binding(2) var<storage, read_write> roots_buffer: FftRoots;
 
-// Complex workspace buffers **A**–**D**.
+// Complex workspace buffers **A**–**D**. Pipelines built with `FFT_VOLUME` bind 3D textures,
+// pipelines built with `FFT_LAYERS` bind 2D array textures of `settings.depth` layers, and
+// pipelines built with `FFT_HALF` bind one packed texture per buffer (`pack2x16float` re/im per
+// channel) in the `_re` slots, leaving the `_im` slots unbound. `FFT_TEXEL_R` and `FFT_TEXEL_RG`
+// select one- and two-component texels for entities with fewer channels; RGBA otherwise.
+// Pipelines built with `FFT_STORAGE_BUFFERS` (`FftBackend::StorageBuffers`) bind storage buffers
+// in the same four slots instead, each sample stored as a real then an imaginary `vec4<f32>`.
+#ifdef FFT_STORAGE_BUFFERS
+@group(0) @binding(3) var<storage, read_write> buffer_a: array<vec4<f32>>;
+@group(0) @binding(5) var<storage, read_write> buffer_b: array<vec4<f32>>;
+@group(0) @binding(7) var<storage, read_write> buffer_c: array<vec4<f32>>;
+@group(0) @binding(9) var<storage, read_write> buffer_d: array<vec4<f32>>;
+#else
+#ifdef FFT_HALF
+#ifdef FFT_LAYERS
+#ifdef FFT_TEXEL_R
+@group(0) @binding(3) var buffer_a: texture_storage_2d_array<r32uint, read_write>;
+@group(0) @binding(5) var buffer_b: texture_storage_2d_array<r32uint, read_write>;
+@group(0) @binding(7) var buffer_c: texture_storage_2d_array<r32uint, read_write>;
+@group(0) @binding(9) var buffer_d: texture_storage_2d_array<r32uint, read_write>;
+#else
+#ifdef FFT_TEXEL_RG
+@group(0) @binding(3) var buffer_a: texture_storage_2d_array<rg32uint, read_write>;
+@group(0) @binding(5) var buffer_b: texture_storage_2d_array<rg32uint, read_write>;
+@group(0) @binding(7) var buffer_c: texture_storage_2d_array<rg32uint, read_write>;
+@group(0) @binding(9) var buffer_d: texture_storage_2d_array<rg32uint, read_write>;
+#else
+@group(0) @binding(3) var buffer_a: texture_storage_2d_array<rgba32uint, read_write>;
+@group(0) @binding(5) var buffer_b: texture_storage_2d_array<rgba32uint, read_write>;
+@group(0) @binding(7) var buffer_c: texture_storage_2d_array<rgba32uint, read_write>;
+@group(0) @binding(9) var buffer_d: texture_storage_2d_array<rgba32uint, read_write>;
+#endif
+#endif
+#else
+#ifdef FFT_TEXEL_R
+@group(0) @binding(3) var buffer_a: texture_storage_2d<r32uint, read_write>;
+@group(0) @binding(5) var buffer_b: texture_storage_2d<r32uint, read_write>;
+@group(0) @binding(7) var buffer_c: texture_storage_2d<r32uint, read_write>;
+@group(0) @binding(9) var buffer_d: texture_storage_2d<r32uint, read_write>;
+#else
+#ifdef FFT_TEXEL_RG
+@group(0) @binding(3) var buffer_a: texture_storage_2d<rg32uint, read_write>;
+@group(0) @binding(5) var buffer_b: texture_storage_2d<rg32uint, read_write>;
+@group(0) @binding(7) var buffer_c: texture_storage_2d<rg32uint, read_write>;
+@group(0) @binding(9) var buffer_d: texture_storage_2d<rg32uint, read_write>;
+#else
+@group(0) @binding(3) var buffer_a: texture_storage_2d<rgba32uint, read_write>;
+@group(0) @binding(5) var buffer_b: texture_storage_2d<rgba32uint, read_write>;
+@group(0) @binding(7) var buffer_c: texture_storage_2d<rgba32uint, read_write>;
+@group(0) @binding(9) var buffer_d: texture_storage_2d<rgba32uint, read_write>;
+#endif
+#endif
+#endif
+#else
+#ifdef FFT_VOLUME
+#ifdef FFT_TEXEL_R
+@group(0) @binding(3) var buffer_a_re: texture_storage_3d<r32float, read_write>;
+@group(0) @binding(4) var buffer_a_im: texture_storage_3d<r32float, read_write>;
+@group(0) @binding(5) var buffer_b_re: texture_storage_3d<r32float, read_write>;
+@group(0) @binding(6) var buffer_b_im: texture_storage_3d<r32float, read_write>;
+@group(0) @binding(7) var buffer_c_re: texture_storage_3d<r32float, read_write>;
+@group(0) @binding(8) var buffer_c_im: texture_storage_3d<r32float, read_write>;
+@group(0) @binding(9) var buffer_d_re: texture_storage_3d<r32float, read_write>;
+@group(0) @binding(10) var buffer_d_im: texture_storage_3d<r32float, read_write>;
+#else
+#ifdef FFT_TEXEL_RG
+@group(0) @binding(3) var buffer_a_re: texture_storage_3d<rg32float, read_write>;
+@group(0) @binding(4) var buffer_a_im: texture_storage_3d<rg32float, read_write>;
+@group(0) @binding(5) var buffer_b_re: texture_storage_3d<rg32float, read_write>;
+@group(0) @binding(6) var buffer_b_im: texture_storage_3d<rg32float, read_write>;
+@group(0) @binding(7) var buffer_c_re: texture_storage_3d<rg32float, read_write>;
+@group(0) @binding(8) var buffer_c_im: texture_storage_3d<rg32float, read_write>;
+@group(0) @binding(9) var buffer_d_re: texture_storage_3d<rg32float, read_write>;
+@group(0) @binding(10) var buffer_d_im: texture_storage_3d<rg32float, read_write>;
+#else
+@group(0) @binding(3) var buffer_a_re: texture_storage_3d<rgba32float, read_write>;
+@group(0) @binding(4) var buffer_a_im: texture_storage_3d<rgba32float, read_write>;
+@group(0) @binding(5) var buffer_b_re: texture_storage_3d<rgba32float, read_write>;
+@group(0) @binding(6) var buffer_b_im: texture_storage_3d<rgba32float, read_write>;
+@group(0) @binding(7) var buffer_c_re: texture_storage_3d<rgba32float, read_write>;
+@group(0) @binding(8) var buffer_c_im: texture_storage_3d<rgba32float, read_write>;
+@group(0) @binding(9) var buffer_d_re: texture_storage_3d<rgba32float, read_write>;
+@group(0) @binding(10) var buffer_d_im: texture_storage_3d<rgba32float, read_write>;
+#endif
+#endif
+#else
+#ifdef FFT_LAYERS
+#ifdef FFT_TEXEL_R
+@group(0) @binding(3) var buffer_a_re: texture_storage_2d_array<r32float, read_write>;
+@group(0) @binding(4) var buffer_a_im: texture_storage_2d_array<r32float, read_write>;
+@group(0) @binding(5) var buffer_b_re: texture_storage_2d_array<r32float, read_write>;
+@group(0) @binding(6) var buffer_b_im: texture_storage_2d_array<r32float, read_write>;
+@group(0) @binding(7) var buffer_c_re: texture_storage_2d_array<r32float, read_write>;
+@group(0) @binding(8) var buffer_c_im: texture_storage_2d_array<r32float, read_write>;
+@group(0) @binding(9) var buffer_d_re: texture_storage_2d_array<r32float, read_write>;
+@group(0) @binding(10) var buffer_d_im: texture_storage_2d_array<r32float, read_write>;
+#else
+#ifdef FFT_TEXEL_RG
+@group(0) @binding(3) var buffer_a_re: texture_storage_2d_array<rg32float, read_write>;
+@group(0) @binding(4) var buffer_a_im: texture_storage_2d_array<rg32float, read_write>;
+@group(0) @binding(5) var buffer_b_re: texture_storage_2d_array<rg32float, read_write>;
+@group(0) @binding(6) var buffer_b_im: texture_storage_2d_array<rg32float, read_write>;
+@group(0) @binding(7) var buffer_c_re: texture_storage_2d_array<rg32float, read_write>;
+@group(0) @binding(8) var buffer_c_im: texture_storage_2d_array<rg32float, read_write>;
+@group(0) @binding(9) var buffer_d_re: texture_storage_2d_array<rg32float, read_write>;
+@group(0) @binding(10) var buffer_d_im: texture_storage_2d_array<rg32float, read_write>;
+#else
+@group(0) @binding(3) var buffer_a_re: texture_storage_2d_array<rgba32float, read_write>;
+@group(0) @binding(4) var buffer_a_im: texture_storage_2d_array<rgba32float, read_write>;
+@group(0) @binding(5) var buffer_b_re: texture_storage_2d_array<rgba32float, read_write>;
+@group(0) @binding(6) var buffer_b_im: texture_storage_2d_array<rgba32float, read_write>;
+@group(0) @binding(7) var buffer_c_re: texture_storage_2d_array<rgba32float, read_write>;
+@group(0) @binding(8) var buffer_c_im: texture_storage_2d_array<rgba32float, read_write>;
+@group(0) @binding(9) var buffer_d_re: texture_storage_2d_array<rgba32float, read_write>;
+@group(0) @binding(10) var buffer_d_im: texture_storage_2d_array<rgba32float, read_write>;
+#endif
+#endif
+#else
+#ifdef FFT_TEXEL_R
+@group(0) @binding(3) var buffer_a_re: texture_storage_2d<r32float, read_write>;
+@group(0) @binding(4) var buffer_a_im: texture_storage_2d<r32float, read_write>;
+@group(0) @binding(5) var buffer_b_re: texture_storage_2d<r32float, read_write>;
+@group(0) @binding(6) var buffer_b_im: texture_storage_2d<r32float, read_write>;
+@group(0) @binding(7) var buffer_c_re: texture_storage_2d<r32float, read_write>;
+@group(0) @binding(8) var buffer_c_im: texture_storage_2d<r32float, read_write>;
+@group(0) @binding(9) var buffer_d_re: texture_storage_2d<r32float, read_write>;
+@group(0) @binding(10) var buffer_d_im: texture_storage_2d<r32float, read_write>;
+#else
+#ifdef FFT_TEXEL_RG
+@group(0) @binding(3) var buffer_a_re: texture_storage_2d<rg32float, read_write>;
+@group(0) @binding(4) var buffer_a_im: texture_storage_2d<rg32float, read_write>;
+@group(0) @binding(5) var buffer_b_re: texture_storage_2d<rg32float, read_write>;
+@group(0) @binding(6) var buffer_b_im: texture_storage_2d<rg32float, read_write>;
+@group(0) @binding(7) var buffer_c_re: texture_storage_2d<rg32float, read_write>;
+@group(0) @binding(8) var buffer_c_im: texture_storage_2d<rg32float, read_write>;
+@group(0) @binding(9) var buffer_d_re: texture_storage_2d<rg32float, read_write>;
+@group(0) @binding(10) var buffer_d_im: texture_storage_2d<rg32float, read_write>;
+#else
 @group(0) @binding(3) var buffer_a_re: texture_storage_2d<rgba32float, read_write>;
 @group(0) @binding(4) var buffer_a_im: texture_storage_2d<rgba32float, read_write>;
 @group(0) @binding(5) var buffer_b_re: texture_storage_2d<rgba32float, read_write>;
@@ -35,3 +190,15 @@ struct FftRoots {
 @group(0) @binding(8) var buffer_c_im: texture_storage_2d<rgba32float, read_write>;
 @group(0) @binding(9) var buffer_d_re: texture_storage_2d<rgba32float, read_write>;
 @group(0) @binding(10) var buffer_d_im: texture_storage_2d<rgba32float, read_write>;
+#endif
+#endif
+#endif
+#endif
+#endif
+#endif
+
+// Bluestein chirp spectra for non-power-of-two axes: rows first, then columns. Holds a single
+// unused entry when both edges are powers of two.
+@group(0) @binding(11) var<storage, read> bluestein_kernel: array<c32>;
+// Two interleaved transform-length slots per grid line, addressed as FFT buffers 3 and 4.
+@group(0) @binding(12) var<storage, read_write> bluestein_workspace: array<vec4<f32>>;
@@ -0,0 +1,151 @@
+//! Bluestein (chirp-z) support for axes whose length is not a power of two.
+//!
+//! An `N`-point DFT becomes a circular convolution of length `M = (2N - 1).next_power_of_two()`:
+//! `X[k] = c[k] · Σ (x[n] · c[n]) · conj(c[k - n])` with the chirp `c[m] = exp(-iπ m² / N)`. The
+//! `fft_bluestein_*` kernels in `fft.wgsl` run it per axis: chirp-modulate into a zero-padded
+//! workspace line, forward transform, multiply by [`bluestein_kernel_spectrum`] and conjugate,
+//! forward transform again, then conjugate and demodulate back into the first `N` bins. Only the
+//! kernel spectrum is computed on the CPU.
+
+use crate::complex::c32;
+
+/// `exp(-iπ m² / n)`, with `m²` reduced mod `2n` so long lines keep their precision.
+pub(super) fn chirp(m: u32, n: u32) -> (f64, f64) {
+    let t = (u64::from(m) * u64::from(m)) % (2 * u64::from(n));
+    let angle = -std::f64::consts::PI * t as f64 / f64::from(n);
+    (angle.cos(), angle.sin())
+}
+
+/// In-place forward radix-2 FFT for the kernel spectrum and [`super::cpu`]. `data.len()` must
+/// be a power of two.
+pub(super) fn fft_in_place(data: &mut [(f64, f64)]) {
+    let n = data.len();
+    let order = n.trailing_zeros();
+    for i in 0..n {
+        let j = i
+            .reverse_bits()
+            .checked_shr(usize::BITS - order)
+            .unwrap_or(0);
+        if i < j {
+            data.swap(i, j);
+        }
+    }
+    let mut len = 2;
+    while len <= n {
+        let angle = -2.0 * std::f64::consts::PI / len as f64;
+        for start in (0..n).step_by(len) {
+            for k in 0..len / 2 {
+                let (wr, wi) = ((angle * k as f64).cos(), (angle * k as f64).sin());
+                let (ar, ai) = data[start + k];
+                let (br, bi) = data[start + k + len / 2];
+                let (tr, ti) = (br * wr - bi * wi, br * wi + bi * wr);
+                data[start + k] = (ar + tr, ai + ti);
+                data[start + k + len / 2] = (ar - tr, ai - ti);
+            }
+        }
+        len <<= 1;
+    }
+}
+
+/// Forward transform of the conjugate chirp of an `n`-point line, wrapped to length `m`.
+pub(super) fn chirp_spectrum(n: u32, m: usize) -> Vec<(f64, f64)> {
+    let mut kernel = vec![(0.0, 0.0); m];
+    for j in 0..n as usize {
+        let (re, im) = chirp(j as u32, n);
+        kernel[j] = (re, -im);
+        if j > 0 {
+            kernel[m - j] = (re, -im);
+        }
+    }
+    fft_in_place(&mut kernel);
+    kernel
+}
+
+/// [`chirp_spectrum`] prescaled by `1/m` so the GPU skips the inverse normalization of the
+/// convolution. Empty when `n` is a power of two.
+pub(crate) fn bluestein_kernel_spectrum(n: u32, m: u32) -> Vec<c32> {
+    if n.is_power_of_two() {
+        return Vec::new();
+    }
+    let scale = 1.0 / f64::from(m);
+    chirp_spectrum(n, m as usize)
+        .into_iter()
+        .map(|(re, im)| c32::new((re * scale) as f32, (im * scale) as f32))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    //! CPU mirror of the `fft_bluestein_*` kernel sequence, checked against a naive DFT.
+
+    use super::{bluestein_kernel_spectrum, chirp, fft_in_place};
+    use crate::fft::fft_transform_len;
+
+    fn bluestein(input: &[(f64, f64)]) -> Vec<(f64, f64)> {
+        let n = input.len() as u32;
+        let m = fft_transform_len(n).unwrap();
+        let kernel = bluestein_kernel_spectrum(n, m);
+        // fft_bluestein_premultiply
+        let mut line = vec![(0.0, 0.0); m as usize];
+        for (i, &(xr, xi)) in input.iter().enumerate() {
+            let (cr, ci) = chirp(i as u32, n);
+            line[i] = (xr * cr - xi * ci, xr * ci + xi * cr);
+        }
+        fft_in_place(&mut line);
+        // fft_bluestein_multiply
+        for (v, b) in line.iter_mut().zip(&kernel) {
+            let (br, bi) = (f64::from(b.re), f64::from(b.im));
+            *v = (v.0 * br - v.1 * bi, -(v.0 * bi + v.1 * br));
+        }
+        fft_in_place(&mut line);
+        // fft_bluestein_postmultiply
+        (0..n)
+            .map(|k| {
+                let (zr, zi) = (line[k as usize].0, -line[k as usize].1);
+                let (cr, ci) = chirp(k, n);
+                (zr * cr - zi * ci, zr * ci + zi * cr)
+            })
+            .collect()
+    }
+
+    fn naive_dft(input: &[(f64, f64)]) -> Vec<(f64, f64)> {
+        let n = input.len();
+        (0..n)
+            .map(|k| {
+                input
+                    .iter()
+                    .enumerate()
+                    .fold((0.0, 0.0), |(ar, ai), (t, &(xr, xi))| {
+                        let theta = -2.0 * std::f64::consts::PI * ((k * t) % n) as f64 / n as f64;
+                        let (c, s) = (theta.cos(), theta.sin());
+                        (ar + xr * c - xi * s, ai + xr * s + xi * c)
+                    })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn bluestein_matches_naive_dft() {
+        for n in [3usize, 5, 6, 7, 12, 100, 600] {
+            let input: Vec<(f64, f64)> = (0..n)
+                .map(|i| ((i as f64 * 0.37).sin(), (i as f64 * 1.3).cos() * 0.5))
+                .collect();
+            let expected = naive_dft(&input);
+            let actual = bluestein(&input);
+            // The kernel spectrum is stored as f32, so allow for that rounding.
+            let tolerance = 1e-5 * n as f64;
+            for (k, (e, a)) in expected.iter().zip(&actual).enumerate() {
+                assert!(
+                    (e.0 - a.0).abs() < tolerance && (e.1 - a.1).abs() < tolerance,
+                    "n={n} bin={k}: expected {e:?}, got {a:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn kernel_spectrum_is_empty_for_powers_of_two() {
+        assert!(bluestein_kernel_spectrum(256, 256).is_empty());
+        assert_eq!(bluestein_kernel_spectrum(600, 2048).len(), 2048);
+    }
+}
@@ -1,24 +1,48 @@
 #define_import_path bevy_fft::buffer
 
-#import bevy_fft::{
-    complex::{
-        splat_c32_n,
-        c32,
-        c32_2,
-        c32_3,
-        c32_4,
-    },
-    bindings::{
-        buffer_a_re,
-        buffer_a_im,
-        buffer_b_re,
-        buffer_b_im,
-        buffer_c_re,
-        buffer_c_im,
-        buffer_d_re,
-        buffer_d_im,
-    }
-};
+#import bevy_fft::complex::{
+    splat_c32_n,
+    c32,
+    c32_2,
+    c32_3,
+    c32_4,
+    packed_c32,
+    packed_c32_2,
+    packed_c32_3,
+    packed_c32_4,
+    pack_c32_n,
+    unpack_c32_n,
+}
+
+#ifdef FFT_STORAGE_BUFFERS
+#import bevy_fft::bindings::{
+    settings,
+    buffer_a,
+    buffer_b,
+    buffer_c,
+    buffer_d,
+}
+#else
+#ifdef FFT_HALF
+#import bevy_fft::bindings::{
+    buffer_a,
+    buffer_b,
+    buffer_c,
+    buffer_d,
+}
+#else
+#import bevy_fft::bindings::{
+    buffer_a_re,
+    buffer_a_im,
+    buffer_b_re,
+    buffer_b_im,
+    buffer_c_re,
+    buffer_c_im,
+    buffer_d_re,
+    buffer_d_im,
+}
+#endif
+#endif
 
 #ifdef CHANNELS
 #if CHANNELS == 1 
@@ -32,59 +56,322 @@
 #endif
 #endif
 
+// The first `CHANNELS` components of a workspace texel, and back. Narrow texel formats return
+// zero in the unused components, so the same helpers serve every `FFT_TEXEL_*` width.
+#ifdef CHANNELS
+#if CHANNELS == 1
+fn fft_lanes(v: vec4<f32>) -> f32 {
+    return v.x;
+}
+
+fn fft_lanes_vec4(x: f32) -> vec4<f32> {
+    return vec4<f32>(x, 0.0, 0.0, 0.0);
+}
+
+fn fft_packed_lanes(v: vec4<u32>) -> packed_c32 {
+    return packed_c32(v.x);
+}
+
+fn fft_packed_vec4(p: packed_c32) -> vec4<u32> {
+    return vec4<u32>(p.value, 0u, 0u, 0u);
+}
+#else if CHANNELS == 2
+fn fft_lanes(v: vec4<f32>) -> vec2<f32> {
+    return v.xy;
+}
+
+fn fft_lanes_vec4(x: vec2<f32>) -> vec4<f32> {
+    return vec4<f32>(x, 0.0, 0.0);
+}
+
+fn fft_packed_lanes(v: vec4<u32>) -> packed_c32_2 {
+    return packed_c32_2(v.xy);
+}
+
+fn fft_packed_vec4(p: packed_c32_2) -> vec4<u32> {
+    return vec4<u32>(p.value, 0u, 0u);
+}
+#else if CHANNELS == 3
+fn fft_lanes(v: vec4<f32>) -> vec3<f32> {
+    return v.xyz;
+}
+
+fn fft_lanes_vec4(x: vec3<f32>) -> vec4<f32> {
+    return vec4<f32>(x, 0.0);
+}
+
+fn fft_packed_lanes(v: vec4<u32>) -> packed_c32_3 {
+    return packed_c32_3(v.xyz);
+}
+
+fn fft_packed_vec4(p: packed_c32_3) -> vec4<u32> {
+    return vec4<u32>(p.value, 0u);
+}
+#else if CHANNELS == 4
+fn fft_lanes(v: vec4<f32>) -> vec4<f32> {
+    return v;
+}
+
+fn fft_lanes_vec4(x: vec4<f32>) -> vec4<f32> {
+    return x;
+}
+
+fn fft_packed_lanes(v: vec4<u32>) -> packed_c32_4 {
+    return packed_c32_4(v);
+}
+
+fn fft_packed_vec4(p: packed_c32_4) -> vec4<u32> {
+    return p.value;
+}
+#endif
+#endif
+
+// Texture coordinate of a workspace position. Positions always carry `z`, which is 0 outside
+// `FFT_VOLUME` and `FFT_LAYERS`, so the same helpers serve 2D and 3D workspace bindings.
+// `FFT_LAYERS` array textures load `pos.xy` from layer `pos.z` instead.
+#ifdef FFT_VOLUME
+fn fft_texel(pos: vec3<u32>) -> vec3<u32> {
+    return pos;
+}
+#else
+fn fft_texel(pos: vec3<u32>) -> vec2<u32> {
+    return pos.xy;
+}
+#endif
+
+#ifdef FFT_STORAGE_BUFFERS
+// `FFT_STORAGE_BUFFERS` workspaces hold samples in row-major order over the workspace extent
+// (slices after rows for volumes), two `vec4<f32>` per sample: real, then imaginary.
+fn fft_sample_index(pos: vec3<u32>) -> u32 {
+    return ((pos.z * settings.size.y + pos.y) * settings.size.x + pos.x) * 2u;
+}
+
+fn read_buffer_a(pos: vec3<u32>) -> c32_n {
+    let i = fft_sample_index(pos);
+    return c32_n(fft_lanes(buffer_a[i]), fft_lanes(buffer_a[i + 1u]));
+}
+
+fn read_buffer_b(pos: vec3<u32>) -> c32_n {
+    let i = fft_sample_index(pos);
+    return c32_n(fft_lanes(buffer_b[i]), fft_lanes(buffer_b[i + 1u]));
+}
+
+fn read_buffer_c(pos: vec3<u32>) -> c32_n {
+    let i = fft_sample_index(pos);
+    return c32_n(fft_lanes(buffer_c[i]), fft_lanes(buffer_c[i + 1u]));
+}
+
+fn read_buffer_d(pos: vec3<u32>) -> c32_n {
+    let i = fft_sample_index(pos);
+    return c32_n(fft_lanes(buffer_d[i]), fft_lanes(buffer_d[i + 1u]));
+}
+
+fn write_buffer_a(pos: vec3<u32>, value: c32_n) {
+    let i = fft_sample_index(pos);
+    buffer_a[i] = fft_lanes_vec4(value.re);
+    buffer_a[i + 1u] = fft_lanes_vec4(value.im);
+}
+
+fn write_buffer_b(pos: vec3<u32>, value: c32_n) {
+    let i = fft_sample_index(pos);
+    buffer_b[i] = fft_lanes_vec4(value.re);
+    buffer_b[i + 1u] = fft_lanes_vec4(value.im);
+}
+
+fn write_buffer_c(pos: vec3<u32>, value: c32_n) {
+    let i = fft_sample_index(pos);
+    buffer_c[i] = fft_lanes_vec4(value.re);
+    buffer_c[i + 1u] = fft_lanes_vec4(value.im);
+}
+
+fn write_buffer_d(pos: vec3<u32>, value: c32_n) {
+    let i = fft_sample_index(pos);
+    buffer_d[i] = fft_lanes_vec4(value.re);
+    buffer_d[i + 1u] = fft_lanes_vec4(value.im);
+}
+#else
+#ifdef FFT_HALF
+// `FFT_HALF` workspaces keep one `*32uint` texel per sample, each channel holding its real and
+// imaginary parts as two f16 halves (`FftPrecision::Half`). Arithmetic still runs in f32.
+#ifdef FFT_LAYERS
+fn read_buffer_a(pos: vec3<u32>) -> c32_n {
+    return unpack_c32_n(fft_packed_lanes(textureLoad(buffer_a, pos.xy, pos.z)));
+}
+
+fn read_buffer_b(pos: vec3<u32>) -> c32_n {
+    return unpack_c32_n(fft_packed_lanes(textureLoad(buffer_b, pos.xy, pos.z)));
+}
+
+fn read_buffer_c(pos: vec3<u32>) -> c32_n {
+    return unpack_c32_n(fft_packed_lanes(textureLoad(buffer_c, pos.xy, pos.z)));
+}
+
+fn read_buffer_d(pos: vec3<u32>) -> c32_n {
+    return unpack_c32_n(fft_packed_lanes(textureLoad(buffer_d, pos.xy, pos.z)));
+}
+
+fn write_buffer_a(pos: vec3<u32>, value: c32_n) {
+    textureStore(buffer_a, pos.xy, pos.z, fft_packed_vec4(pack_c32_n(value)));
+}
+
+fn write_buffer_b(pos: vec3<u32>, value: c32_n) {
+    textureStore(buffer_b, pos.xy, pos.z, fft_packed_vec4(pack_c32_n(value)));
+}
+
+fn write_buffer_c(pos: vec3<u32>, value: c32_n) {
+    textureStore(buffer_c, pos.xy, pos.z, fft_packed_vec4(pack_c32_n(value)));
+}
+
+fn write_buffer_d(pos: vec3<u32>, value: c32_n) {
+    textureStore(buffer_d, pos.xy, pos.z, fft_packed_vec4(pack_c32_n(value)));
+}
+#else
+fn read_buffer_a(pos: vec3<u32>) -> c32_n {
+    return unpack_c32_n(fft_packed_lanes(textureLoad(buffer_a, fft_texel(pos))));
+}
+
+fn read_buffer_b(pos: vec3<u32>) -> c32_n {
+    return unpack_c32_n(fft_packed_lanes(textureLoad(buffer_b, fft_texel(pos))));
+}
+
+fn read_buffer_c(pos: vec3<u32>) -> c32_n {
+    return unpack_c32_n(fft_packed_lanes(textureLoad(buffer_c, fft_texel(pos))));
+}
+
+fn read_buffer_d(pos: vec3<u32>) -> c32_n {
+    return unpack_c32_n(fft_packed_lanes(textureLoad(buffer_d, fft_texel(pos))));
+}
+
+fn write_buffer_a(pos: vec3<u32>, value: c32_n) {
+    textureStore(buffer_a, fft_texel(pos), fft_packed_vec4(pack_c32_n(value)));
+}
+
+fn write_buffer_b(pos: vec3<u32>, value: c32_n) {
+    textureStore(buffer_b, fft_texel(pos), fft_packed_vec4(pack_c32_n(value)));
+}
+
+fn write_buffer_c(pos: vec3<u32>, value: c32_n) {
+    textureStore(buffer_c, fft_texel(pos), fft_packed_vec4(pack_c32_n(value)));
+}
+
+fn write_buffer_d(pos: vec3<u32>, value: c32_n) {
+    textureStore(buffer_d, fft_texel(pos), fft_packed_vec4(pack_c32_n(value)));
+}
+#endif
+#else
 // Helper functions that take an explicit iteration parameter
-fn read_buffer_a(pos: vec2<u32>) -> c32_n {
+#ifdef FFT_LAYERS
+fn read_buffer_a(pos: vec3<u32>) -> c32_n {
     return c32_n(
-        textureLoad(buffer_a_re, pos),
-        textureLoad(buffer_a_im, pos)
+        fft_lanes(textureLoad(buffer_a_re, pos.xy, pos.z)),
+        fft_lanes(textureLoad(buffer_a_im, pos.xy, pos.z))
     );
 }
 
-fn read_buffer_b(pos: vec2<u32>) -> c32_n {
+fn read_buffer_b(pos: vec3<u32>) -> c32_n {
     return c32_n(
-        textureLoad(buffer_b_re, pos),
-        textureLoad(buffer_b_im, pos)
+        fft_lanes(textureLoad(buffer_b_re, pos.xy, pos.z)),
+        fft_lanes(textureLoad(buffer_b_im, pos.xy, pos.z))
     );
 }
 
-fn read_buffer_c(pos: vec2<u32>) -> c32_n {
+fn read_buffer_c(pos: vec3<u32>) -> c32_n {
     return c32_n(
-        textureLoad(buffer_c_re, pos),
-        textureLoad(buffer_c_im, pos)
+        fft_lanes(textureLoad(buffer_c_re, pos.xy, pos.z)),
+        fft_lanes(textureLoad(buffer_c_im, pos.xy, pos.z))
     );
 }
 
-fn read_buffer_d(pos: vec2<u32>) -> c32_n {
+fn read_buffer_d(pos: vec3<u32>) -> c32_n {
     return c32_n(
-        textureLoad(buffer_d_re, pos),
-        textureLoad(buffer_d_im, pos)
+        fft_lanes(textureLoad(buffer_d_re, pos.xy, pos.z)),
+        fft_lanes(textureLoad(buffer_d_im, pos.xy, pos.z))
     );
 }
 
-fn write_buffer_a(pos: vec2<u32>, value: c32_n) {
-    textureStore(buffer_a_re, pos, value.re);
-    textureStore(buffer_a_im, pos, value.im);
+fn write_buffer_a(pos: vec3<u32>, value: c32_n) {
+    textureStore(buffer_a_re, pos.xy, pos.z, fft_lanes_vec4(value.re));
+    textureStore(buffer_a_im, pos.xy, pos.z, fft_lanes_vec4(value.im));
 }
 
-fn write_buffer_b(pos: vec2<u32>, value: c32_n) {
-    textureStore(buffer_b_re, pos, value.re);
-    textureStore(buffer_b_im, pos, value.im);
+fn write_buffer_b(pos: vec3<u32>, value: c32_n) {
+    textureStore(buffer_b_re, pos.xy, pos.z, fft_lanes_vec4(value.re));
+    textureStore(buffer_b_im, pos.xy, pos.z, fft_lanes_vec4(value.im));
 }
 
-fn write_buffer_c(pos: vec2<u32>, value: c32_n) {
-    textureStore(buffer_c_re, pos, value.re);
-    textureStore(buffer_c_im, pos, value.im);
+fn write_buffer_c(pos: vec3<u32>, value: c32_n) {
+    textureStore(buffer_c_re, pos.xy, pos.z, fft_lanes_vec4(value.re));
+    textureStore(buffer_c_im, pos.xy, pos.z, fft_lanes_vec4(value.im));
 }
 
-fn write_buffer_d(pos: vec2<u32>, value: c32_n) {
-    textureStore(buffer_d_re, pos, value.re);
-    textureStore(buffer_d_im, pos, value.im);
+fn write_buffer_d(pos: vec3<u32>, value: c32_n) {
+    textureStore(buffer_d_re, pos.xy, pos.z, fft_lanes_vec4(value.re));
+    textureStore(buffer_d_im, pos.xy, pos.z, fft_lanes_vec4(value.im));
 }
 
-fn write_shifted_d_re(pos: vec2<u32>, value: vec4<f32>) {
-    textureStore(buffer_d_re, pos, value);
+fn write_shifted_d_re(pos: vec3<u32>, value: vec4<f32>) {
+    textureStore(buffer_d_re, pos.xy, pos.z, value);
 }
 
-fn write_shifted_d_im(pos: vec2<u32>, value: vec4<f32>) {
-    textureStore(buffer_d_im, pos, value);
+fn write_shifted_d_im(pos: vec3<u32>, value: vec4<f32>) {
+    textureStore(buffer_d_im, pos.xy, pos.z, value);
 }
+#else
+fn read_buffer_a(pos: vec3<u32>) -> c32_n {
+    return c32_n(
+        fft_lanes(textureLoad(buffer_a_re, fft_texel(pos))),
+        fft_lanes(textureLoad(buffer_a_im, fft_texel(pos)))
+    );
+}
+
+fn read_buffer_b(pos: vec3<u32>) -> c32_n {
+    return c32_n(
+        fft_lanes(textureLoad(buffer_b_re, fft_texel(pos))),
+        fft_lanes(textureLoad(buffer_b_im, fft_texel(pos)))
+    );
+}
+
+fn read_buffer_c(pos: vec3<u32>) -> c32_n {
+    return c32_n(
+        fft_lanes(textureLoad(buffer_c_re, fft_texel(pos))),
+        fft_lanes(textureLoad(buffer_c_im, fft_texel(pos)))
+    );
+}
+
+fn read_buffer_d(pos: vec3<u32>) -> c32_n {
+    return c32_n(
+        fft_lanes(textureLoad(buffer_d_re, fft_texel(pos))),
+        fft_lanes(textureLoad(buffer_d_im, fft_texel(pos)))
+    );
+}
+
+fn write_buffer_a(pos: vec3<u32>, value: c32_n) {
+    textureStore(buffer_a_re, fft_texel(pos), fft_lanes_vec4(value.re));
+    textureStore(buffer_a_im, fft_texel(pos), fft_lanes_vec4(value.im));
+}
+
+fn write_buffer_b(pos: vec3<u32>, value: c32_n) {
+    textureStore(buffer_b_re, fft_texel(pos), fft_lanes_vec4(value.re));
+    textureStore(buffer_b_im, fft_texel(pos), fft_lanes_vec4(value.im));
+}
+
+fn write_buffer_c(pos: vec3<u32>, value: c32_n) {
+    textureStore(buffer_c_re, fft_texel(pos), fft_lanes_vec4(value.re));
+    textureStore(buffer_c_im, fft_texel(pos), fft_lanes_vec4(value.im));
+}
+
+fn write_buffer_d(pos: vec3<u32>, value: c32_n) {
+    textureStore(buffer_d_re, fft_texel(pos), fft_lanes_vec4(value.re));
+    textureStore(buffer_d_im, fft_texel(pos), fft_lanes_vec4(value.im));
+}
+
+fn write_shifted_d_re(pos: vec3<u32>, value: vec4<f32>) {
+    textureStore(buffer_d_re, fft_texel(pos), value);
+}
+
+fn write_shifted_d_im(pos: vec3<u32>, value: vec4<f32>) {
+    textureStore(buffer_d_im, fft_texel(pos), value);
+}
+#endif
+#endif
+#endif
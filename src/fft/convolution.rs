@@ -0,0 +1,688 @@
+//! Spectral convolution of an [`FftSource`] with an image kernel, without a custom spectrum pass.
+//!
+//! The main world transforms each [`FftConvolution`] kernel with [`cpu::forward`] on the
+//! [`AsyncComputeTaskPool`] when the kernel, the component or the workspace changes. The render
+//! world multiplies the result into buffer **C** at
+//! [`FftNode::Convolve`](super::FftNode::Convolve), after any spliced spectrum pass and before the
+//! spectrum is resolved, so the inverse transform writes the convolved signal to
+//! [`FftTextures::spatial_output`](super::FftTextures::spatial_output).
+
+use bevy::{
+    asset::AssetEvent,
+    ecs::query::QueryState,
+    platform::{collections::HashMap, sync::Arc},
+    prelude::*,
+    render::{
+        diagnostic::RecordDiagnostics,
+        extract_component::ExtractComponent,
+        render_graph::{Node, NodeRunError, RenderGraphContext},
+        render_resource::{binding_types::storage_buffer_read_only_sized, *},
+        renderer::{RenderContext, RenderDevice},
+        sync_world::MainEntity,
+    },
+    tasks::{AsyncComputeTaskPool, Task, block_on, poll_once},
+};
+
+use super::{
+    FftNormalization, FftRunRequest, FftSettings, FftSource, FftTrigger, cpu,
+    diagnostics::FftTimedPass,
+    resources::{FftBindGroups, FftPipelines, FftWorkspaceFormat},
+    shaders,
+    trigger::{FftSkippedRuns, FftTriggered, modified_images},
+};
+use crate::complex::c32;
+
+/// How [`FftConvolution`] treats samples past the edges of the signal.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, Reflect)]
+pub enum FftConvolutionMode {
+    /// The kernel wraps around the workspace edges, as the plain spectral product does.
+    #[default]
+    Circular,
+    /// Samples past the signal edges count as zero. Needs [`FftSource::padding`] of at least
+    /// half the kernel extent on each transformed axis, which
+    /// [`FftSource::with_convolution_padding`] provides; entities with less are not convolved.
+    Linear,
+}
+
+/// Convolves the entity's signal with `kernel` by multiplying its spectrum into buffer **C**
+/// between the forward and inverse transforms.
+///
+/// The kernel is an `Rgba32Float` image no larger than the workspace, centred on texel
+/// `(width / 2, height / 2)`. Channel `k` filters workspace channel `k`; four-channel sources
+/// leave alpha untouched. [`FftDimension::OneD`](super::FftDimension::OneD) batches take a kernel one row high and apply it
+/// to every row, and every layer of a layered source shares the kernel. Volumes are not
+/// supported. The result is the convolution itself whatever the source's [`FftNormalization`].
+#[derive(Component, ExtractComponent, Clone, Debug, Reflect)]
+pub struct FftConvolution {
+    pub kernel: Handle<Image>,
+    pub mode: FftConvolutionMode,
+}
+
+impl FftConvolution {
+    pub fn circular(kernel: Handle<Image>) -> Self {
+        Self {
+            kernel,
+            mode: FftConvolutionMode::Circular,
+        }
+    }
+
+    pub fn linear(kernel: Handle<Image>) -> Self {
+        Self {
+            kernel,
+            mode: FftConvolutionMode::Linear,
+        }
+    }
+}
+
+/// Workspace properties a kernel spectrum depends on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct FftKernelLayout {
+    size: UVec2,
+    padding: UVec2,
+    dimension: u32,
+    channels: u32,
+    normalization: u32,
+}
+
+impl FftKernelLayout {
+    fn of(settings: &FftSettings) -> Self {
+        Self {
+            size: settings.size,
+            padding: settings.padding,
+            dimension: settings.dimension,
+            channels: settings.channels,
+            normalization: settings.fft_normalization,
+        }
+    }
+}
+
+/// Kernel spectrum of an [`FftConvolution`], one real then one imaginary `vec4<f32>` per bin of
+/// a single grid, row-major like [`FftStorageBuffers`](super::FftStorageBuffers).
+#[derive(Component, ExtractComponent, Clone)]
+pub(super) struct FftConvolutionKernel {
+    layout: FftKernelLayout,
+    /// `None` when the kernel cannot convolve the entity, which then runs unconvolved.
+    spectrum: Option<Arc<[u8]>>,
+}
+
+/// Kernel size, or why `kernel` cannot convolve a workspace with `settings`.
+fn check_kernel(
+    settings: &FftSettings,
+    mode: FftConvolutionMode,
+    kernel: &Image,
+) -> Result<UVec2, String> {
+    if settings.is_volume() {
+        return Err("volumes are not supported".into());
+    }
+    if kernel.texture_descriptor.format != TextureFormat::Rgba32Float {
+        return Err(format!(
+            "the kernel is {:?}, not Rgba32Float",
+            kernel.texture_descriptor.format
+        ));
+    }
+    let extent = kernel.texture_descriptor.size;
+    let size = UVec2::new(extent.width, extent.height);
+    if extent.depth_or_array_layers != 1 || size.cmpgt(settings.size).any() {
+        return Err(format!(
+            "the {}x{}x{} kernel does not fit the {} workspace",
+            extent.width, extent.height, extent.depth_or_array_layers, settings.size
+        ));
+    }
+    let one_d = settings.transformed_axes() == 1;
+    if one_d && size.y != 1 {
+        return Err("1D batches need a kernel one row high".into());
+    }
+    let needed = match mode {
+        FftConvolutionMode::Circular => UVec2::ZERO,
+        FftConvolutionMode::Linear if one_d => UVec2::new(size.x / 2, 0),
+        FftConvolutionMode::Linear => size / 2,
+    };
+    if settings.padding.cmplt(needed).any() {
+        return Err(format!(
+            "linear mode needs at least {needed} padding, see FftSource::with_convolution_padding"
+        ));
+    }
+    if kernel.data.as_ref().map(Vec::len) != Some((size.x * size.y * 16) as usize) {
+        return Err("the kernel has no CPU-side data".into());
+    }
+    Ok(size)
+}
+
+/// Forward transform of `kernel`, `Rgba32Float` texels over `kernel_size`, for a workspace with
+/// `settings`. Scaled so that multiplying it into **C** and running the inverse yields the plain
+/// convolution under every [`FftNormalization`]. Lane 3 of four-channel workspaces is one, so the
+/// alpha marker of the forward pass survives the product.
+fn kernel_spectrum(settings: &FftSettings, kernel: &[f32], kernel_size: UVec2) -> Vec<c32> {
+    let normalization =
+        FftNormalization::try_from_bits(settings.fft_normalization).unwrap_or_default();
+    let settings = FftSettings {
+        depth: 1,
+        window_axes: 0,
+        fft_normalization: FftNormalization::None.to_bits(),
+        ..*settings
+    };
+    let size = settings.size;
+    let channels = settings.channels as usize;
+    let one_d = settings.transformed_axes() == 1;
+
+    // Centre texel to the origin, wrapping the rest around the edges.
+    let wrap = |k: u32, kernel_len: u32, len: u32| (k + len - kernel_len / 2) % len;
+    let mut data = vec![c32::new(0.0, 0.0); cpu::workspace_len(&settings)];
+    for ky in 0..kernel_size.y {
+        let rows = if one_d {
+            0..size.y
+        } else {
+            let y = wrap(ky, kernel_size.y, size.y);
+            y..y + 1
+        };
+        for kx in 0..kernel_size.x {
+            let x = wrap(kx, kernel_size.x, size.x);
+            let texel = (ky * kernel_size.x + kx) as usize * 4;
+            for y in rows.clone() {
+                let sample = (y * size.x + x) as usize * channels;
+                for lane in 0..channels {
+                    data[sample + lane] = c32::new(kernel[texel + lane], 0.0);
+                }
+            }
+        }
+    }
+    cpu::forward(&settings, &mut data);
+
+    // The source's forward and inverse scales multiply the product by `s_f * s_i * n` per axis.
+    let gain: f32 = [size.x, size.y][..settings.transformed_axes() as usize]
+        .iter()
+        .map(|&n| {
+            let (forward, inverse) = normalization.scales(n);
+            forward * inverse * n as f32
+        })
+        .product();
+    for sample in &mut data {
+        *sample = *sample * (1.0 / gain);
+    }
+    if channels == 4 {
+        for texel in data.chunks_exact_mut(4) {
+            texel[3] = c32::new(1.0, 0.0);
+        }
+    }
+    data
+}
+
+/// Packs `spectrum` as one real then one imaginary `vec4<f32>` per bin, zeroing unused lanes.
+fn pack_spectrum(spectrum: &[c32], channels: usize) -> Arc<[u8]> {
+    let mut packed = Vec::with_capacity(spectrum.len() / channels * 8);
+    for bin in spectrum.chunks_exact(channels) {
+        let lanes = |part: fn(&c32) -> f32| {
+            let mut lanes = [0.0f32; 4];
+            for (lane, sample) in lanes.iter_mut().zip(bin) {
+                *lane = part(sample);
+            }
+            lanes
+        };
+        packed.extend(lanes(|c| c.re));
+        packed.extend(lanes(|c| c.im));
+    }
+    Arc::from(bytemuck::cast_slice::<f32, u8>(&packed))
+}
+
+/// Kernel spectrum an [`FftConvolution`] is transforming on the [`AsyncComputeTaskPool`], so a
+/// large workspace does not stall the frame. Replacing it drops the superseded transform.
+#[derive(Component)]
+pub(super) struct FftConvolutionKernelTask {
+    layout: FftKernelLayout,
+    task: Task<Arc<[u8]>>,
+}
+
+type FftConvolutionQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        Ref<'static, FftConvolution>,
+        &'static FftSource,
+        Option<&'static FftConvolutionKernel>,
+        Option<&'static mut FftConvolutionKernelTask>,
+        Option<&'static FftTrigger>,
+    ),
+>;
+
+/// Transforms [`FftConvolution`] kernels into [`FftConvolutionKernel`]s when the kernel, the
+/// component or the workspace changes, and asks [`FftTrigger::OnChange`] entities to run again
+/// once the transform lands. Until then [`FftNode::Convolve`](super::FftNode::Convolve) skips the
+/// entity.
+pub(super) fn prepare_fft_convolution_kernels(
+    mut commands: Commands,
+    images: Res<Assets<Image>>,
+    mut image_events: MessageReader<AssetEvent<Image>>,
+    mut query: FftConvolutionQuery,
+) {
+    let modified = modified_images(&mut image_events);
+    for (entity, convolution, source, existing, pending, trigger) in &mut query {
+        let settings = FftSettings::from_fft_source(source);
+        let layout = FftKernelLayout::of(&settings);
+        let current = match &pending {
+            Some(pending) => Some(pending.layout),
+            None => existing.map(|kernel| kernel.layout),
+        };
+        let stale = convolution.is_changed()
+            || modified.contains(&convolution.kernel.id())
+            || current != Some(layout);
+        let mut entity_commands = commands.entity(entity);
+        if !stale {
+            let Some(mut pending) = pending else {
+                continue;
+            };
+            let Some(spectrum) = block_on(poll_once(&mut pending.task)) else {
+                continue;
+            };
+            entity_commands
+                .remove::<FftConvolutionKernelTask>()
+                .insert(FftConvolutionKernel {
+                    layout,
+                    spectrum: Some(spectrum),
+                });
+            if trigger == Some(&FftTrigger::OnChange) {
+                entity_commands.insert(FftRunRequest);
+            }
+            continue;
+        }
+        // Waits for the new kernel rather than convolving with the old one.
+        if existing.is_some() {
+            entity_commands.remove::<FftConvolutionKernel>();
+        }
+        if pending.is_some() {
+            entity_commands.remove::<FftConvolutionKernelTask>();
+        }
+        let Some(image) = images.get(&convolution.kernel) else {
+            continue;
+        };
+        match check_kernel(&settings, convolution.mode, image) {
+            Ok(kernel_size) => {
+                let texels: Vec<f32> = image
+                    .data
+                    .iter()
+                    .flat_map(|data| data.chunks_exact(4))
+                    .map(|bytes| f32::from_le_bytes(bytes.try_into().unwrap()))
+                    .collect();
+                let task = AsyncComputeTaskPool::get().spawn(async move {
+                    let spectrum = kernel_spectrum(&settings, &texels, kernel_size);
+                    pack_spectrum(&spectrum, settings.channels as usize)
+                });
+                entity_commands.insert(FftConvolutionKernelTask { layout, task });
+            }
+            Err(reason) => {
+                warn!("FftConvolution on {entity} is ignored: {reason}");
+                entity_commands.insert(FftConvolutionKernel {
+                    layout,
+                    spectrum: None,
+                });
+            }
+        }
+    }
+}
+
+/// `fft_convolve_spectrum` pipelines per [`FftWorkspaceFormat`], queued the first frame an
+/// entity with an [`FftConvolution`] needs them.
+#[derive(Resource)]
+pub(super) struct FftConvolutionPipelines {
+    layout: BindGroupLayoutDescriptor,
+    pipelines: HashMap<FftWorkspaceFormat, CachedComputePipelineId>,
+}
+
+impl Default for FftConvolutionPipelines {
+    fn default() -> Self {
+        let entries = BindGroupLayoutEntries::single(
+            ShaderStages::COMPUTE,
+            storage_buffer_read_only_sized(false, None),
+        );
+        Self {
+            layout: BindGroupLayoutDescriptor::new("fft_convolution_bind_group_layout", &entries),
+            pipelines: HashMap::default(),
+        }
+    }
+}
+
+impl FftConvolutionPipelines {
+    /// `fft_convolve_spectrum` for the workspace format of `settings`, once compiled.
+    fn pipeline<'a>(
+        &self,
+        fft_pipelines: &FftPipelines,
+        pipeline_cache: &'a PipelineCache,
+        settings: &FftSettings,
+    ) -> Option<&'a ComputePipeline> {
+        let id = self.pipelines.get(&fft_pipelines.format(settings))?;
+        pipeline_cache.get_compute_pipeline(*id)
+    }
+}
+
+pub(super) fn prepare_fft_convolution_pipelines(
+    mut convolution: ResMut<FftConvolutionPipelines>,
+    fft_pipelines: Res<FftPipelines>,
+    pipeline_cache: Res<PipelineCache>,
+    query: Query<&FftSettings, With<FftConvolutionKernel>>,
+) {
+    let convolution = &mut *convolution;
+    for settings in &query {
+        let format = fft_pipelines.format(settings);
+        if format.volume {
+            continue;
+        }
+        convolution.pipelines.entry(format).or_insert_with(|| {
+            pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
+                label: Some("fft_convolve_spectrum_pipeline".into()),
+                layout: vec![format.layout(), convolution.layout.clone()],
+                push_constant_ranges: vec![],
+                shader: shaders::CONVOLUTION.clone(),
+                shader_defs: format.shader_defs(),
+                entry_point: Some("fft_convolve_spectrum".into()),
+                zero_initialize_workgroup_memory: false,
+            })
+        });
+    }
+}
+
+/// Kernel spectrum of an [`FftConvolutionKernel`] uploaded to the GPU.
+#[derive(Component)]
+pub(super) struct FftConvolutionBindGroup {
+    group: BindGroup,
+    spectrum: Arc<[u8]>,
+}
+
+/// Uploads kernel spectra whenever the main world rebuilds them.
+pub(super) fn prepare_fft_convolution_bind_groups(
+    mut commands: Commands,
+    device: Res<RenderDevice>,
+    pipeline_cache: Res<PipelineCache>,
+    pipelines: Res<FftConvolutionPipelines>,
+    query: Query<(
+        Entity,
+        Option<&FftConvolutionKernel>,
+        Option<&FftConvolutionBindGroup>,
+    )>,
+) {
+    for (entity, kernel, existing) in &query {
+        let Some(spectrum) = kernel.and_then(|kernel| kernel.spectrum.as_ref()) else {
+            if existing.is_some() {
+                commands.entity(entity).remove::<FftConvolutionBindGroup>();
+            }
+            continue;
+        };
+        if existing.is_some_and(|existing| Arc::ptr_eq(&existing.spectrum, spectrum)) {
+            continue;
+        }
+        let buffer = device.create_buffer_with_data(&BufferInitDescriptor {
+            label: Some("fft_convolution_kernel_spectrum"),
+            contents: spectrum,
+            usage: BufferUsages::STORAGE,
+        });
+        let group = device.create_bind_group(
+            "fft_convolution_bind_group",
+            &pipeline_cache.get_bind_group_layout(&pipelines.layout),
+            &BindGroupEntries::single(buffer.as_entire_binding()),
+        );
+        commands.entity(entity).insert(FftConvolutionBindGroup {
+            group,
+            spectrum: spectrum.clone(),
+        });
+    }
+}
+
+fn dispatch_fft_convolution(
+    pass: &mut ComputePass<'_>,
+    pipeline: &ComputePipeline,
+    bind: &FftBindGroups,
+    kernel: &BindGroup,
+    settings: &FftSettings,
+) {
+    let wg = 16u32;
+    pass.set_pipeline(pipeline);
+    bind.set(pass, 0);
+    pass.set_bind_group(1, kernel, &[]);
+    let nx = settings.size.x.div_ceil(wg);
+    let ny = settings.size.y.div_ceil(wg);
+    pass.dispatch_workgroups(nx, ny, settings.layers());
+}
+
+/// Multiplies **C** of the render-world `entity` by its [`FftConvolution`] kernel within `pass`,
+/// for nodes that drive [`run_forward_fft`](super::run_forward_fft) and
+/// [`run_inverse_fft`](super::run_inverse_fft) themselves. Returns `false` without recording
+/// anything while the kernel spectrum or its pipeline is not ready.
+pub(crate) fn record_fft_convolution(
+    world: &World,
+    pass: &mut ComputePass<'_>,
+    entity: Entity,
+    bind: &FftBindGroups,
+    settings: &FftSettings,
+) -> bool {
+    let Some(kernel) = world.get::<FftConvolutionBindGroup>(entity) else {
+        return false;
+    };
+    let Some(pipeline) = world.resource::<FftConvolutionPipelines>().pipeline(
+        world.resource(),
+        world.resource(),
+        settings,
+    ) else {
+        return false;
+    };
+    dispatch_fft_convolution(pass, pipeline, bind, &kernel.group, settings);
+    true
+}
+
+type FftConvolutionNodeQuery = (
+    Entity,
+    &'static MainEntity,
+    &'static FftBindGroups,
+    &'static FftSettings,
+    Option<&'static FftConvolutionKernel>,
+    Option<&'static FftConvolutionBindGroup>,
+    Option<&'static FftTriggered>,
+);
+
+/// Runs at [`FftNode::Convolve`](super::FftNode::Convolve).
+pub(super) struct FftConvolutionNode {
+    query: QueryState<FftConvolutionNodeQuery, With<FftConvolution>>,
+}
+
+impl FromWorld for FftConvolutionNode {
+    fn from_world(world: &mut World) -> Self {
+        Self {
+            query: world.query_filtered(),
+        }
+    }
+}
+
+impl Node for FftConvolutionNode {
+    fn update(&mut self, world: &mut World) {
+        self.query.update_archetypes(world);
+    }
+
+    fn run(
+        &self,
+        _graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        let fft_pipelines = world.resource::<FftPipelines>();
+        let pipelines = world.resource::<FftConvolutionPipelines>();
+        let pipeline_cache = world.resource::<PipelineCache>();
+        let skipped = world.resource::<FftSkippedRuns>();
+        let diagnostics = render_context.diagnostic_recorder();
+
+        let command_encoder = render_context.command_encoder();
+
+        for (entity, main_entity, bind_groups, settings, kernel, kernel_group, triggered) in
+            self.query.iter_manual(world)
+        {
+            if FftTriggered::is_idle(triggered)
+                || kernel.is_some_and(|kernel| kernel.spectrum.is_none())
+            {
+                continue;
+            }
+            let pipeline = pipelines.pipeline(fft_pipelines, pipeline_cache, settings);
+            let (Some(pipeline), Some(kernel_group)) = (pipeline, kernel_group) else {
+                // The kernel is still loading or compiling; the trigger runs the entity again.
+                skipped.push(entity);
+                continue;
+            };
+            let mut compute_pass = command_encoder.begin_compute_pass(&ComputePassDescriptor {
+                label: Some("fft_convolution_pass"),
+                timestamp_writes: None,
+            });
+            let span = diagnostics.pass_span(
+                &mut compute_pass,
+                FftTimedPass::Convolve.span(main_entity.id()),
+            );
+            dispatch_fft_convolution(
+                &mut compute_pass,
+                pipeline,
+                bind_groups,
+                &kernel_group.group,
+                settings,
+            );
+            span.end(&mut compute_pass);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        asset::RenderAssetUsages,
+        image::Image,
+        math::UVec2,
+        render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+    };
+
+    use super::{FftConvolutionMode, check_kernel, kernel_spectrum};
+    use crate::{
+        complex::c32,
+        fft::{FftDimension, FftNormalization, FftSettings, FftSource, cpu},
+    };
+
+    fn kernel_image(size: UVec2) -> Image {
+        Image::new_fill(
+            Extent3d {
+                width: size.x,
+                height: size.y,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            &[0; 16],
+            TextureFormat::Rgba32Float,
+            RenderAssetUsages::all(),
+        )
+    }
+
+    #[test]
+    fn spectral_product_matches_centred_circular_convolution() {
+        let (size, kernel_size, channels) = (UVec2::new(8, 6), UVec2::new(3, 2), 2);
+        let kernel: Vec<f32> = (0..kernel_size.x * kernel_size.y * 4)
+            .map(|i| (i as f32 * 0.7).cos())
+            .collect();
+        for normalization in [
+            FftNormalization::Backward,
+            FftNormalization::Ortho,
+            FftNormalization::None,
+        ] {
+            let settings = FftSettings::from_fft_source(&FftSource {
+                channels,
+                normalization,
+                ..FftSource::forward_then_inverse(size)
+            });
+            let signal: Vec<c32> = (0..cpu::workspace_len(&settings))
+                .map(|i| c32::new((i as f32 * 0.37).sin(), 0.0))
+                .collect();
+
+            let mut convolved = signal.clone();
+            cpu::forward(&settings, &mut convolved);
+            let spectrum = kernel_spectrum(&settings, &kernel, kernel_size);
+            for (bin, k) in convolved.iter_mut().zip(&spectrum) {
+                *bin *= *k;
+            }
+            cpu::inverse(&settings, &mut convolved);
+
+            let (w, h) = (size.x as i32, size.y as i32);
+            let (kw, kh) = (kernel_size.x as i32, kernel_size.y as i32);
+            for (y, x, lane) in
+                (0..h).flat_map(|y| (0..w).flat_map(move |x| (0..2).map(move |c| (y, x, c))))
+            {
+                let mut expected = 0.0;
+                for ky in 0..kh {
+                    for kx in 0..kw {
+                        let sx = (x - (kx - kw / 2)).rem_euclid(w);
+                        let sy = (y - (ky - kh / 2)).rem_euclid(h);
+                        expected += kernel[((ky * kw + kx) * 4 + lane) as usize]
+                            * signal[((sy * w + sx) * 2 + lane) as usize].re;
+                    }
+                }
+                let actual = convolved[((y * w + x) * 2 + lane) as usize];
+                assert!(
+                    (actual.re - expected).abs() < 1e-3 && actual.im.abs() < 1e-3,
+                    "{normalization:?} at ({x}, {y}) lane {lane}: expected {expected}, got {actual}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn linear_mode_needs_half_the_kernel_as_padding() {
+        let kernel = kernel_image(UVec2::new(5, 3));
+        let source = FftSource::forward_then_inverse(UVec2::new(16, 16));
+        let unpadded = FftSettings::from_fft_source(&source);
+        assert!(check_kernel(&unpadded, FftConvolutionMode::Circular, &kernel).is_ok());
+        assert!(check_kernel(&unpadded, FftConvolutionMode::Linear, &kernel).is_err());
+
+        let padded =
+            FftSettings::from_fft_source(&source.with_convolution_padding(UVec2::new(5, 3)));
+        assert_eq!(
+            check_kernel(&padded, FftConvolutionMode::Linear, &kernel),
+            Ok(UVec2::new(5, 3))
+        );
+    }
+
+    #[test]
+    fn row_kernels_filter_every_row_and_keep_alpha() {
+        let size = UVec2::new(8, 3);
+        let settings = FftSettings::from_fft_source(&FftSource {
+            dimension: FftDimension::OneD,
+            ..FftSource::forward_then_inverse(size)
+        });
+        assert!(
+            check_kernel(
+                &settings,
+                FftConvolutionMode::Circular,
+                &kernel_image(UVec2::new(3, 2))
+            )
+            .is_err()
+        );
+
+        // A one-texel shift to the right: the centre of a 3-wide kernel is texel 1.
+        let mut kernel = vec![0.0; 3 * 4];
+        kernel[2 * 4..2 * 4 + 3].fill(1.0);
+        let spectrum = kernel_spectrum(&settings, &kernel, UVec2::new(3, 1));
+        let mut data: Vec<c32> = (0..cpu::workspace_len(&settings))
+            .map(|i| c32::new(i as f32, 0.0))
+            .collect();
+        let input = data.clone();
+        cpu::forward(&settings, &mut data);
+        for (bin, k) in data.iter_mut().zip(&spectrum) {
+            *bin *= *k;
+        }
+        cpu::inverse(&settings, &mut data);
+        for y in 0..3 {
+            for x in 0..8 {
+                let texel = (y * 8 + x) * 4;
+                let shifted = (y * 8 + (x + 7) % 8) * 4;
+                for lane in 0..3 {
+                    assert!((data[texel + lane].re - input[shifted + lane].re).abs() < 1e-3);
+                }
+            }
+        }
+        assert!(
+            spectrum
+                .chunks_exact(4)
+                .all(|texel| texel[3] == c32::new(1.0, 0.0))
+        );
+    }
+}
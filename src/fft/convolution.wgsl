@@ -0,0 +1,41 @@
+#import bevy_fft::{
+    complex::{
+        mul_c32_n,
+        c32,
+        c32_2,
+        c32_3,
+        c32_4,
+    },
+    bindings::settings,
+    buffer::{
+        read_buffer_c,
+        write_buffer_c,
+        fft_lanes,
+    },
+}
+
+#if CHANNELS == 1
+    alias c32_n = c32;
+#else if CHANNELS == 2
+    alias c32_n = c32_2;
+#else if CHANNELS == 3
+    alias c32_n = c32_3;
+#else if CHANNELS == 4
+    alias c32_n = c32_4;
+#endif
+
+// Kernel spectrum of `FftConvolution` for one grid: a real then an imaginary `vec4<f32>` per bin,
+// row-major over the workspace. Layers share it.
+@group(1) @binding(0) var<storage, read> kernel_spectrum: array<vec4<f32>>;
+
+// Multiplies spectrum **C** by the kernel spectrum, bin by bin.
+@compute
+@workgroup_size(16, 16, 1)
+fn fft_convolve_spectrum(@builtin(global_invocation_id) gid: vec3<u32>) {
+    if (gid.x >= settings.size.x || gid.y >= settings.size.y) {
+        return;
+    }
+    let i = (gid.y * settings.size.x + gid.x) * 2u;
+    let kernel = c32_n(fft_lanes(kernel_spectrum[i]), fft_lanes(kernel_spectrum[i + 1u]));
+    write_buffer_c(gid, mul_c32_n(read_buffer_c(gid), kernel));
+}
@@ -0,0 +1,292 @@
+//! CPU reference for the stock transform. [`forward`] and [`inverse`] leave the same bins as
+//! [`run_forward_fft`](super::run_forward_fft) and [`run_inverse_fft`](super::run_inverse_fft):
+//! DC at index `(0, 0)` followed by positive then wrapped negative frequencies, the
+//! [`FftNormalization`] and window of the [`FftSettings`], and the texel layout of buffers
+//! **A**–**C**. Use it where no GPU is available, to build spectra offline, or as the oracle for
+//! GPU results read back through [`FftReadback`](super::FftReadback).
+//!
+//! Lines are transformed in f64: power-of-two axes with radix-2, others with Bluestein's
+//! algorithm, so results agree with the GPU up to its f32 rounding.
+
+use bevy::math::UVec3;
+
+use super::{
+    FftNormalization, FftSettings, FftWindow,
+    bluestein::{chirp, chirp_spectrum, fft_in_place},
+};
+use crate::complex::c32;
+
+/// Samples [`forward`] and [`inverse`] expect: every workspace texel of `settings`, times its
+/// channel count. Texels are row-major with `x` fastest, then `y`, then volume depth or layer,
+/// and lane `k` of texel `i` sits at `i * channels + k`.
+pub fn workspace_len(settings: &FftSettings) -> usize {
+    let extent = workspace_extent(settings);
+    (extent.x * extent.y * extent.z * settings.channels) as usize
+}
+
+/// Forward transform in place, from what buffer **A** holds to what [`super::run_forward_fft`]
+/// leaves in **C**. Like the GPU, four-channel sources come out with lane 3 set to `1 + 1i`,
+/// which keeps resolved alpha opaque.
+///
+/// # Panics
+///
+/// When `data.len()` differs from [`workspace_len`].
+pub fn forward(settings: &FftSettings, data: &mut [c32]) {
+    transform(settings, data, false);
+    if settings.channels == 4 {
+        for texel in data.chunks_exact_mut(4) {
+            texel[3] = c32::new(1.0, 1.0);
+        }
+    }
+}
+
+/// Inverse transform in place, from a spectrum in buffer **C** to what
+/// [`super::run_inverse_fft`] leaves in **B**.
+///
+/// # Panics
+///
+/// When `data.len()` differs from [`workspace_len`].
+pub fn inverse(settings: &FftSettings, data: &mut [c32]) {
+    transform(settings, data, true);
+}
+
+fn workspace_extent(settings: &FftSettings) -> UVec3 {
+    settings.size.extend(settings.depth)
+}
+
+fn transform(settings: &FftSettings, data: &mut [c32], inverse: bool) {
+    assert_eq!(
+        data.len(),
+        workspace_len(settings),
+        "cpu FFT input does not match the workspace of its settings"
+    );
+    let extent = workspace_extent(settings);
+    let channels = settings.channels as usize;
+    let normalization =
+        FftNormalization::try_from_bits(settings.fft_normalization).unwrap_or_default();
+    let window =
+        FftWindow::try_from_bits(settings.window_type, settings.window_param).unwrap_or_default();
+    let signal = (settings.size - settings.padding).extend(settings.depth);
+    let strides = [
+        channels,
+        channels * extent.x as usize,
+        channels * (extent.x * extent.y) as usize,
+    ];
+
+    for axis in 0..settings.transformed_axes() as usize {
+        let n = extent[axis];
+        let line_dft = LineDft::new(n);
+        let (forward_scale, inverse_scale) = normalization.scales(n);
+        let scale = f64::from(if inverse {
+            inverse_scale
+        } else {
+            forward_scale
+        });
+        let windowed = !inverse && settings.window_axes & (1 << axis) != 0;
+        let weights: Vec<f64> = (0..n)
+            .map(|i| {
+                if windowed {
+                    let w = window.weight(i, signal[axis]);
+                    f64::from((1.0 - settings.window_strength) + settings.window_strength * w)
+                } else {
+                    1.0
+                }
+            })
+            .collect();
+
+        // The inverse runs as conj(DFT(conj(X))), as the GPU kernels do.
+        let sign = if inverse { -1.0 } else { 1.0 };
+        let mut cross = extent;
+        cross[axis] = 1;
+        let mut line = vec![(0.0, 0.0); n as usize];
+        for z in 0..cross.z {
+            for y in 0..cross.y {
+                for x in 0..cross.x {
+                    let base =
+                        x as usize * strides[0] + y as usize * strides[1] + z as usize * strides[2];
+                    for lane in 0..channels {
+                        let at = |i: usize| base + i * strides[axis] + lane;
+                        for (i, v) in line.iter_mut().enumerate() {
+                            let s = data[at(i)];
+                            let w = weights[i];
+                            *v = (f64::from(s.re) * w, sign * f64::from(s.im) * w);
+                        }
+                        line_dft.apply(&mut line);
+                        for (i, &(re, im)) in line.iter().enumerate() {
+                            data[at(i)] = c32::new((re * scale) as f32, (sign * im * scale) as f32);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Forward DFT of one line length: radix-2 for powers of two, Bluestein otherwise.
+enum LineDft {
+    Radix2,
+    Bluestein {
+        /// `exp(-iπ m² / n)` for the first `n` samples.
+        chirp: Vec<(f64, f64)>,
+        /// Forward transform of the conjugate chirp wrapped to the padded length.
+        kernel: Vec<(f64, f64)>,
+    },
+}
+
+impl LineDft {
+    fn new(n: u32) -> Self {
+        if n.is_power_of_two() {
+            return Self::Radix2;
+        }
+        let m = (2 * n - 1).next_power_of_two() as usize;
+        let chirp = (0..n).map(|i| chirp(i, n)).collect();
+        let kernel = chirp_spectrum(n, m);
+        Self::Bluestein { chirp, kernel }
+    }
+
+    fn apply(&self, line: &mut [(f64, f64)]) {
+        let Self::Bluestein { chirp, kernel } = self else {
+            fft_in_place(line);
+            return;
+        };
+        let mul =
+            |(ar, ai): (f64, f64), (br, bi): (f64, f64)| (ar * br - ai * bi, ar * bi + ai * br);
+        let m = kernel.len();
+        let mut padded = vec![(0.0, 0.0); m];
+        for ((p, &x), &c) in padded.iter_mut().zip(line.iter()).zip(chirp) {
+            *p = mul(x, c);
+        }
+        fft_in_place(&mut padded);
+        // Circular convolution with the kernel: multiply, then inverse through conjugation.
+        for (p, &k) in padded.iter_mut().zip(kernel) {
+            let (re, im) = mul(*p, k);
+            *p = (re, -im);
+        }
+        fft_in_place(&mut padded);
+        let inv_m = 1.0 / m as f64;
+        for ((x, &p), &c) in line.iter_mut().zip(&padded).zip(chirp) {
+            *x = mul((p.0 * inv_m, -p.1 * inv_m), c);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{forward, inverse, workspace_len};
+    use crate::{
+        complex::c32,
+        fft::{FftDimension, FftNormalization, FftSettings, FftSource},
+    };
+    use bevy::math::UVec2;
+
+    fn settings(size: UVec2, channels: u32, normalization: FftNormalization) -> FftSettings {
+        FftSettings::from_fft_source(&FftSource {
+            channels,
+            normalization,
+            ..FftSource::forward_then_inverse(size)
+        })
+    }
+
+    fn signal(len: usize) -> Vec<c32> {
+        (0..len)
+            .map(|i| c32::new((i as f32 * 0.37).sin(), (i as f32 * 1.3).cos() * 0.5))
+            .collect()
+    }
+
+    fn naive_dft_2d(input: &[c32], size: UVec2, channels: usize) -> Vec<c32> {
+        let (w, h) = (size.x as usize, size.y as usize);
+        let mut out = vec![c32::new(0.0, 0.0); input.len()];
+        for (ky, kx, lane) in
+            (0..h).flat_map(|y| (0..w).flat_map(move |x| (0..channels).map(move |c| (y, x, c))))
+        {
+            let (mut re, mut im) = (0.0f64, 0.0f64);
+            for y in 0..h {
+                for x in 0..w {
+                    let s = input[(y * w + x) * channels + lane];
+                    let theta = -std::f64::consts::TAU
+                        * (((kx * x) % w) as f64 / w as f64 + ((ky * y) % h) as f64 / h as f64);
+                    let (sin, cos) = theta.sin_cos();
+                    re += f64::from(s.re) * cos - f64::from(s.im) * sin;
+                    im += f64::from(s.re) * sin + f64::from(s.im) * cos;
+                }
+            }
+            out[(ky * w + kx) * channels + lane] = c32::new(re as f32, im as f32);
+        }
+        out
+    }
+
+    fn assert_close(expected: &[c32], actual: &[c32], tolerance: f32) {
+        for (i, (e, a)) in expected.iter().zip(actual).enumerate() {
+            assert!(
+                (e.re - a.re).abs() < tolerance && (e.im - a.im).abs() < tolerance,
+                "sample {i}: expected {e}, got {a}"
+            );
+        }
+    }
+
+    #[test]
+    fn forward_matches_naive_dft_on_mixed_edges() {
+        // An 8-sample power-of-two axis and a 6-sample Bluestein axis, two lanes per texel.
+        let size = UVec2::new(8, 6);
+        let settings = settings(size, 2, FftNormalization::Backward);
+        let input = signal(workspace_len(&settings));
+        let mut data = input.clone();
+        forward(&settings, &mut data);
+        assert_close(&naive_dft_2d(&input, size, 2), &data, 1e-3);
+    }
+
+    #[test]
+    fn round_trip_follows_normalization() {
+        let size = UVec2::new(5, 4);
+        for (normalization, gain) in [
+            (FftNormalization::Backward, 1.0),
+            (FftNormalization::Forward, 1.0),
+            (FftNormalization::Ortho, 1.0),
+            (FftNormalization::None, 20.0),
+        ] {
+            let settings = settings(size, 1, normalization);
+            let input = signal(workspace_len(&settings));
+            let mut data = input.clone();
+            forward(&settings, &mut data);
+            inverse(&settings, &mut data);
+            let expected: Vec<c32> = input.iter().map(|&s| s * gain).collect();
+            assert_close(&expected, &data, 1e-3 * gain);
+        }
+    }
+
+    #[test]
+    fn layers_transform_independently() {
+        let size = UVec2::new(6, 4);
+        let single = settings(size, 2, FftNormalization::Backward);
+        let layered = FftSettings::from_fft_source(&FftSource {
+            channels: 2,
+            ..FftSource::forward_then_inverse(size).with_layers(3)
+        });
+        let input = signal(workspace_len(&layered));
+        let mut data = input.clone();
+        forward(&layered, &mut data);
+        let layer_len = workspace_len(&single);
+        for (expected, actual) in input
+            .chunks_exact(layer_len)
+            .zip(data.chunks_exact(layer_len))
+        {
+            let mut expected = expected.to_vec();
+            forward(&single, &mut expected);
+            assert_close(&expected, actual, 1e-5);
+        }
+    }
+
+    #[test]
+    fn rows_only_and_opaque_alpha_match_the_gpu_layout() {
+        let size = UVec2::new(4, 2);
+        let mut settings = settings(size, 4, FftNormalization::Backward);
+        settings.dimension = FftDimension::OneD.to_bits();
+        let mut data = vec![c32::new(1.0, 0.0); workspace_len(&settings)];
+        forward(&settings, &mut data);
+        for (texel, lanes) in data.chunks_exact(4).enumerate() {
+            let dc = if texel % 4 == 0 { 4.0 } else { 0.0 };
+            assert_close(&[c32::new(dc, 0.0); 3], &lanes[..3], 1e-5);
+            assert_eq!(lanes[3], c32::new(1.0, 1.0));
+        }
+    }
+}
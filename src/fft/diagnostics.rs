@@ -0,0 +1,145 @@
+//! GPU timings of the stock FFT passes, recorded as Bevy render diagnostics.
+//!
+//! Add [`FftDiagnosticsPlugin`] after [`FftPlugin`](super::FftPlugin). It adds Bevy's
+//! [`RenderDiagnosticsPlugin`], and every pass the stock graph records for an [`FftSource`]
+//! entity opens a [`RecordDiagnostics::pass_span`] named by [`FftTimedPass::span`]. The timings
+//! land in the [`DiagnosticsStore`](bevy::diagnostic::DiagnosticsStore) a frame or two later,
+//! under [`FftDiagnosticsPlugin::path`]. Spliced spectrum passes opt in by opening a span named
+//! [`FftTimedPass::Spectrum`] themselves; every other node, from the phase-correlation input to
+//! the resolve passes, times each entity under its own [`FftTimedPass`].
+//!
+//! GPU time needs timestamp queries inside passes (Vulkan and DX12); elsewhere Bevy records CPU
+//! time only, under `elapsed_cpu`.
+//!
+//! [`FftSource`]: super::FftSource
+//! [`RecordDiagnostics::pass_span`]: bevy::render::diagnostic::RecordDiagnostics::pass_span
+
+use bevy::{
+    diagnostic::{DiagnosticPath, DiagnosticsStore},
+    prelude::*,
+    render::diagnostic::RenderDiagnosticsPlugin,
+};
+
+/// Stage of the FFT graph an entity's timing covers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FftTimedPass {
+    /// [`FftNode::PhaseCorrelationInput`](super::FftNode::PhaseCorrelationInput), for
+    /// [`FftPhaseCorrelation`] entities.
+    ///
+    /// [`FftPhaseCorrelation`]: super::FftPhaseCorrelation
+    PhaseCorrelationInput,
+    /// [`FftNode::ComputeFFT`](super::FftNode::ComputeFFT).
+    Forward,
+    /// The pass spliced at [`FftNode::SpectrumPass`](super::FftNode::SpectrumPass), when it
+    /// opens a span named by [`Self::span`].
+    Spectrum,
+    /// [`FftNode::Convolve`](super::FftNode::Convolve), for [`FftConvolution`] entities.
+    ///
+    /// [`FftConvolution`]: super::FftConvolution
+    Convolve,
+    /// [`FftNode::Filter`](super::FftNode::Filter), for [`FftFilter`] entities.
+    ///
+    /// [`FftFilter`]: super::FftFilter
+    Filter,
+    /// [`FftNode::CrossPowerSpectrum`](super::FftNode::CrossPowerSpectrum).
+    CrossPowerSpectrum,
+    /// [`FftNode::ResolveSpectrum`](super::FftNode::ResolveSpectrum).
+    ResolveSpectrum,
+    /// [`FftNode::ComputeIFFT`](super::FftNode::ComputeIFFT).
+    Inverse,
+    /// [`FftNode::CorrelationPeak`](super::FftNode::CorrelationPeak).
+    CorrelationPeak,
+    /// [`FftNode::ResolveOutputs`](super::FftNode::ResolveOutputs).
+    ResolveOutputs,
+}
+
+impl FftTimedPass {
+    /// Every pass, in graph order.
+    pub const ALL: [Self; 10] = [
+        Self::PhaseCorrelationInput,
+        Self::Forward,
+        Self::Spectrum,
+        Self::Convolve,
+        Self::Filter,
+        Self::CrossPowerSpectrum,
+        Self::ResolveSpectrum,
+        Self::Inverse,
+        Self::CorrelationPeak,
+        Self::ResolveOutputs,
+    ];
+
+    /// Last component of the pass's span name.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::PhaseCorrelationInput => "phase_correlation_input",
+            Self::Forward => "forward",
+            Self::Spectrum => "spectrum",
+            Self::Convolve => "convolve",
+            Self::Filter => "filter",
+            Self::CrossPowerSpectrum => "cross_power_spectrum",
+            Self::ResolveSpectrum => "resolve_spectrum",
+            Self::Inverse => "inverse",
+            Self::CorrelationPeak => "correlation_peak",
+            Self::ResolveOutputs => "resolve_outputs",
+        }
+    }
+
+    /// `fft/<entity>/<pass>`, the name to pass to
+    /// [`RecordDiagnostics::pass_span`](bevy::render::diagnostic::RecordDiagnostics::pass_span)
+    /// for the main-world `entity`
+    /// ([`MainEntity::id`](bevy::render::sync_world::MainEntity::id)).
+    pub fn span(self, entity: Entity) -> String {
+        format!("fft/{entity}/{}", self.name())
+    }
+}
+
+/// Times the FFT passes through Bevy's [`RenderDiagnosticsPlugin`], which it adds when missing.
+#[derive(Default)]
+pub struct FftDiagnosticsPlugin;
+
+impl FftDiagnosticsPlugin {
+    /// `render/fft/<entity>/<pass>/elapsed_gpu`, in milliseconds.
+    pub fn path(entity: Entity, pass: FftTimedPass) -> DiagnosticPath {
+        DiagnosticPath::from_components([
+            "render",
+            "fft",
+            &entity.to_string(),
+            pass.name(),
+            "elapsed_gpu",
+        ])
+    }
+}
+
+impl Plugin for FftDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DiagnosticsStore>();
+        if !app.is_plugin_added::<RenderDiagnosticsPlugin>() {
+            app.add_plugins(RenderDiagnosticsPlugin);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paths_follow_the_span_names() {
+        let entity = Entity::from_raw_u32(7).unwrap();
+        for pass in FftTimedPass::ALL {
+            let path = FftDiagnosticsPlugin::path(entity, pass);
+            assert_eq!(
+                path.as_str(),
+                format!("render/{}/elapsed_gpu", pass.span(entity))
+            );
+        }
+        assert_eq!(
+            FftDiagnosticsPlugin::path(entity, FftTimedPass::Inverse).as_str(),
+            format!("render/fft/{entity}/inverse/elapsed_gpu")
+        );
+        assert_eq!(
+            FftDiagnosticsPlugin::path(entity, FftTimedPass::ResolveOutputs).as_str(),
+            format!("render/fft/{entity}/resolve_outputs/elapsed_gpu")
+        );
+    }
+}
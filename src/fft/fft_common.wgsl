@@ -19,3 +19,18 @@ fn get_fft_root(stage_plus_one: u32, index: u32) -> c32 {
     let i = base + index % count;
     return roots_buffer.roots[i];
 }
+
+// `exp(-2πi·m / 2^order)` for any `m < 2^order`. The table only stores the first half of each
+// base, so the upper half folds back with a sign flip (`exp(-iπ) = -1`).
+fn get_fft_twiddle(order: u32, m: u32) -> c32 {
+    if (order == 0u) {
+        return c32(1.0, 0.0);
+    }
+    let base = 1u << order;
+    let half = base >> 1u;
+    if (m < half) {
+        return roots_buffer.roots[base + m];
+    }
+    let r = roots_buffer.roots[base + m - half];
+    return c32(-r.re, -r.im);
+}
@@ -0,0 +1,460 @@
+//! Stock spectrum filters, attachable to an [`FftSource`] entity as an [`FftFilter`].
+//!
+//! The render world multiplies buffer **C** by the filter's response at
+//! [`FftNode::Filter`](super::FftNode::Filter), after [`FftConvolution`](super::FftConvolution)
+//! and before the spectrum is resolved, so both the power spectrum and the inverse transform see
+//! the filtered signal. Bins map to frequencies with the stock layout: DC at `(0, 0)`, then
+//! positive and wrapped negative frequencies, as [`FftFilter::bin_frequency`] spells out.
+
+use std::f32::consts::PI;
+
+use bevy::{
+    ecs::{
+        query::{QueryItem, QueryState},
+        system::lifetimeless::Read,
+    },
+    platform::collections::HashMap,
+    prelude::*,
+    render::{
+        diagnostic::RecordDiagnostics,
+        extract_component::{ComponentUniforms, DynamicUniformIndex, ExtractComponent},
+        render_graph::{Node, NodeRunError, RenderGraphContext},
+        render_resource::{binding_types::uniform_buffer, *},
+        renderer::{RenderContext, RenderDevice},
+        sync_world::MainEntity,
+    },
+};
+
+use super::{
+    FftRunRequest, FftSettings, FftSource, FftTrigger,
+    diagnostics::FftTimedPass,
+    resources::{FftBindGroups, FftPipelines, FftWorkspaceFormat},
+    shaders,
+    trigger::{FftSkippedRuns, FftTriggered},
+};
+
+/// Which frequencies an [`FftFilter`] keeps. Radii are in cycles per sample, so the Nyquist
+/// frequency of each axis is `0.5`; angles are in radians from `+x` towards `+y`.
+#[derive(Clone, Copy, Debug, PartialEq, Reflect)]
+pub enum FftFilterKind {
+    /// Keeps frequencies below `cutoff`.
+    LowPass { cutoff: f32 },
+    /// Keeps frequencies above `cutoff`.
+    HighPass { cutoff: f32 },
+    /// Keeps the ring of radius `center` and full width `width`.
+    BandPass { center: f32, width: f32 },
+    /// Removes the ring of radius `center` and full width `width`.
+    BandStop { center: f32, width: f32 },
+    /// Keeps frequencies within `half_width` of the orientation `angle`, and DC. A real signal's
+    /// spectrum is symmetric, so the wedge opposite `angle` is kept as well.
+    Wedge { angle: f32, half_width: f32 },
+    /// Removes the frequencies within `radius` of `frequency` and of its mirror `-frequency`,
+    /// such as the peaks of a periodic pattern.
+    Notch { frequency: Vec2, radius: f32 },
+}
+
+/// How an [`FftFilter`] moves from its pass to its stop region.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, Reflect)]
+pub enum FftFilterProfile {
+    /// Brick wall at the edge, which rings in the spatial domain.
+    Ideal,
+    /// Gaussian falloff with the edge one standard deviation out; does not ring.
+    #[default]
+    Gaussian,
+    /// Butterworth falloff of the given order, at half gain on the edge. Higher orders approach
+    /// [`Self::Ideal`].
+    Butterworth(u32),
+}
+
+impl FftFilterProfile {
+    /// Gain at `x`, the distance into the stop region in units of the filter's edge: one at
+    /// `x = 0`, falling towards zero past `x = 1`.
+    fn falloff(self, x: f32) -> f32 {
+        match self {
+            Self::Ideal => {
+                if x <= 1.0 {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            Self::Gaussian => (-0.5 * x * x).exp(),
+            Self::Butterworth(order) => 1.0 / (1.0 + x.powi(2 * order.max(1) as i32)),
+        }
+    }
+
+    fn to_bits(self) -> (u32, u32) {
+        match self {
+            Self::Ideal => (0, 0),
+            Self::Gaussian => (1, 0),
+            Self::Butterworth(order) => (2, order.max(1)),
+        }
+    }
+}
+
+/// Multiplies the entity's spectrum by a stock frequency response between the forward and inverse
+/// transforms, on every channel but the alpha lane of four-channel sources.
+///
+/// [`FftDimension::OneD`](super::FftDimension::OneD) batches filter each row along `x`. Volumes
+/// are not filtered.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Reflect)]
+pub struct FftFilter {
+    pub kind: FftFilterKind,
+    pub profile: FftFilterProfile,
+}
+
+impl FftFilter {
+    pub fn ideal(kind: FftFilterKind) -> Self {
+        Self {
+            kind,
+            profile: FftFilterProfile::Ideal,
+        }
+    }
+
+    pub fn gaussian(kind: FftFilterKind) -> Self {
+        Self {
+            kind,
+            profile: FftFilterProfile::Gaussian,
+        }
+    }
+
+    pub fn butterworth(kind: FftFilterKind, order: u32) -> Self {
+        Self {
+            kind,
+            profile: FftFilterProfile::Butterworth(order),
+        }
+    }
+
+    /// Frequency in cycles per sample of `bin` in a spectrum of `size` bins: indices past the
+    /// middle of an axis wrap to negative frequencies.
+    pub fn bin_frequency(bin: UVec2, size: UVec2) -> Vec2 {
+        let axis = |i: u32, n: u32| {
+            let k = if i > n / 2 {
+                i as f32 - n as f32
+            } else {
+                i as f32
+            };
+            k / n as f32
+        };
+        Vec2::new(axis(bin.x, size.x), axis(bin.y, size.y))
+    }
+
+    /// Gain at `frequency` in cycles per sample. Mirrors `fft_filter_response` in `filter.wgsl`.
+    pub fn response(&self, frequency: Vec2) -> f32 {
+        let falloff = |distance: f32, edge: f32| self.profile.falloff(distance / edge.max(1e-6));
+        let radius = frequency.length();
+        match self.kind {
+            FftFilterKind::LowPass { cutoff } => falloff(radius, cutoff),
+            FftFilterKind::HighPass { cutoff } => 1.0 - falloff(radius, cutoff),
+            FftFilterKind::BandPass { center, width } => {
+                falloff((radius - center).abs(), 0.5 * width)
+            }
+            FftFilterKind::BandStop { center, width } => {
+                1.0 - falloff((radius - center).abs(), 0.5 * width)
+            }
+            FftFilterKind::Wedge { angle, half_width } => {
+                if radius == 0.0 {
+                    return 1.0;
+                }
+                let offset = (frequency.to_angle() - angle).rem_euclid(PI);
+                falloff(offset.min(PI - offset), half_width)
+            }
+            FftFilterKind::Notch {
+                frequency: notch,
+                radius: edge,
+            } => {
+                (1.0 - falloff(frequency.distance(notch), edge))
+                    * (1.0 - falloff(frequency.distance(-notch), edge))
+            }
+        }
+    }
+}
+
+/// Render-world [`FftFilter`], as `filter.wgsl` reads it.
+#[derive(Component, ShaderType, Clone, Copy, Debug)]
+pub struct FftFilterUniform {
+    kind: u32,
+    profile: u32,
+    order: u32,
+    /// The kind's fields in declaration order, `Notch` with its frequency first.
+    params: Vec4,
+}
+
+impl From<&FftFilter> for FftFilterUniform {
+    fn from(filter: &FftFilter) -> Self {
+        let (kind, params) = match filter.kind {
+            FftFilterKind::LowPass { cutoff } => (0, Vec4::new(cutoff, 0.0, 0.0, 0.0)),
+            FftFilterKind::HighPass { cutoff } => (1, Vec4::new(cutoff, 0.0, 0.0, 0.0)),
+            FftFilterKind::BandPass { center, width } => (2, Vec4::new(center, width, 0.0, 0.0)),
+            FftFilterKind::BandStop { center, width } => (3, Vec4::new(center, width, 0.0, 0.0)),
+            FftFilterKind::Wedge { angle, half_width } => {
+                (4, Vec4::new(angle, half_width, 0.0, 0.0))
+            }
+            FftFilterKind::Notch { frequency, radius } => (5, frequency.extend(radius).extend(0.0)),
+        };
+        let (profile, order) = filter.profile.to_bits();
+        Self {
+            kind,
+            profile,
+            order,
+            params,
+        }
+    }
+}
+
+impl ExtractComponent for FftFilter {
+    type QueryData = Read<FftFilter>;
+    type QueryFilter = With<FftSource>;
+    type Out = FftFilterUniform;
+
+    fn extract_component(filter: QueryItem<'_, '_, Self::QueryData>) -> Option<Self::Out> {
+        Some(filter.into())
+    }
+}
+
+/// Schedules another run of [`FftTrigger::OnChange`] entities when their filter changes.
+pub(super) fn request_fft_filter_runs(
+    mut commands: Commands,
+    query: Query<(Entity, &FftTrigger), Changed<FftFilter>>,
+) {
+    for (entity, trigger) in &query {
+        if *trigger == FftTrigger::OnChange {
+            commands.entity(entity).insert(FftRunRequest);
+        }
+    }
+}
+
+/// `fft_filter_spectrum` pipelines per [`FftWorkspaceFormat`], queued the first frame an entity
+/// with an [`FftFilter`] needs them.
+#[derive(Resource)]
+pub(super) struct FftFilterPipelines {
+    layout: BindGroupLayoutDescriptor,
+    pipelines: HashMap<FftWorkspaceFormat, CachedComputePipelineId>,
+}
+
+impl Default for FftFilterPipelines {
+    fn default() -> Self {
+        let entries = BindGroupLayoutEntries::single(
+            ShaderStages::COMPUTE,
+            uniform_buffer::<FftFilterUniform>(true),
+        );
+        Self {
+            layout: BindGroupLayoutDescriptor::new("fft_filter_bind_group_layout", &entries),
+            pipelines: HashMap::default(),
+        }
+    }
+}
+
+pub(super) fn prepare_fft_filter_pipelines(
+    mut filter: ResMut<FftFilterPipelines>,
+    fft_pipelines: Res<FftPipelines>,
+    pipeline_cache: Res<PipelineCache>,
+    query: Query<&FftSettings, With<FftFilterUniform>>,
+) {
+    let filter = &mut *filter;
+    for settings in &query {
+        let format = fft_pipelines.format(settings);
+        if format.volume {
+            continue;
+        }
+        filter.pipelines.entry(format).or_insert_with(|| {
+            pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
+                label: Some("fft_filter_spectrum_pipeline".into()),
+                layout: vec![format.layout(), filter.layout.clone()],
+                push_constant_ranges: vec![],
+                shader: shaders::FILTER.clone(),
+                shader_defs: format.shader_defs(),
+                entry_point: Some("fft_filter_spectrum".into()),
+                zero_initialize_workgroup_memory: false,
+            })
+        });
+    }
+}
+
+/// This frame's [`FftFilterUniform`]s, shared by every filtered entity through dynamic offsets.
+#[derive(Resource)]
+pub(super) struct FftFilterBindGroup(BindGroup);
+
+pub(super) fn prepare_fft_filter_bind_group(
+    mut commands: Commands,
+    device: Res<RenderDevice>,
+    pipeline_cache: Res<PipelineCache>,
+    pipelines: Res<FftFilterPipelines>,
+    uniforms: Res<ComponentUniforms<FftFilterUniform>>,
+) {
+    let Some(binding) = uniforms.binding() else {
+        commands.remove_resource::<FftFilterBindGroup>();
+        return;
+    };
+    commands.insert_resource(FftFilterBindGroup(device.create_bind_group(
+        "fft_filter_bind_group",
+        &pipeline_cache.get_bind_group_layout(&pipelines.layout),
+        &BindGroupEntries::single(binding),
+    )));
+}
+
+type FftFilterNodeQuery = (
+    Entity,
+    &'static MainEntity,
+    &'static FftBindGroups,
+    &'static FftSettings,
+    &'static DynamicUniformIndex<FftFilterUniform>,
+    Option<&'static FftTriggered>,
+);
+
+/// Runs at [`FftNode::Filter`](super::FftNode::Filter).
+pub(super) struct FftFilterNode {
+    query: QueryState<FftFilterNodeQuery>,
+}
+
+impl FromWorld for FftFilterNode {
+    fn from_world(world: &mut World) -> Self {
+        Self {
+            query: world.query(),
+        }
+    }
+}
+
+impl Node for FftFilterNode {
+    fn update(&mut self, world: &mut World) {
+        self.query.update_archetypes(world);
+    }
+
+    fn run(
+        &self,
+        _graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        let fft_pipelines = world.resource::<FftPipelines>();
+        let pipelines = world.resource::<FftFilterPipelines>();
+        let pipeline_cache = world.resource::<PipelineCache>();
+        let skipped = world.resource::<FftSkippedRuns>();
+        let diagnostics = render_context.diagnostic_recorder();
+        let Some(FftFilterBindGroup(filter_group)) = world.get_resource() else {
+            return Ok(());
+        };
+
+        let command_encoder = render_context.command_encoder();
+
+        for (entity, main_entity, bind_groups, settings, uniform_index, triggered) in
+            self.query.iter_manual(world)
+        {
+            if FftTriggered::is_idle(triggered) || settings.is_volume() {
+                continue;
+            }
+            let pipeline = pipelines
+                .pipelines
+                .get(&fft_pipelines.format(settings))
+                .and_then(|id| pipeline_cache.get_compute_pipeline(*id));
+            let Some(pipeline) = pipeline else {
+                // Still compiling; the trigger runs the entity again.
+                skipped.push(entity);
+                continue;
+            };
+            let wg = 16u32;
+            let mut pass = command_encoder.begin_compute_pass(&ComputePassDescriptor {
+                label: Some("fft_filter_pass"),
+                timestamp_writes: None,
+            });
+            let span =
+                diagnostics.pass_span(&mut pass, FftTimedPass::Filter.span(main_entity.id()));
+            pass.set_pipeline(pipeline);
+            bind_groups.set(&mut pass, 0);
+            pass.set_bind_group(1, filter_group, &[uniform_index.index()]);
+            pass.dispatch_workgroups(
+                settings.size.x.div_ceil(wg),
+                settings.size.y.div_ceil(wg),
+                settings.layers(),
+            );
+            span.end(&mut pass);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_4;
+
+    use bevy::math::{UVec2, Vec2};
+
+    use super::{FftFilter, FftFilterKind, FftFilterProfile};
+
+    #[test]
+    fn bins_wrap_to_negative_frequencies_past_the_middle() {
+        let size = UVec2::new(8, 5);
+        assert_eq!(FftFilter::bin_frequency(UVec2::ZERO, size), Vec2::ZERO);
+        assert_eq!(
+            FftFilter::bin_frequency(UVec2::new(4, 2), size),
+            Vec2::new(0.5, 0.4)
+        );
+        assert_eq!(
+            FftFilter::bin_frequency(UVec2::new(7, 3), size),
+            Vec2::new(-0.125, -0.4)
+        );
+    }
+
+    #[test]
+    fn radial_profiles_meet_at_their_edges() {
+        let at = |filter: FftFilter, radius: f32| filter.response(Vec2::new(0.0, radius));
+        let low = FftFilterKind::LowPass { cutoff: 0.2 };
+        let high = FftFilterKind::HighPass { cutoff: 0.2 };
+        for profile in [
+            FftFilterProfile::Ideal,
+            FftFilterProfile::Gaussian,
+            FftFilterProfile::Butterworth(3),
+        ] {
+            let (low, high) = (
+                FftFilter { kind: low, profile },
+                FftFilter {
+                    kind: high,
+                    profile,
+                },
+            );
+            assert_eq!(at(low, 0.0), 1.0);
+            for radius in [0.05, 0.2, 0.35] {
+                assert!((at(low, radius) + at(high, radius) - 1.0).abs() < 1e-6);
+            }
+        }
+        let butterworth = FftFilter::butterworth(low, 3);
+        assert!((at(butterworth, 0.2) - 0.5).abs() < 1e-6);
+        assert!((at(FftFilter::gaussian(low), 0.2) - (-0.5f32).exp()).abs() < 1e-6);
+
+        let band = FftFilter::ideal(FftFilterKind::BandPass {
+            center: 0.25,
+            width: 0.1,
+        });
+        assert_eq!(
+            [0.19, 0.21, 0.29, 0.31].map(|radius| at(band, radius)),
+            [0.0, 1.0, 1.0, 0.0]
+        );
+        let stop = FftFilter::ideal(FftFilterKind::BandStop {
+            center: 0.25,
+            width: 0.1,
+        });
+        assert_eq!(at(stop, 0.25), 0.0);
+        assert_eq!(at(stop, 0.0), 1.0);
+    }
+
+    #[test]
+    fn wedges_and_notches_are_point_symmetric() {
+        let wedge = FftFilter::ideal(FftFilterKind::Wedge {
+            angle: FRAC_PI_4,
+            half_width: 0.1,
+        });
+        assert_eq!(wedge.response(Vec2::ZERO), 1.0);
+        assert_eq!(wedge.response(Vec2::new(0.1, 0.1)), 1.0);
+        assert_eq!(wedge.response(Vec2::new(-0.1, -0.1)), 1.0);
+        assert_eq!(wedge.response(Vec2::new(-0.1, 0.1)), 0.0);
+
+        let notch = FftFilter::gaussian(FftFilterKind::Notch {
+            frequency: Vec2::new(0.25, -0.125),
+            radius: 0.02,
+        });
+        assert_eq!(notch.response(Vec2::new(0.25, -0.125)), 0.0);
+        assert_eq!(notch.response(Vec2::new(-0.25, 0.125)), 0.0);
+        assert!(notch.response(Vec2::ZERO) > 0.999);
+    }
+}
@@ -0,0 +1,123 @@
+#import bevy_fft::{
+    complex::{
+        c32,
+        c32_2,
+        c32_3,
+        c32_4,
+    },
+    bindings::settings,
+    buffer::{
+        read_buffer_c,
+        write_buffer_c,
+        fft_lanes,
+    },
+}
+
+#if CHANNELS == 1
+    alias c32_n = c32;
+#else if CHANNELS == 2
+    alias c32_n = c32_2;
+#else if CHANNELS == 3
+    alias c32_n = c32_3;
+#else if CHANNELS == 4
+    alias c32_n = c32_4;
+#endif
+
+// Mirrors `FftFilterUniform` in `filter.rs`.
+struct FftFilter {
+    kind: u32,
+    profile: u32,
+    order: u32,
+    params: vec4<f32>,
+}
+
+@group(1) @binding(0) var<uniform> spectrum_filter: FftFilter;
+
+const PI: f32 = 3.141592653589793;
+
+const KIND_LOW_PASS: u32 = 0u;
+const KIND_HIGH_PASS: u32 = 1u;
+const KIND_BAND_PASS: u32 = 2u;
+const KIND_BAND_STOP: u32 = 3u;
+const KIND_WEDGE: u32 = 4u;
+const KIND_NOTCH: u32 = 5u;
+
+const PROFILE_IDEAL: u32 = 0u;
+const PROFILE_GAUSSIAN: u32 = 1u;
+
+// `FftFilterProfile::falloff` at `distance` past the filter's `edge`.
+fn falloff(distance: f32, edge: f32) -> f32 {
+    let x = distance / max(edge, 1e-6);
+    switch spectrum_filter.profile {
+        case PROFILE_IDEAL: {
+            return select(0.0, 1.0, x <= 1.0);
+        }
+        case PROFILE_GAUSSIAN: {
+            return exp(-0.5 * x * x);
+        }
+        default: {
+            return 1.0 / (1.0 + pow(x, 2.0 * f32(spectrum_filter.order)));
+        }
+    }
+}
+
+// Mirrors `FftFilter::response`.
+fn fft_filter_response(frequency: vec2<f32>) -> f32 {
+    let p = spectrum_filter.params;
+    let radius = length(frequency);
+    switch spectrum_filter.kind {
+        case KIND_LOW_PASS: {
+            return falloff(radius, p.x);
+        }
+        case KIND_HIGH_PASS: {
+            return 1.0 - falloff(radius, p.x);
+        }
+        case KIND_BAND_PASS: {
+            return falloff(abs(radius - p.x), 0.5 * p.y);
+        }
+        case KIND_BAND_STOP: {
+            return 1.0 - falloff(abs(radius - p.x), 0.5 * p.y);
+        }
+        case KIND_WEDGE: {
+            if (radius == 0.0) {
+                return 1.0;
+            }
+            let turn = atan2(frequency.y, frequency.x) - p.x;
+            let offset = turn - PI * floor(turn / PI);
+            return falloff(min(offset, PI - offset), p.y);
+        }
+        case KIND_NOTCH: {
+            return (1.0 - falloff(distance(frequency, p.xy), p.z))
+                * (1.0 - falloff(distance(frequency, -p.xy), p.z));
+        }
+        default: {
+            return 1.0;
+        }
+    }
+}
+
+// `FftFilter::bin_frequency`. 1D batches keep one signal per row, so rows have no frequency.
+fn bin_frequency(bin: vec2<u32>) -> vec2<f32> {
+    let size = settings.size;
+    let wrapped = vec2<f32>(bin) - select(vec2<f32>(0.0), vec2<f32>(size), bin > size / 2u);
+    let frequency = wrapped / vec2<f32>(size);
+    return select(frequency, vec2<f32>(frequency.x, 0.0), settings.dimension == 1u);
+}
+
+// Scales spectrum **C** by the filter response, leaving the alpha lane of four-channel
+// workspaces alone.
+@compute
+@workgroup_size(16, 16, 1)
+fn fft_filter_spectrum(@builtin(global_invocation_id) gid: vec3<u32>) {
+    if (gid.x >= settings.size.x || gid.y >= settings.size.y) {
+        return;
+    }
+    let gain = fft_filter_response(bin_frequency(gid.xy));
+#if CHANNELS == 4
+    let lanes = vec4<f32>(vec3<f32>(gain), 1.0);
+#else
+    let lanes = fft_lanes(vec4<f32>(gain));
+#endif
+    let s = read_buffer_c(gid);
+    write_buffer_c(gid, c32_n(s.re * lanes, s.im * lanes));
+}
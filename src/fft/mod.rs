@@ -5,11 +5,12 @@ use bevy::{
         change_detection::Mut,
         component::Component,
         query::QueryItem,
+        resource::Resource,
         schedule::{IntoScheduleConfigs, SystemSet},
         system::lifetimeless::Read,
         world::FromWorld,
     },
-    math::UVec2,
+    math::{UVec2, UVec3},
     prelude::Image,
     reflect::Reflect,
     render::{
@@ -22,41 +23,83 @@ use bevy::{
     shader::Shader,
 };
 
+mod bluestein;
+mod convolution;
+pub mod cpu;
+mod diagnostics;
+mod filter;
 mod node;
+mod phase_correlation;
+mod readback;
 pub mod resources;
+mod trigger;
 
+pub(crate) use convolution::record_fft_convolution;
+pub use convolution::{FftConvolution, FftConvolutionMode};
+pub use diagnostics::{FftDiagnosticsPlugin, FftTimedPass};
+pub use filter::{FftFilter, FftFilterKind, FftFilterProfile};
 pub use node::{
     FftNode, FftSpectrumPassthroughNode, run_forward_fft, run_inverse_fft,
     splice_after_resolve_outputs, splice_spectrum_pass,
 };
-pub use resources::{FftPipelines, FftTextures, prepare_fft_bind_groups, prepare_fft_textures};
+pub use phase_correlation::{
+    FftFourierMellin, FftFourierMellinComplete, FftPhaseCorrelation, FftPhaseCorrelationComplete,
+};
+pub use readback::{FftReadback, FftReadbackComplete, FftReadbackData, FftReadbackTarget};
+pub use resources::{
+    FftPipelines, FftStorageBuffers, FftTexelSupport, FftTextures, FftWorkspaceFormat,
+    prepare_fft_bind_groups, prepare_fft_textures,
+};
+pub use trigger::{FftRunComplete, FftRunRequest, FftTrigger};
 
+use convolution::{
+    FftConvolutionKernel, FftConvolutionNode, FftConvolutionPipelines,
+    prepare_fft_convolution_bind_groups, prepare_fft_convolution_pipelines,
+};
+use filter::{
+    FftFilterNode, FftFilterPipelines, FftFilterUniform, prepare_fft_filter_bind_group,
+    prepare_fft_filter_pipelines,
+};
 use node::{FftComputeNode, FftResolveOutputsNode, FftResolveSpectrumNode};
+use phase_correlation::{
+    FftCorrelationPeakBuffer, FftPhaseCorrelationNode, FftPhaseCorrelationPipelines,
+    FftPhaseCorrelationStage, prepare_fft_phase_correlation_bind_groups,
+    prepare_fft_phase_correlation_pipelines,
+};
 use resources::{
-    FftBindGroupLayouts, FftRootsBuffer, copy_input_textures_to_fft_buffers,
-    prepare_fft_resolve_bind_groups, prepare_fft_roots_buffer,
+    FftBindGroupLayouts, FftStorageUpload, copy_input_textures_to_fft_buffers,
+    prepare_fft_bluestein_buffers, prepare_fft_constant_slots, prepare_fft_pipelines,
+    prepare_fft_resolve_bind_groups, prepare_fft_roots_buffer, prepare_fft_storage_buffers,
 };
+use trigger::{FftRunReports, FftSkippedRuns, FftTriggered, report_fft_runs};
 
 use crate::complex::c32;
 
 /// Fills `roots` with the twiddle factors used by the forward FFT. For each stage with
 /// `base = 2^order`, the value at index `base + k` is `exp(-i·2π·k / base)`. The WGSL `get_root`
-/// routines in `fft.wgsl` and `ifft.wgsl` read the table using the same layout.
-pub fn fill_forward_fft_twiddles(roots: &mut [c32; 8192]) {
+/// routines in `fft.wgsl` and `fft_common.wgsl` read the table using the same layout. Every base
+/// whose entries fit in `roots` is filled, so `2^(order + 1)` entries cover transforms up to
+/// `2^order` samples.
+pub fn fill_forward_fft_twiddles(roots: &mut [c32]) {
     roots.fill(c32::new(0.0, 0.0));
-    for order in 0..13u32 {
-        let base = 1u32 << order;
+    for order in 0..usize::BITS {
+        let base = 1usize << order;
         let count = (base >> 1).max(1);
+        if base + count > roots.len() {
+            break;
+        }
         for k in 0..count {
-            let theta = -2.0 * std::f32::consts::PI * (k as f32) / (base as f32);
-            roots[(base + k) as usize] = c32::new(theta.cos(), theta.sin());
+            // f64 keeps the angles of long bases exact to f32 precision.
+            let theta = -2.0 * std::f64::consts::PI * (k as f64) / (base as f64);
+            roots[base + k] = c32::new(theta.cos() as f32, theta.sin() as f32);
         }
     }
 }
 
-/// Builds a twiddle table that covers forward stages up through `orders ≤ 12`.
-pub fn forward_fft_twiddle_table() -> [c32; 8192] {
-    let mut roots = [c32::new(0.0, 0.0); 8192];
+/// Builds a twiddle table for transforms of up to `2^max_order` samples: `2^(max_order + 1)`
+/// entries, the size [`resources::FftRootsBuffer`] allocates for an entity's longest axis.
+pub fn forward_fft_twiddle_table(max_order: u32) -> Vec<c32> {
+    let mut roots = vec![c32::new(0.0, 0.0); 2 << max_order];
     fill_forward_fft_twiddles(&mut roots);
     roots
 }
@@ -69,14 +112,52 @@ pub fn fft_orders_for_size(n: u32) -> Option<u32> {
     Some(n.trailing_zeros())
 }
 
-/// Error returned by [`FftSource::try_square_forward_then_inverse`] and
-/// [`FftSource::try_square_inverse_only`] when the edge length is not a non-zero power of two.
+/// Per-axis [`fft_orders_for_size`] for a `width`×`height` grid. Returns `None` when either edge is
+/// not a non-zero power of two.
+pub fn fft_orders_for_extent(size: UVec2) -> Option<UVec2> {
+    Some(UVec2::new(
+        fft_orders_for_size(size.x)?,
+        fft_orders_for_size(size.y)?,
+    ))
+}
+
+/// Longest power-of-two transform the constructors accept (`2^15`). Twiddles are generated per
+/// entity up to its own longest axis, so in practice the adapter's max texture dimension and
+/// `max_storage_buffer_binding_size` usually bind first.
+pub const FFT_MAX_TRANSFORM_LEN: u32 = 1 << 15;
+
+/// Length of the power-of-two transform behind an axis of `n` samples: `n` itself when it is a
+/// power of two, otherwise the Bluestein convolution length `(2n - 1).next_power_of_two()`.
+/// Returns `None` for zero or when that length exceeds [`FFT_MAX_TRANSFORM_LEN`].
+pub fn fft_transform_len(n: u32) -> Option<u32> {
+    let len = match n {
+        0 => return None,
+        n if n.is_power_of_two() => n,
+        n => (2 * n - 1).next_power_of_two(),
+    };
+    (len <= FFT_MAX_TRANSFORM_LEN).then_some(len)
+}
+
+/// Per-axis `log2` of [`fft_transform_len`] for a `width`×`height` grid of any size.
+pub fn fft_transform_orders(size: UVec2) -> Option<UVec2> {
+    Some(UVec2::new(
+        fft_transform_len(size.x)?.trailing_zeros(),
+        fft_transform_len(size.y)?.trailing_zeros(),
+    ))
+}
+
+/// Error returned by the `try_*` constructors on [`FftSource`] (for example
+/// [`FftSource::try_square_forward_then_inverse`] and [`FftSource::try_forward_then_inverse`]) when
+/// an edge is zero or too long for [`fft_transform_len`].
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct FftInvalidSize;
 
 impl std::fmt::Display for FftInvalidSize {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str("FFT size must be a non-zero power of two")
+        write!(
+            f,
+            "FFT edges must be non-zero and need a transform of at most {FFT_MAX_TRANSFORM_LEN} samples"
+        )
     }
 }
 
@@ -91,7 +172,7 @@ pub enum FftSystemSet {
 
 #[cfg(test)]
 mod layout_tests {
-    use super::{FftSettings, forward_fft_twiddle_table};
+    use super::{FftSettings, fill_forward_fft_twiddles, forward_fft_twiddle_table};
     use bevy::math::UVec2;
     use bevy::render::render_resource::ShaderType;
     use std::f32::consts::PI;
@@ -100,7 +181,7 @@ mod layout_tests {
     fn fft_settings_uniform_size_matches_wgsl() {
         // If this fails, update `bindings.wgsl` so `FftSettings` matches the Rust uniform layout.
         let n = FftSettings::min_size().get() as usize;
-        assert_eq!(n, 48, "update bindings.wgsl FftSettings if this changes");
+        assert_eq!(n, 88, "update bindings.wgsl FftSettings if this changes");
     }
 
     #[test]
@@ -109,16 +190,236 @@ mod layout_tests {
         let s = super::FftSettings::from_fft_source(&src);
         assert_eq!(s.size, src.size);
         assert_eq!(s.orders, src.orders);
+        assert_eq!(s.orders, UVec2::splat(7));
         assert_eq!(s.schedule, src.schedule.to_bits());
         assert_eq!(s.pattern_target, src.pattern_target as u32);
         assert_eq!(s.normalization, src.spatial_display_gain);
+        assert_eq!(s.radix, src.radix.to_bits());
+        assert_eq!(s.fft_normalization, src.normalization.to_bits());
+        assert!(!s.is_half());
+        let half = super::FftSource {
+            precision: super::FftPrecision::Half,
+            ..src
+        };
+        assert!(super::FftSettings::from_fft_source(&half).is_half());
+        let volume = super::FftSource {
+            precision: super::FftPrecision::Half,
+            ..super::FftSource::volume(bevy::math::UVec3::splat(16), super::FftSchedule::Forward)
+        };
+        assert!(!super::FftSettings::from_fft_source(&volume).is_half());
+    }
+
+    #[test]
+    fn half_inputs_pack_real_in_the_low_bits() {
+        let re: Vec<u8> = [1.0f32, -2.0, 0.5, 0.0]
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect();
+        let im: Vec<u8> = [0.25f32, 0.0, -1.0, 3.0]
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect();
+        let packed = super::resources::pack_half_texels(&re, Some(&im), 4).unwrap();
+        let words: Vec<u32> = packed
+            .chunks_exact(4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect();
+        assert_eq!(words[0], 0x3c00 | (0x3400 << 16));
+        assert_eq!(words[1], 0xc000);
+        assert_eq!(words[2], 0x3800 | (0xbc00 << 16));
+        assert_eq!(words[3], 0x4200 << 16);
+        assert_eq!(
+            super::resources::pack_half_texels(&re, None, 4).unwrap()[..4],
+            0x3c00u32.to_le_bytes()
+        );
+        assert!(super::resources::pack_half_texels(&re, Some(&im[..8]), 4).is_none());
+        assert_eq!(
+            super::resources::pack_half_texels(&re, Some(&im), 1).unwrap(),
+            words[0].to_le_bytes()
+        );
+    }
+
+    #[test]
+    fn workspace_format_narrows_with_channels_and_support() {
+        use super::resources::{FftTexelSupport, FftWorkspaceFormat, narrow_texels};
+        use bevy::render::render_resource::TextureFormat;
+
+        let format = |channels, precision, support: &FftTexelSupport| {
+            let source = super::FftSource {
+                channels,
+                precision,
+                ..super::FftSource::default()
+            };
+            FftWorkspaceFormat::from_source(&source, support, super::FftBackend::Textures)
+                .texture_format()
+        };
+        let (full, half) = (super::FftPrecision::Full, super::FftPrecision::Half);
+        let all = FftTexelSupport {
+            r32_float: true,
+            rg32_float: true,
+            r32_uint: true,
+            rg32_uint: true,
+        };
+        assert_eq!(format(1, full, &all), TextureFormat::R32Float);
+        assert_eq!(format(2, full, &all), TextureFormat::Rg32Float);
+        assert_eq!(format(3, full, &all), TextureFormat::Rgba32Float);
+        assert_eq!(format(0, full, &all), TextureFormat::R32Float);
+        assert_eq!(format(2, half, &all), TextureFormat::Rg32Uint);
+        let rg_only = FftTexelSupport {
+            rg32_float: true,
+            ..FftTexelSupport::default()
+        };
+        assert_eq!(format(1, full, &rg_only), TextureFormat::Rg32Float);
+        assert_eq!(format(1, half, &rg_only), TextureFormat::Rgba32Uint);
+        let none = FftTexelSupport::default();
+        assert_eq!(format(1, full, &none), TextureFormat::Rgba32Float);
+
+        let texels: Vec<u8> = (0u8..32).collect();
+        assert_eq!(narrow_texels(&texels, 1), [0, 1, 2, 3, 16, 17, 18, 19]);
+    }
+
+    #[test]
+    fn storage_buffer_workspaces_stay_full_rgba() {
+        use super::resources::{FftTexelSupport, FftWorkspaceFormat, interleave_texels};
+
+        let source = super::FftSource {
+            channels: 1,
+            precision: super::FftPrecision::Half,
+            ..super::FftSource::default()
+        };
+        let support = FftTexelSupport {
+            r32_float: true,
+            r32_uint: true,
+            ..FftTexelSupport::default()
+        };
+        let format =
+            FftWorkspaceFormat::from_source(&source, &support, super::FftBackend::StorageBuffers);
+        assert!(format.storage_buffers);
+        assert!(!format.half);
+        assert_eq!((format.channels, format.texel_width), (1, 4));
+
+        let re: Vec<u8> = (0u8..32).collect();
+        let im: Vec<u8> = (100u8..132).collect();
+        let samples = interleave_texels(&re, Some(&im)).unwrap();
+        assert_eq!(samples.len(), 64);
+        assert_eq!(samples[..16], re[..16]);
+        assert_eq!(samples[16..32], im[..16]);
+        assert_eq!(samples[32..48], re[16..]);
+        assert!(
+            interleave_texels(&re, None).unwrap()[48..]
+                .iter()
+                .all(|&b| b == 0)
+        );
+        assert!(interleave_texels(&re, Some(&im[..16])).is_none());
+    }
+
+    #[test]
+    fn windows_taper_edges_and_keep_the_centre() {
+        use super::FftWindow;
+
+        let n = 65;
+        assert_eq!(FftWindow::Hann.weight(0, n), 0.0);
+        assert!((FftWindow::Hann.weight(32, n) - 1.0).abs() < 1e-6);
+        assert!((FftWindow::Hamming.weight(0, n) - 0.08).abs() < 1e-6);
+        assert!(FftWindow::Blackman.weight(0, n).abs() < 1e-6);
+        assert!((FftWindow::Kaiser { beta: 8.6 }.weight(32, n) - 1.0).abs() < 1e-5);
+        assert!(FftWindow::Kaiser { beta: 8.6 }.weight(0, n) < 2e-3);
+        assert!((FftWindow::Kaiser { beta: 0.0 }.weight(3, n) - 1.0).abs() < 1e-6);
+        assert_eq!(FftWindow::Tukey { alpha: 0.0 }.weight(0, n), 1.0);
+        assert_eq!(FftWindow::Tukey { alpha: 0.5 }.weight(24, n), 1.0);
+        assert_eq!(
+            FftWindow::Tukey { alpha: 1.0 }.weight(8, n),
+            FftWindow::Hann.weight(8, n)
+        );
+        let edge = FftWindow::Gaussian { sigma: 0.5 }.weight(0, n);
+        assert!((edge - (-2.0f32).exp()).abs() < 1e-6);
+        for i in 0..n {
+            assert_eq!(FftWindow::None.weight(i, n), 1.0);
+            let w = FftWindow::Blackman.weight(i, n);
+            assert!((w - FftWindow::Blackman.weight(n - 1 - i, n)).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn window_axes_follow_per_axis_flag() {
+        use super::{FftSource, FftWindow};
+
+        let base = FftSource::forward_then_inverse(UVec2::new(256, 64));
+        assert_eq!(FftSettings::from_fft_source(&base).window_axes, 0);
+        let rows = FftSource {
+            window: FftWindow::Kaiser { beta: 6.0 },
+            ..base
+        };
+        let s = FftSettings::from_fft_source(&rows);
+        assert_eq!(s.window_axes, 0b1);
+        assert_eq!(s.window_type, 3);
+        assert_eq!(s.window_param, 6.0);
+        assert_eq!(s.window_strength, 1.0);
+        let separable = FftSource {
+            window_per_axis: true,
+            ..rows
+        };
+        assert_eq!(FftSettings::from_fft_source(&separable).window_axes, 0b11);
+    }
+
+    #[test]
+    fn convolution_padding_reaches_the_next_power_of_two() {
+        use super::{FftSchedule, FftSource};
+        use bevy::math::UVec3;
+
+        let s = FftSource::forward_then_inverse(UVec2::new(100, 60))
+            .with_convolution_padding(UVec2::new(29, 5));
+        assert_eq!(s.size, UVec2::new(100, 60));
+        assert_eq!(s.padding, UVec2::new(28, 4));
+        assert_eq!(s.orders, UVec2::new(7, 6));
+        let settings = FftSettings::from_fft_source(&s);
+        assert_eq!(settings.size, UVec2::new(128, 64));
+        assert_eq!(settings.fft_size, UVec2::new(128, 64));
+        assert_eq!(settings.signal_size(), UVec2::new(100, 60));
+
+        // A padded extent that is not a power of two falls back to Bluestein lengths.
+        let odd = FftSource::forward_then_inverse(UVec2::splat(64)).with_padding(UVec2::new(36, 0));
+        assert_eq!(odd.orders, UVec2::new(8, 6));
+
+        let batch = FftSource::one_d(1000, 7, FftSchedule::Forward)
+            .with_convolution_padding(UVec2::new(25, 9));
+        assert_eq!(batch.padding, UVec2::new(24, 0));
+        assert_eq!(batch.orders, UVec2::new(10, 0));
+        assert!(batch.try_with_padding(UVec2::new(0, 1)).is_err());
+
+        let volume = FftSource::volume(UVec3::splat(32), FftSchedule::Forward);
+        assert!(volume.clone().try_with_padding(UVec2::new(32, 0)).is_ok());
+        assert!(volume.try_with_padding(UVec2::new(8, 0)).is_err());
+        assert!(
+            FftSource::forward_then_inverse(UVec2::splat(64))
+                .try_with_convolution_padding(UVec2::new(0, 3))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn normalization_scales_round_trip_to_one_over_n() {
+        use super::FftNormalization;
+
+        for mode in [
+            FftNormalization::Backward,
+            FftNormalization::Forward,
+            FftNormalization::Ortho,
+        ] {
+            let (forward, inverse) = mode.scales(256);
+            assert!((forward * inverse * 256.0 - 1.0).abs() < 1e-6, "{mode:?}");
+            assert_eq!(FftNormalization::try_from_bits(mode.to_bits()), Some(mode));
+        }
+        assert_eq!(FftNormalization::Ortho.scales(64), (0.125, 0.125));
+        assert_eq!(FftNormalization::None.scales(64), (1.0, 1.0));
     }
 
     /// Cheap regression check for the twiddle indexing logic.
     #[test]
     fn twiddle_table_matches_formula() {
-        let roots = forward_fft_twiddle_table();
-        for order in 1u32..=12 {
+        let roots = forward_fft_twiddle_table(13);
+        assert_eq!(roots.len(), 1 << 14);
+        for order in 1u32..=13 {
             let base = 1u32 << order;
             let count = (base >> 1).max(1);
             for k in [0u32, 1, count / 2, count.saturating_sub(1)] {
@@ -136,14 +437,67 @@ mod layout_tests {
         }
     }
 
+    #[test]
+    fn twiddle_tables_follow_the_longest_axis() {
+        let s = super::FftSource::forward_then_inverse(UVec2::new(8192, 600));
+        let settings = FftSettings::from_fft_source(&s);
+        assert_eq!(settings.max_order(), 13);
+        assert_eq!(forward_fft_twiddle_table(settings.max_order()).len(), 16384);
+        let volume = super::FftSource::volume(
+            bevy::math::UVec3::new(16, 16, 64),
+            super::FftSchedule::Forward,
+        );
+        assert_eq!(FftSettings::from_fft_source(&volume).max_order(), 6);
+        // A short buffer only fills the bases that fit.
+        let mut short = [super::c32::new(9.0, 9.0); 5];
+        fill_forward_fft_twiddles(&mut short);
+        assert_eq!(short[2], super::c32::new(1.0, 0.0));
+        assert_eq!(short[4], super::c32::new(0.0, 0.0));
+    }
+
     #[test]
     fn square_inverse_only_sizes_track_orders() {
         let s = super::FftSource::square_inverse_only(256);
         assert_eq!(s.size, UVec2::splat(256));
-        assert_eq!(s.orders, 8);
+        assert_eq!(s.orders, UVec2::splat(8));
         assert_eq!(s.schedule, super::FftSchedule::Inverse);
         let s512 = super::FftSource::square_inverse_only(512);
-        assert_eq!(s512.orders, 9);
+        assert_eq!(s512.orders, UVec2::splat(9));
+    }
+
+    #[test]
+    fn rectangular_constructors_track_orders_per_axis() {
+        let s = super::FftSource::forward_then_inverse(UVec2::new(2048, 512));
+        assert_eq!(s.size, UVec2::new(2048, 512));
+        assert_eq!(s.orders, UVec2::new(11, 9));
+        assert_eq!(s.schedule, super::FftSchedule::ForwardThenInverse);
+        let s = super::FftSource::inverse_only(UVec2::new(64, 256));
+        assert_eq!(s.orders, UVec2::new(6, 8));
+        assert_eq!(s.schedule, super::FftSchedule::Inverse);
+    }
+
+    #[test]
+    fn shared_line_capacity_follows_workgroup_storage() {
+        use super::resources::shared_line_capacity;
+        use bevy::render::settings::WgpuLimits;
+
+        // 16 KiB default: 512 four-channel samples per line.
+        assert_eq!(shared_line_capacity(&WgpuLimits::default()), 512);
+        let roomy = WgpuLimits {
+            max_compute_workgroup_storage_size: 48 * 1024,
+            ..Default::default()
+        };
+        assert_eq!(shared_line_capacity(&roomy), 1024);
+        let huge = WgpuLimits {
+            max_compute_workgroup_storage_size: 1 << 20,
+            ..Default::default()
+        };
+        assert_eq!(shared_line_capacity(&huge), 32768);
+        let narrow = WgpuLimits {
+            max_compute_invocations_per_workgroup: 128,
+            ..Default::default()
+        };
+        assert_eq!(shared_line_capacity(&narrow), 0);
     }
 
     #[test]
@@ -152,15 +506,123 @@ mod layout_tests {
         assert_eq!(super::fft_orders_for_size(1), Some(0));
         assert_eq!(super::fft_orders_for_size(0), None);
         assert_eq!(super::fft_orders_for_size(3), None);
+        assert_eq!(
+            super::fft_orders_for_extent(UVec2::new(2048, 512)),
+            Some(UVec2::new(11, 9))
+        );
+        assert_eq!(super::fft_orders_for_extent(UVec2::new(2048, 600)), None);
     }
 
     #[test]
     fn try_square_constructors_reject_invalid_sizes() {
         assert!(super::FftSource::try_square_forward_then_inverse(0).is_err());
-        assert!(super::FftSource::try_square_forward_then_inverse(3).is_err());
         assert!(super::FftSource::try_square_forward_then_inverse(256).is_ok());
+        assert!(super::FftSource::try_square_forward_then_inverse(2049).is_ok());
+        assert!(super::FftSource::try_square_forward_then_inverse(16385).is_err());
         assert!(super::FftSource::try_square_inverse_only(0).is_err());
         assert!(super::FftSource::try_square_inverse_only(512).is_ok());
+        assert!(super::FftSource::try_square_inverse_only(8192).is_ok());
+        assert!(super::FftSource::try_square_inverse_only(65536).is_err());
+        assert!(super::FftSource::try_forward_then_inverse(UVec2::new(256, 0)).is_err());
+        assert!(super::FftSource::try_inverse_only(UVec2::new(128, 64)).is_ok());
+    }
+
+    #[test]
+    fn one_d_batches_accept_any_row_count() {
+        let s = super::FftSource::one_d(1024, 300, super::FftSchedule::Forward);
+        assert_eq!(s.size, UVec2::new(1024, 300));
+        assert_eq!(s.orders, UVec2::new(10, 0));
+        let settings = super::FftSettings::from_fft_source(&s);
+        assert_eq!(settings.dimension, super::FftDimension::OneD.to_bits());
+        assert_eq!(settings.fft_size, UVec2::new(1024, 1));
+        assert!(
+            super::FftSource::try_one_d(1000, 5000, super::FftSchedule::Inverse).is_ok(),
+            "1000 samples pad to a 2048-point Bluestein line"
+        );
+        assert!(super::FftSource::try_one_d(256, 0, super::FftSchedule::Forward).is_err());
+        assert!(super::FftSource::try_one_d(0, 8, super::FftSchedule::Forward).is_err());
+    }
+
+    #[test]
+    fn volume_constructor_tracks_depth() {
+        use bevy::math::UVec3;
+
+        let s = super::FftSource::volume(UVec3::new(128, 64, 32), super::FftSchedule::Forward);
+        assert_eq!(s.size, UVec2::new(128, 64));
+        assert_eq!(s.orders, UVec2::new(7, 6));
+        assert_eq!(s.depth, 32);
+        let settings = super::FftSettings::from_fft_source(&s);
+        assert_eq!(settings.depth, 32);
+        assert!(settings.is_volume());
+        let flat = super::FftSource {
+            depth: 32,
+            ..super::FftSource::square_forward_then_inverse(64)
+        };
+        assert_eq!(super::FftSettings::from_fft_source(&flat).depth, 1);
+        let volume = |x, y, z| {
+            super::FftSource::try_volume(UVec3::new(x, y, z), super::FftSchedule::Inverse)
+        };
+        assert!(volume(64, 64, 64).is_ok());
+        assert!(volume(64, 64, 0).is_err());
+        assert!(
+            volume(64, 48, 64).is_err(),
+            "volumes have no Bluestein path"
+        );
+        assert!(volume(64, 64, 65536).is_err());
+    }
+
+    #[test]
+    fn layers_stack_on_the_workspace_z_extent() {
+        use super::resources::{FftTexelSupport, FftWorkspaceFormat};
+        use bevy::math::UVec3;
+
+        let s = super::FftSource::forward_then_inverse(UVec2::splat(64)).with_layers(24);
+        let settings = super::FftSettings::from_fft_source(&s);
+        assert_eq!((settings.depth, settings.layers()), (24, 24));
+        assert!(settings.is_layered() && !settings.is_volume());
+        assert_eq!(settings.max_order(), 6);
+        let format = FftWorkspaceFormat::from_settings(
+            &settings,
+            &FftTexelSupport::default(),
+            super::FftBackend::Textures,
+        );
+        assert!(format.layered);
+        assert!(format.shader_defs().contains(&"FFT_LAYERS".into()));
+
+        let single = super::FftSettings::from_fft_source(&s.clone().with_layers(1));
+        assert!(!single.is_layered());
+        assert!(s.clone().try_with_layers(0).is_err());
+        let volume = super::FftSource::volume(UVec3::splat(16), super::FftSchedule::Forward);
+        assert!(volume.clone().try_with_layers(4).is_err());
+        let ignored = super::FftSource {
+            layers: 4,
+            ..volume
+        };
+        assert_eq!(super::FftSettings::from_fft_source(&ignored).layers(), 1);
+    }
+
+    #[test]
+    fn non_power_of_two_edges_use_bluestein_lengths() {
+        assert_eq!(super::fft_transform_len(512), Some(512));
+        assert_eq!(super::fft_transform_len(3), Some(8));
+        assert_eq!(super::fft_transform_len(600), Some(2048));
+        assert_eq!(super::fft_transform_len(1920), Some(4096));
+        assert_eq!(super::fft_transform_len(2048), Some(2048));
+        assert_eq!(super::fft_transform_len(2049), Some(8192));
+        assert_eq!(super::fft_transform_len(16384), Some(16384));
+        assert_eq!(super::fft_transform_len(16385), None);
+        assert_eq!(super::fft_transform_len(0), None);
+
+        let s = super::FftSource::forward_then_inverse(UVec2::new(1920, 1080));
+        assert_eq!(s.orders, UVec2::new(12, 12));
+        let settings = super::FftSettings::from_fft_source(&s);
+        assert_eq!(settings.size, UVec2::new(1920, 1080));
+        assert_eq!(settings.fft_size, UVec2::new(4096, 4096));
+        let mixed = super::FftSource::inverse_only(UVec2::new(96, 64));
+        assert_eq!(
+            super::FftSettings::from_fft_source(&mixed).fft_size,
+            UVec2::new(256, 64)
+        );
     }
 }
 
@@ -175,6 +637,10 @@ pub(crate) mod shaders {
     pub const FFT_COMMON: Handle<Shader> = uuid_handle!("a1b2c3d4-1111-2222-3333-444455556677");
     pub const RESOLVE_OUTPUTS: Handle<Shader> =
         uuid_handle!("c4d5e6f0-1111-4222-a333-444455556666");
+    pub const CONVOLUTION: Handle<Shader> = uuid_handle!("5b0e7c1d-92a4-4f36-8d1e-3c7a9f20b6e4");
+    pub const PHASE_CORRELATION: Handle<Shader> =
+        uuid_handle!("e2a47c90-6d15-4b3f-9c8e-17f5a0d3b264");
+    pub const FILTER: Handle<Shader> = uuid_handle!("8c3f1a26-5e07-4d9b-b2a4-61d0e9f47c15");
 }
 
 /// Chooses how much of the 2D FFT pipeline runs on each frame.
@@ -208,6 +674,257 @@ impl FftSchedule {
     }
 }
 
+/// Butterfly kernel family used by the stock forward and inverse passes.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, Reflect)]
+#[repr(u32)]
+pub enum FftRadix {
+    /// Bit-reverse permute, then one radix-2 DIT dispatch per stage. Odd orders add a copy pass.
+    #[default]
+    Radix2 = 0,
+    /// Stockham autosort with radix-4 stages (plus one radix-2 stage for odd orders). Needs no
+    /// bit-reverse or copy passes and roughly halves the dispatch count per axis.
+    Radix4 = 1,
+}
+
+impl FftRadix {
+    #[inline]
+    pub const fn to_bits(self) -> u32 {
+        self as u32
+    }
+
+    #[inline]
+    pub fn try_from_bits(bits: u32) -> Option<Self> {
+        match bits {
+            0 => Some(Self::Radix2),
+            1 => Some(Self::Radix4),
+            _ => None,
+        }
+    }
+}
+
+/// Where the `1/N` of a forward/inverse pair goes, named like numpy's `norm` argument. `N` is
+/// the logical length of each transformed axis, applied on that axis's last pass.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, Reflect)]
+#[repr(u32)]
+pub enum FftNormalization {
+    /// Unscaled forward transform, `1/N` on the inverse (numpy and FFTW default).
+    #[default]
+    Backward = 0,
+    /// `1/N` on the forward transform, unscaled inverse.
+    Forward = 1,
+    /// `1/√N` on both, so the transform is unitary and Parseval holds without rescaling.
+    Ortho = 2,
+    /// No scaling either way; a round trip multiplies by `N`.
+    None = 3,
+}
+
+impl FftNormalization {
+    #[inline]
+    pub const fn to_bits(self) -> u32 {
+        self as u32
+    }
+
+    #[inline]
+    pub fn try_from_bits(bits: u32) -> Option<Self> {
+        match bits {
+            0 => Some(Self::Backward),
+            1 => Some(Self::Forward),
+            2 => Some(Self::Ortho),
+            3 => Some(Self::None),
+            _ => None,
+        }
+    }
+
+    /// `(forward, inverse)` factors for an axis of `n` samples, as applied by `fft.wgsl`.
+    pub fn scales(self, n: u32) -> (f32, f32) {
+        let n = n as f32;
+        match self {
+            Self::Backward => (1.0, 1.0 / n),
+            Self::Forward => (1.0 / n, 1.0),
+            Self::Ortho => (1.0 / n.sqrt(), 1.0 / n.sqrt()),
+            Self::None => (1.0, 1.0),
+        }
+    }
+}
+
+/// Storage format of the workspace buffers **A**–**D**. Kernels compute in f32 either way.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, Reflect)]
+#[repr(u32)]
+pub enum FftPrecision {
+    /// Two `Rgba32Float` textures per buffer, real and imaginary.
+    #[default]
+    Full = 0,
+    /// One `Rgba32Uint` texture per buffer whose channels pack real and imaginary parts as an f16
+    /// pair (`pack2x16float`), which halves workspace memory and bandwidth. Each `_im` handle on
+    /// [`FftTextures`] aliases its `_re` texture. Fits large grids where f16 accuracy is enough,
+    /// such as bloom or visual ocean detail. Custom kernels reading the buffers must build with
+    /// the `FFT_HALF` shader def. Volumes ([`FftDimension::ThreeD`]) always use [`Self::Full`].
+    Half = 1,
+}
+
+impl FftPrecision {
+    #[inline]
+    pub const fn to_bits(self) -> u32 {
+        self as u32
+    }
+
+    #[inline]
+    pub fn try_from_bits(bits: u32) -> Option<Self> {
+        match bits {
+            0 => Some(Self::Full),
+            1 => Some(Self::Half),
+            _ => None,
+        }
+    }
+}
+
+/// Spatial taper applied to samples as the forward transform loads them, to cut spectral leakage
+/// from the discontinuity between opposite edges. Each variant is a 1D window over one axis; see
+/// [`FftSource::window_per_axis`] for separable use on every transformed axis.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Reflect)]
+pub enum FftWindow {
+    /// Samples pass through unchanged.
+    #[default]
+    None,
+    /// Flat centre with cosine edges spanning the `alpha` fraction of the axis. `alpha = 0` is
+    /// rectangular and `alpha = 1` is Hann.
+    Tukey { alpha: f32 },
+    /// Three-term Blackman, with strong sidelobe suppression.
+    Blackman,
+    /// Kaiser–Bessel. Larger `beta` widens the main lobe and lowers the sidelobes; `0` is
+    /// rectangular and around `8.6` resembles Blackman.
+    Kaiser { beta: f32 },
+    /// Raised cosine reaching zero at both ends.
+    Hann,
+    /// Raised cosine that stops at `0.08`, trading edge roll-off for a lower first sidelobe.
+    Hamming,
+    /// Gaussian with standard deviation `sigma` relative to the half-width of the axis.
+    Gaussian { sigma: f32 },
+}
+
+impl FftWindow {
+    /// Encoding of `window_type` in the WGSL uniform.
+    #[inline]
+    pub const fn to_bits(self) -> u32 {
+        match self {
+            Self::None => 0,
+            Self::Tukey { .. } => 1,
+            Self::Blackman => 2,
+            Self::Kaiser { .. } => 3,
+            Self::Hann => 4,
+            Self::Hamming => 5,
+            Self::Gaussian { .. } => 6,
+        }
+    }
+
+    /// Decodes `window_type` and `window_param` from the WGSL uniform.
+    pub fn try_from_bits(bits: u32, parameter: f32) -> Option<Self> {
+        match bits {
+            0 => Some(Self::None),
+            1 => Some(Self::Tukey { alpha: parameter }),
+            2 => Some(Self::Blackman),
+            3 => Some(Self::Kaiser { beta: parameter }),
+            4 => Some(Self::Hann),
+            5 => Some(Self::Hamming),
+            6 => Some(Self::Gaussian { sigma: parameter }),
+            _ => None,
+        }
+    }
+
+    /// Shape parameter carried in `window_param`, or `0` for windows without one.
+    #[inline]
+    pub const fn parameter(self) -> f32 {
+        match self {
+            Self::Tukey { alpha } => alpha,
+            Self::Kaiser { beta } => beta,
+            Self::Gaussian { sigma } => sigma,
+            Self::None | Self::Blackman | Self::Hann | Self::Hamming => 0.0,
+        }
+    }
+
+    /// Weight of sample `i` on an axis of `n` samples, matching `apply_window` in `plot.wgsl`.
+    pub fn weight(self, i: u32, n: u32) -> f32 {
+        if n <= 1 {
+            return 1.0;
+        }
+        let x = i as f32 / (n - 1) as f32;
+        let tau = std::f32::consts::TAU;
+        match self {
+            Self::None => 1.0,
+            Self::Tukey { alpha } => {
+                if alpha <= 0.0 {
+                    return 1.0;
+                }
+                let edge = 0.5 * alpha;
+                let d = x.min(1.0 - x);
+                if d >= edge {
+                    1.0
+                } else {
+                    0.5 - 0.5 * (std::f32::consts::PI * d / edge).cos()
+                }
+            }
+            Self::Blackman => 0.42 - 0.5 * (tau * x).cos() + 0.08 * (2.0 * tau * x).cos(),
+            Self::Kaiser { beta } => {
+                let r = 2.0 * x - 1.0;
+                bessel_i0(beta * (1.0 - r * r).max(0.0).sqrt()) / bessel_i0(beta)
+            }
+            Self::Hann => 0.5 - 0.5 * (tau * x).cos(),
+            Self::Hamming => 0.54 - 0.46 * (tau * x).cos(),
+            Self::Gaussian { sigma } => {
+                let r = (2.0 * x - 1.0) / sigma.max(1e-4);
+                (-0.5 * r * r).exp()
+            }
+        }
+    }
+}
+
+/// Power series for the zeroth-order modified Bessel function, as in `plot.wgsl`.
+fn bessel_i0(x: f32) -> f32 {
+    let q = 0.25 * x * x;
+    let mut term = 1.0;
+    let mut sum = 1.0;
+    for k in 1..48u32 {
+        term *= q / (k * k) as f32;
+        sum += term;
+    }
+    sum
+}
+
+/// Which axes the stock passes transform.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, Reflect)]
+#[repr(u32)]
+pub enum FftDimension {
+    /// Rows, then columns: **A** → **C** forward and **C** → **B** inverse.
+    #[default]
+    TwoD = 0,
+    /// Rows only. Every texture row is an independent signal, so one dispatch per stage covers a
+    /// whole batch of audio or telemetry windows. Buffers are the same as in [`Self::TwoD`], and
+    /// the spectrum in **C** keeps one row of bins per signal.
+    OneD = 1,
+    /// Rows, columns, then depth over `TextureDimension::D3` buffers of
+    /// [`FftSource::depth`] slices. Every edge must be a power of two and each axis runs the
+    /// radix-2 DIT chain. Bind groups follow [`resources::FftBindGroupLayouts::volume`], and the
+    /// 2D resolve passes skip the entity, so read **B** and **C** directly.
+    ThreeD = 2,
+}
+
+impl FftDimension {
+    #[inline]
+    pub const fn to_bits(self) -> u32 {
+        self as u32
+    }
+
+    #[inline]
+    pub fn try_from_bits(bits: u32) -> Option<Self> {
+        match bits {
+            0 => Some(Self::TwoD),
+            1 => Some(Self::OneD),
+            2 => Some(Self::ThreeD),
+            _ => None,
+        }
+    }
+}
+
 /// Describes what your [`FftInputTexture`] images represent so CPU uploads go to the right buffer.
 ///
 /// Spatial images land in **A**. Ready-made spectra land in **C**.
@@ -233,23 +950,27 @@ pub enum FftPatternTarget {
 /// When present on the same entity as [`FftSource`] and [`crate::fft::resources::FftTextures`], the
 /// stock render-graph FFT and resolve passes skip that entity. Use this when a custom render node
 /// drives [`run_forward_fft`](crate::fft::run_forward_fft) and [`run_inverse_fft`](crate::fft::run_inverse_fft)
-/// on that entity’s buffers (for example eWave). Extraction and [`crate::fft::resources::prepare_fft_roots_buffer`]
-/// still run from [`FftSource`].
+/// on that entity’s buffers (for example eWave). Extraction and the per-entity
+/// [`crate::fft::resources::FftRootsBuffer`] still come from [`FftSource`].
 #[derive(Component, Clone, Copy, Default, Reflect)]
 pub struct FftSkipStockPipeline;
 
-/// Main-world FFT configuration. The render world mirrors this into [`FftSettings`], [`FftRoots`],
-/// and related extracted components each frame.
+/// Main-world FFT configuration. The render world mirrors this into [`FftSettings`] and related
+/// extracted components each frame, and builds the twiddle table from it.
 #[derive(Component, Clone, Reflect)]
 pub struct FftSource {
-    /// Grid width and height for this FFT entity.
+    /// Grid width and height for this FFT entity. Edges need not match or be powers of two.
     pub size: UVec2,
-    /// Base-two logarithm of the transform size. The stock WGSL expects eight stages for 256×256 inputs.
-    pub orders: u32,
-    /// Extra border pixels reserved for future windowing or padding work.
+    /// Base-two logarithm of the transform length along each axis (see [`fft_transform_len`]):
+    /// `x` counts row stages and `y` counts column stages. A 2048×512 grid uses eleven row stages
+    /// and nine column stages; a 600-sample edge pads to a 2048-point Bluestein convolution.
+    pub orders: UVec2,
+    /// Zero border past the right and bottom edges of [`Self::size`]. The workspace buffers span
+    /// `size + padding`, inputs land in their top-left corner, and
+    /// [`FftTextures::spatial_output`] is cropped back to `size`, so spectral products give
+    /// linear rather than circular convolution. Set it through [`Self::with_padding`] or
+    /// [`Self::with_convolution_padding`] so [`Self::orders`] follows the padded extent.
     pub padding: UVec2,
-    /// Twiddle factors shared with the GPU through [`FftRoots`].
-    pub roots: [c32; 8192],
     /// Forward, inverse, or both. See [`FftSchedule`].
     pub schedule: FftSchedule,
     /// Whether CPU uploads are spatial images or spectra. Spectrum mode targets buffer **C**.
@@ -258,19 +979,54 @@ pub struct FftSource {
     pub pattern_target: FftPatternTarget,
     /// Scales values written to [`crate::fft::resources::FftTextures::spatial_output`]. FFT buffers stay untouched.
     pub spatial_display_gain: f32,
+    /// Radix-2 DIT or radix-4 Stockham stages. Both produce the same bins in the same buffers.
+    pub radix: FftRadix,
+    /// 2D grid, a batch of independent 1D rows, or a 3D volume.
+    pub dimension: FftDimension,
+    /// Number of `z` slices for [`FftDimension::ThreeD`]. Ignored by the other modes.
+    pub depth: u32,
+    /// Independent grids (or 1D batches) of [`Self::size`] stacked in `D2Array` workspaces and
+    /// transformed together: each pass is one dispatch for the whole stack, with layers on the
+    /// workgroup `z` dimension. Inputs and the resolved images are array textures of as many
+    /// layers. Ignored by [`FftDimension::ThreeD`]. Set it through [`Self::with_layers`].
+    pub layers: u32,
+    /// Which direction carries the `1/N` scaling. See [`FftNormalization`].
+    pub normalization: FftNormalization,
+    /// Taper applied to spatial samples on the forward pass. See [`FftWindow`].
+    pub window: FftWindow,
+    /// When `false`, [`Self::window`] tapers rows only, matching a 1D signal layout. When `true`,
+    /// it is applied separably along every transformed axis, so rectangular grids get a
+    /// per-axis window sized to each edge.
+    pub window_per_axis: bool,
+    /// f32 or packed f16 workspace storage. See [`FftPrecision`].
+    pub precision: FftPrecision,
+    /// Independent signals transformed per texel, from 1 to 4 (values outside are clamped). One-
+    /// and two-channel workspaces use `R32Float` / `Rg32Float` textures where the adapter can
+    /// read-write them as storage, and fall back to `Rgba32Float` otherwise (as on Metal). Inputs
+    /// stay `Rgba32Float`; only their first `channels` components are transformed. The domain
+    /// plugins write four-channel spectra and need the default of `4`.
+    pub channels: u32,
 }
 
 impl Default for FftSource {
     fn default() -> Self {
         Self {
             size: UVec2::new(256, 256),
-            orders: 8,
+            orders: UVec2::splat(8),
             padding: UVec2::ZERO,
-            roots: forward_fft_twiddle_table(),
             schedule: FftSchedule::Forward,
             input_domain: FftInputDomain::Spatial,
             pattern_target: FftPatternTarget::SpatialA,
             spatial_display_gain: 1.0,
+            radix: FftRadix::Radix2,
+            dimension: FftDimension::TwoD,
+            depth: 1,
+            layers: 1,
+            normalization: FftNormalization::Backward,
+            window: FftWindow::None,
+            window_per_axis: false,
+            precision: FftPrecision::Full,
+            channels: 4,
         }
     }
 }
@@ -278,45 +1034,194 @@ impl Default for FftSource {
 impl FftSource {
     /// Square `n`×`n` grid with [`Self::schedule`] set to [`FftSchedule::ForwardThenInverse`].
     pub fn square_forward_then_inverse(n: u32) -> Self {
-        Self::try_square_forward_then_inverse(n).expect("FFT size must be a non-zero power of two")
+        Self::try_square_forward_then_inverse(n).expect("unsupported FFT size")
     }
 
-    /// Like [`Self::square_forward_then_inverse`], but returns an error when `n` is not a non-zero power of two.
+    /// Like [`Self::square_forward_then_inverse`], but returns an error when `n` is unsupported.
     pub fn try_square_forward_then_inverse(n: u32) -> Result<Self, FftInvalidSize> {
-        let orders = fft_orders_for_size(n).ok_or(FftInvalidSize)?;
-        Ok(Self {
-            size: UVec2::splat(n),
-            orders,
-            padding: UVec2::ZERO,
-            roots: forward_fft_twiddle_table(),
-            schedule: FftSchedule::ForwardThenInverse,
-            input_domain: FftInputDomain::Spatial,
-            pattern_target: FftPatternTarget::SpatialA,
-            spatial_display_gain: 1.0,
-        })
+        Self::try_forward_then_inverse(UVec2::splat(n))
     }
 
     /// Square grid that only runs the inverse transform each frame (spectrum writers such as
     /// [`crate::ocean::OceanPlugin`]). Data ends in buffer **B** as spatial output.
     pub fn square_inverse_only(n: u32) -> Self {
-        Self::try_square_inverse_only(n).expect("FFT size must be a non-zero power of two")
+        Self::try_square_inverse_only(n).expect("unsupported FFT size")
     }
 
-    /// Like [`Self::square_inverse_only`], but returns an error when `n` is not a non-zero power of two.
+    /// Like [`Self::square_inverse_only`], but returns an error when `n` is unsupported.
     pub fn try_square_inverse_only(n: u32) -> Result<Self, FftInvalidSize> {
-        let orders = fft_orders_for_size(n).ok_or(FftInvalidSize)?;
+        Self::try_inverse_only(UVec2::splat(n))
+    }
+
+    /// `width`×`height` grid with [`Self::schedule`] set to [`FftSchedule::ForwardThenInverse`].
+    /// Rows and columns are transformed with their own lengths, so strips such as 2048×512 work.
+    /// Edges that are not powers of two, such as 1920×1080, run through Bluestein's algorithm.
+    pub fn forward_then_inverse(size: UVec2) -> Self {
+        Self::try_forward_then_inverse(size).expect("unsupported FFT size")
+    }
+
+    /// Like [`Self::forward_then_inverse`], but returns an error when either edge is unsupported.
+    pub fn try_forward_then_inverse(size: UVec2) -> Result<Self, FftInvalidSize> {
+        Self::try_with_schedule(size, FftSchedule::ForwardThenInverse)
+    }
+
+    /// Rectangular counterpart of [`Self::square_inverse_only`].
+    pub fn inverse_only(size: UVec2) -> Self {
+        Self::try_inverse_only(size).expect("unsupported FFT size")
+    }
+
+    /// Like [`Self::inverse_only`], but returns an error when either edge is unsupported.
+    pub fn try_inverse_only(size: UVec2) -> Result<Self, FftInvalidSize> {
+        Self::try_with_schedule(size, FftSchedule::Inverse)
+    }
+
+    fn try_with_schedule(size: UVec2, schedule: FftSchedule) -> Result<Self, FftInvalidSize> {
+        let orders = fft_transform_orders(size).ok_or(FftInvalidSize)?;
         Ok(Self {
-            size: UVec2::splat(n),
+            size,
             orders,
             padding: UVec2::ZERO,
-            roots: forward_fft_twiddle_table(),
-            schedule: FftSchedule::Inverse,
+            schedule,
             input_domain: FftInputDomain::Spatial,
             pattern_target: FftPatternTarget::SpatialA,
             spatial_display_gain: 1.0,
+            radix: FftRadix::Radix2,
+            dimension: FftDimension::TwoD,
+            depth: 1,
+            layers: 1,
+            normalization: FftNormalization::Backward,
+            window: FftWindow::None,
+            window_per_axis: false,
+            precision: FftPrecision::Full,
+            channels: 4,
+        })
+    }
+
+    /// Batch of `rows` independent signals of `length` samples each, transformed along rows only
+    /// (see [`FftDimension::OneD`]).
+    pub fn one_d(length: u32, rows: u32, schedule: FftSchedule) -> Self {
+        Self::try_one_d(length, rows, schedule).expect("unsupported FFT size")
+    }
+
+    /// Like [`Self::one_d`], but returns an error when `length` is unsupported or `rows` is zero.
+    /// The row count is a batch size, so it need not be a power of two.
+    pub fn try_one_d(
+        length: u32,
+        rows: u32,
+        schedule: FftSchedule,
+    ) -> Result<Self, FftInvalidSize> {
+        if rows == 0 {
+            return Err(FftInvalidSize);
+        }
+        let order = fft_transform_len(length)
+            .ok_or(FftInvalidSize)?
+            .trailing_zeros();
+        Ok(Self {
+            size: UVec2::new(length, rows),
+            orders: UVec2::new(order, 0),
+            schedule,
+            dimension: FftDimension::OneD,
+            ..Self::default()
+        })
+    }
+
+    /// `width`×`height`×`depth` volume over 3D buffers (see [`FftDimension::ThreeD`]). Every edge
+    /// must be a power of two up to [`FFT_MAX_TRANSFORM_LEN`].
+    pub fn volume(size: UVec3, schedule: FftSchedule) -> Self {
+        Self::try_volume(size, schedule).expect("unsupported FFT size")
+    }
+
+    /// Like [`Self::volume`], but returns an error when an edge is zero, not a power of two, or
+    /// longer than [`FFT_MAX_TRANSFORM_LEN`].
+    pub fn try_volume(size: UVec3, schedule: FftSchedule) -> Result<Self, FftInvalidSize> {
+        if size.max_element() > FFT_MAX_TRANSFORM_LEN || fft_orders_for_size(size.z).is_none() {
+            return Err(FftInvalidSize);
+        }
+        Ok(Self {
+            size: size.truncate(),
+            orders: fft_orders_for_extent(size.truncate()).ok_or(FftInvalidSize)?,
+            schedule,
+            dimension: FftDimension::ThreeD,
+            depth: size.z,
+            ..Self::default()
         })
     }
 
+    /// Stacks `layers` independent grids of [`Self::size`] (see [`Self::layers`]).
+    pub fn with_layers(self, layers: u32) -> Self {
+        self.try_with_layers(layers)
+            .expect("unsupported FFT layer count")
+    }
+
+    /// Like [`Self::with_layers`], but returns an error when `layers` is zero or the source is a
+    /// [`FftDimension::ThreeD`] volume. The count must also fit the adapter's
+    /// `max_texture_array_layers` (256 by default).
+    pub fn try_with_layers(self, layers: u32) -> Result<Self, FftInvalidSize> {
+        if layers == 0 || (self.dimension == FftDimension::ThreeD && layers != 1) {
+            return Err(FftInvalidSize);
+        }
+        Ok(Self { layers, ..self })
+    }
+
+    /// Pads the workspace by `padding` past the right and bottom edges (see [`Self::padding`]).
+    pub fn with_padding(self, padding: UVec2) -> Self {
+        self.try_with_padding(padding)
+            .expect("unsupported FFT size")
+    }
+
+    /// Like [`Self::with_padding`], but returns an error when the padded extent is unsupported.
+    /// 1D batches only pad rows, and volumes still need power-of-two edges after padding.
+    pub fn try_with_padding(self, padding: UVec2) -> Result<Self, FftInvalidSize> {
+        if self.dimension == FftDimension::OneD && padding.y != 0 {
+            return Err(FftInvalidSize);
+        }
+        let workspace = UVec2::new(
+            self.size.x.checked_add(padding.x).ok_or(FftInvalidSize)?,
+            self.size.y.checked_add(padding.y).ok_or(FftInvalidSize)?,
+        );
+        let orders = match self.dimension {
+            FftDimension::TwoD => fft_transform_orders(workspace),
+            FftDimension::OneD => {
+                fft_transform_len(workspace.x).map(|n| UVec2::new(n.trailing_zeros(), 0))
+            }
+            FftDimension::ThreeD => fft_orders_for_extent(workspace)
+                .filter(|_| workspace.max_element() <= FFT_MAX_TRANSFORM_LEN),
+        }
+        .ok_or(FftInvalidSize)?;
+        Ok(Self {
+            orders,
+            padding,
+            ..self
+        })
+    }
+
+    /// Pads each axis up to the next power of two that holds `size + kernel - 1` samples, the
+    /// full linear convolution of the input with a `kernel`-sized filter. 1D batches ignore
+    /// `kernel.y`.
+    pub fn with_convolution_padding(self, kernel: UVec2) -> Self {
+        self.try_with_convolution_padding(kernel)
+            .expect("unsupported FFT size")
+    }
+
+    /// Like [`Self::with_convolution_padding`], but returns an error when the padded extent is
+    /// unsupported or `kernel` has a zero edge.
+    pub fn try_with_convolution_padding(self, kernel: UVec2) -> Result<Self, FftInvalidSize> {
+        if kernel.x == 0 || (kernel.y == 0 && self.dimension != FftDimension::OneD) {
+            return Err(FftInvalidSize);
+        }
+        let full = self
+            .size
+            .saturating_add(kernel.max(UVec2::ONE) - 1)
+            .min(UVec2::splat(FFT_MAX_TRANSFORM_LEN + 1));
+        let mut padding =
+            UVec2::new(full.x.next_power_of_two(), full.y.next_power_of_two()) - self.size;
+        if self.dimension == FftDimension::OneD {
+            // Rows are independent signals, so the batch never grows.
+            padding.y = 0;
+        }
+        self.try_with_padding(padding)
+    }
+
     /// Builds the usual 256×256 setup that runs a forward FFT and inverse FFT each frame.
     pub fn grid_256_forward_then_inverse() -> Self {
         Self::square_forward_then_inverse(256)
@@ -351,34 +1256,143 @@ impl ExtractComponent for FftInputTexture {
 #[derive(Component, Clone, Copy, Reflect, ShaderType)]
 #[repr(C)]
 pub struct FftSettings {
+    /// Workspace extent: [`FftSource::size`] plus [`FftSource::padding`].
     pub size: UVec2,
-    pub orders: u32,
+    /// Row (`x`) and column (`y`) stage counts, `log2` of [`Self::fft_size`].
+    pub orders: UVec2,
+    /// Power-of-two transform length per axis. Equals [`Self::size`] on power-of-two edges and
+    /// the Bluestein padding length elsewhere.
+    pub fft_size: UVec2,
+    /// [`FftSource::padding`]: the zero border at the right and bottom of the workspace.
     pub padding: UVec2,
     /// [`FftSchedule`] encoded the way the WGSL uniform expects.
     pub schedule: u32,
     /// [`FftPatternTarget`] encoded the way the WGSL uniform expects.
     pub pattern_target: u32,
+    /// [`FftWindow`] encoded the way the WGSL uniform expects.
     pub window_type: u32,
+    /// Mix between no taper (`0`) and the full window (`1`).
     pub window_strength: f32,
-    pub radial_falloff: f32,
+    /// [`FftWindow::parameter`].
+    pub window_param: f32,
     pub normalization: f32,
+    /// [`FftRadix`] encoded the way the WGSL uniform expects.
+    pub radix: u32,
+    /// [`FftDimension`] encoded the way the WGSL uniform expects.
+    pub dimension: u32,
+    /// Volume depth for [`FftDimension::ThreeD`], otherwise [`FftSource::layers`]. Either way
+    /// the `z` extent of the workspace.
+    pub depth: u32,
+    /// [`FftNormalization`] encoded the way the WGSL uniform expects. Not to be confused with
+    /// [`Self::normalization`], the display gain.
+    pub fft_normalization: u32,
+    /// Bit `a` set means the forward pass tapers axis `a` with the window.
+    pub window_axes: u32,
+    /// [`FftPrecision`] encoded the way the WGSL uniform expects, always
+    /// [`FftPrecision::Full`] for volumes. Named `storage_precision` in WGSL, where `precision`
+    /// is reserved.
+    pub precision: u32,
+    /// [`FftSource::channels`] clamped to `1..=4`. Shaders read the count from the `CHANNELS`
+    /// shader def of their pipeline; the uniform copy is for custom passes.
+    pub channels: u32,
 }
 
 impl FftSettings {
     /// Uniform layout for the GPU, matching [`FftSource`] and the `FftSettings` block in `bindings.wgsl`.
     pub fn from_fft_source(source: &FftSource) -> Self {
         FftSettings {
-            size: source.size,
+            size: source.size + source.padding,
             orders: source.orders,
+            fft_size: UVec2::new(1 << source.orders.x, 1 << source.orders.y),
             padding: source.padding,
             schedule: source.schedule.to_bits(),
             pattern_target: source.pattern_target as u32,
-            window_type: 0,
-            window_strength: 0.0,
-            radial_falloff: 0.0,
+            window_type: source.window.to_bits(),
+            window_strength: if source.window == FftWindow::None {
+                0.0
+            } else {
+                1.0
+            },
+            window_param: source.window.parameter(),
             normalization: source.spatial_display_gain,
+            radix: source.radix.to_bits(),
+            dimension: source.dimension.to_bits(),
+            depth: match source.dimension {
+                FftDimension::ThreeD => source.depth,
+                FftDimension::TwoD | FftDimension::OneD => source.layers.max(1),
+            },
+            fft_normalization: source.normalization.to_bits(),
+            window_axes: window_axes(source),
+            precision: match source.dimension {
+                FftDimension::ThreeD => FftPrecision::Full,
+                FftDimension::TwoD | FftDimension::OneD => source.precision,
+            }
+            .to_bits(),
+            channels: source.channels.clamp(1, 4),
         }
     }
+
+    /// Axes the stock passes transform: only rows for [`FftDimension::OneD`], rows, columns and
+    /// depth for [`FftDimension::ThreeD`], rows then columns otherwise.
+    pub fn transformed_axes(&self) -> u32 {
+        match FftDimension::try_from_bits(self.dimension) {
+            Some(FftDimension::OneD) => 1,
+            Some(FftDimension::ThreeD) => 3,
+            _ => 2,
+        }
+    }
+
+    /// `log2` of the longest transform across the axes that run, which sizes the twiddle table.
+    pub fn max_order(&self) -> u32 {
+        let orders = [self.orders.x, self.orders.y, self.depth.trailing_zeros()];
+        orders[..self.transformed_axes() as usize]
+            .iter()
+            .copied()
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Extent of the signal inside the workspace, [`FftSource::size`], which is also the extent
+    /// of [`FftTextures::spatial_output`].
+    pub fn signal_size(&self) -> UVec2 {
+        self.size - self.padding
+    }
+
+    /// Whether the workspace holds packed f16 samples, see [`FftPrecision::Half`].
+    pub fn is_half(&self) -> bool {
+        self.precision == FftPrecision::Half.to_bits()
+    }
+
+    /// Whether the workspace textures are 3D, see [`FftDimension::ThreeD`].
+    pub fn is_volume(&self) -> bool {
+        self.transformed_axes() == 3
+    }
+
+    /// Number of stacked grids, see [`FftSource::layers`]. Always `1` for volumes.
+    pub fn layers(&self) -> u32 {
+        if self.is_volume() { 1 } else { self.depth }
+    }
+
+    /// Whether the workspace textures are 2D arrays, see [`FftSource::layers`].
+    pub fn is_layered(&self) -> bool {
+        self.layers() > 1
+    }
+}
+
+/// Axis bitmask for [`FftSettings::window_axes`]: rows only, or every transformed axis with
+/// [`FftSource::window_per_axis`].
+fn window_axes(source: &FftSource) -> u32 {
+    if source.window == FftWindow::None {
+        return 0;
+    }
+    if !source.window_per_axis {
+        return 1;
+    }
+    match source.dimension {
+        FftDimension::OneD => 0b1,
+        FftDimension::TwoD => 0b11,
+        FftDimension::ThreeD => 0b111,
+    }
 }
 
 impl ExtractComponent for FftSettings {
@@ -387,27 +1401,50 @@ impl ExtractComponent for FftSettings {
     type Out = FftSettings;
 
     fn extract_component(item: QueryItem<'_, '_, Self::QueryData>) -> Option<Self::Out> {
-        Some(FftSettings::from_fft_source(&item))
+        Some(FftSettings::from_fft_source(item))
     }
 }
 
-#[derive(Component, Clone, Copy, Reflect, ShaderType)]
-#[repr(C)]
+/// Optional custom twiddle table for an [`FftSource`] entity, in the layout of
+/// [`fill_forward_fft_twiddles`]. It must hold at least `2^(order + 1)` entries for the entity's
+/// longest axis. Without it, the render world generates the stock table at exactly that size.
+#[derive(Component, Clone)]
 pub struct FftRoots {
-    pub roots: [c32; 8192],
+    pub roots: Vec<c32>,
 }
 
 impl ExtractComponent for FftRoots {
-    type QueryData = Read<FftSource>;
+    type QueryData = Read<FftRoots>;
     type QueryFilter = ();
     type Out = FftRoots;
 
     fn extract_component(item: QueryItem<'_, '_, Self::QueryData>) -> Option<Self::Out> {
-        Some(FftRoots { roots: item.roots })
+        Some(item.clone())
     }
 }
 
-pub struct FftPlugin;
+/// Where the workspace buffers **A**–**D** live. Chosen once per app through
+/// [`FftPlugin::backend`].
+#[derive(Resource, Clone, Copy, Default, Debug, PartialEq, Eq, Reflect)]
+pub enum FftBackend {
+    /// Read-write storage textures, the `buffer_*` images on [`FftTextures`]. Needs the
+    /// adapter's read-write storage texture tier. The ocean and ewave plugins and the custom
+    /// passes in the examples write these textures directly and require this backend.
+    #[default]
+    Textures,
+    /// Storage buffers of complex samples ([`FftStorageBuffers`]), for adapters and WebGPU
+    /// targets without read-write storage textures. Workspaces are always f32 with four lanes
+    /// per sample, so [`FftPrecision::Half`] and narrow texel formats do not apply. The
+    /// `buffer_*` handles on [`FftTextures`] are unused; the resolve passes still write
+    /// [`FftTextures::spatial_output`] and [`FftTextures::power_spectrum`].
+    StorageBuffers,
+}
+
+#[derive(Default)]
+pub struct FftPlugin {
+    /// Workspace storage for every FFT entity. See [`FftBackend`].
+    pub backend: FftBackend,
+}
 
 impl Plugin for FftPlugin {
     fn build(&self, app: &mut App) {
@@ -427,53 +1464,143 @@ impl Plugin for FftPlugin {
             "resolve_outputs.wgsl",
             Shader::from_wgsl
         );
+        load_internal_asset!(
+            app,
+            shaders::CONVOLUTION,
+            "convolution.wgsl",
+            Shader::from_wgsl
+        );
+        load_internal_asset!(
+            app,
+            shaders::PHASE_CORRELATION,
+            "phase_correlation.wgsl",
+            Shader::from_wgsl
+        );
+        load_internal_asset!(app, shaders::FILTER, "filter.wgsl", Shader::from_wgsl);
         // Forward and inverse passes still load from `assets/` so they are easy to tweak.
 
         app.register_type::<FftSource>()
             .register_type::<FftSkipStockPipeline>()
             .register_type::<FftSchedule>()
+            .register_type::<FftRadix>()
+            .register_type::<FftDimension>()
+            .register_type::<FftWindow>()
+            .register_type::<FftPrecision>()
+            .register_type::<FftBackend>()
+            .register_type::<FftNormalization>()
             .register_type::<FftInputDomain>()
             .register_type::<FftPatternTarget>()
-            .register_type::<FftRoots>()
             .register_type::<FftInputTexture>()
+            .register_type::<FftReadback>()
+            .register_type::<FftReadbackTarget>()
+            .register_type::<FftTrigger>()
+            .register_type::<FftRunRequest>()
+            .register_type::<FftConvolution>()
+            .register_type::<FftConvolutionMode>()
+            .register_type::<FftPhaseCorrelation>()
+            .register_type::<FftFourierMellin>()
+            .register_type::<FftFilter>()
+            .register_type::<FftFilterKind>()
+            .register_type::<FftFilterProfile>()
+            .insert_resource(self.backend)
+            .init_resource::<FftRunReports>()
             .configure_sets(Update, FftSystemSet::PrepareTextures)
             .add_systems(
                 Update,
                 (
                     resources::prepare_fft_textures.in_set(FftSystemSet::PrepareTextures),
-                    copy_input_textures_to_fft_buffers
+                    convolution::prepare_fft_convolution_kernels
                         .after(resources::prepare_fft_textures)
+                        .before(trigger::update_fft_triggers)
+                        .in_set(FftSystemSet::PrepareTextures),
+                    phase_correlation::request_fft_phase_correlation_runs
+                        .before(trigger::update_fft_triggers)
                         .in_set(FftSystemSet::PrepareTextures),
+                    filter::request_fft_filter_runs
+                        .before(trigger::update_fft_triggers)
+                        .in_set(FftSystemSet::PrepareTextures),
+                    trigger::update_fft_triggers
+                        .after(resources::prepare_fft_textures)
+                        .in_set(FftSystemSet::PrepareTextures),
+                    copy_input_textures_to_fft_buffers
+                        .after(trigger::update_fft_triggers)
+                        .in_set(FftSystemSet::PrepareTextures),
+                    readback::request_fft_readbacks.after(FftSystemSet::PrepareTextures),
+                    phase_correlation::request_fft_correlation_readbacks
+                        .after(FftSystemSet::PrepareTextures),
                 ),
             )
+            .add_observer(readback::on_fft_readback_complete)
+            .add_observer(phase_correlation::insert_fft_correlation_peak_buffer)
+            .add_observer(phase_correlation::on_fft_correlation_readback)
             .add_plugins((
                 ExtractComponentPlugin::<FftSettings>::default(),
                 UniformComponentPlugin::<FftSettings>::default(),
                 ExtractComponentPlugin::<FftRoots>::default(),
                 ExtractComponentPlugin::<FftTextures>::default(),
                 ExtractComponentPlugin::<FftInputTexture>::default(),
+                ExtractComponentPlugin::<FftStorageUpload>::default(),
+                ExtractComponentPlugin::<FftTriggered>::default(),
+                ExtractComponentPlugin::<FftConvolution>::default(),
+                ExtractComponentPlugin::<FftConvolutionKernel>::default(),
+                ExtractComponentPlugin::<FftPhaseCorrelation>::default(),
+                ExtractComponentPlugin::<FftCorrelationPeakBuffer>::default(),
+                ExtractComponentPlugin::<FftFourierMellin>::default(),
+                ExtractComponentPlugin::<FftFilter>::default(),
+                UniformComponentPlugin::<FftFilterUniform>::default(),
             ));
     }
 
     fn finish(&self, app: &mut App) {
+        // Sampled once here, after the render plugin has inserted the adapter, so texture
+        // allocation and pipelines agree on the workspace formats.
+        let texel_support = FftTexelSupport::from_adapter(app.world().get_resource());
+        app.insert_resource(texel_support);
+        let run_reports = app.world().resource::<FftRunReports>().clone();
         let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
             return;
         };
 
         render_app
+            .insert_resource(texel_support)
+            .insert_resource(self.backend)
+            .insert_resource(run_reports)
+            .init_resource::<FftSkippedRuns>()
             .init_resource::<FftBindGroupLayouts>()
             .init_resource::<FftPipelines>()
-            .init_resource::<FftRootsBuffer>()
+            .init_resource::<FftConvolutionPipelines>()
+            .init_resource::<FftPhaseCorrelationPipelines>()
+            .init_resource::<FftFilterPipelines>()
             .add_systems(
                 Render,
                 (
-                    prepare_fft_roots_buffer
-                        .in_set(RenderSystems::Prepare)
-                        .before(RenderSystems::PrepareBindGroups),
+                    prepare_fft_pipelines.in_set(RenderSystems::PrepareResources),
+                    prepare_fft_constant_slots.in_set(RenderSystems::PrepareResources),
+                    prepare_fft_roots_buffer.in_set(RenderSystems::PrepareResources),
+                    prepare_fft_bluestein_buffers.in_set(RenderSystems::PrepareResources),
+                    prepare_fft_storage_buffers
+                        .in_set(RenderSystems::PrepareResources)
+                        .after(prepare_fft_pipelines),
+                    prepare_fft_convolution_pipelines
+                        .in_set(RenderSystems::PrepareResources)
+                        .after(prepare_fft_pipelines),
+                    prepare_fft_phase_correlation_pipelines
+                        .in_set(RenderSystems::PrepareResources)
+                        .after(prepare_fft_pipelines),
+                    prepare_fft_filter_pipelines
+                        .in_set(RenderSystems::PrepareResources)
+                        .after(prepare_fft_pipelines),
                     prepare_fft_bind_groups.in_set(RenderSystems::PrepareBindGroups),
                     prepare_fft_resolve_bind_groups
                         .in_set(RenderSystems::PrepareBindGroups)
                         .after(prepare_fft_bind_groups),
+                    prepare_fft_convolution_bind_groups.in_set(RenderSystems::PrepareBindGroups),
+                    prepare_fft_phase_correlation_bind_groups
+                        .in_set(RenderSystems::PrepareBindGroups),
+                    phase_correlation::clear_fft_correlation_results
+                        .in_set(RenderSystems::PrepareResources),
+                    prepare_fft_filter_bind_group.in_set(RenderSystems::PrepareBindGroups),
+                    report_fft_runs.in_set(RenderSystems::Cleanup),
                 ),
             );
 
@@ -481,22 +1608,41 @@ impl Plugin for FftPlugin {
         render_app
             .world_mut()
             .resource_scope(|world, mut graph: Mut<RenderGraph>| {
+                graph.add_node(
+                    FftNode::PhaseCorrelationInput,
+                    FftPhaseCorrelationNode::new(world, FftPhaseCorrelationStage::Input),
+                );
                 graph.add_node(FftNode::ComputeFFT, FftComputeNode::from_world(world));
-                graph.add_node(FftNode::SpectrumPass, FftSpectrumPassthroughNode::default());
+                graph.add_node(FftNode::SpectrumPass, FftSpectrumPassthroughNode);
+                graph.add_node(FftNode::Convolve, FftConvolutionNode::from_world(world));
+                graph.add_node(FftNode::Filter, FftFilterNode::from_world(world));
+                graph.add_node(
+                    FftNode::CrossPowerSpectrum,
+                    FftPhaseCorrelationNode::new(world, FftPhaseCorrelationStage::CrossPower),
+                );
                 graph.add_node(
                     FftNode::ResolveSpectrum,
                     FftResolveSpectrumNode::from_world(world),
                 );
                 graph.add_node(FftNode::ComputeIFFT, FftComputeNode::from_world(world));
+                graph.add_node(
+                    FftNode::CorrelationPeak,
+                    FftPhaseCorrelationNode::new(world, FftPhaseCorrelationStage::Peak),
+                );
                 graph.add_node(
                     FftNode::ResolveOutputs,
                     FftResolveOutputsNode::from_world(world),
                 );
                 graph.add_node_edges((
+                    FftNode::PhaseCorrelationInput,
                     FftNode::ComputeFFT,
                     FftNode::SpectrumPass,
+                    FftNode::Convolve,
+                    FftNode::Filter,
+                    FftNode::CrossPowerSpectrum,
                     FftNode::ResolveSpectrum,
                     FftNode::ComputeIFFT,
+                    FftNode::CorrelationPeak,
                     FftNode::ResolveOutputs,
                 ));
                 graph.add_node_edge(FftNode::ResolveOutputs, CameraDriverLabel);
@@ -1,49 +1,86 @@
+use std::ops::{Deref, DerefMut};
+
 use bevy::{
     ecs::{
+        entity::Entity,
         query::QueryState,
         world::{FromWorld, World},
     },
     log::{error, info},
+    math::{UVec2, UVec3},
     render::{
+        diagnostic::RecordDiagnostics,
         graph::CameraDriverLabel,
         render_graph::{Node, NodeRunError, RenderGraph, RenderGraphContext, RenderLabel},
-        render_resource::{ComputePass, ComputePassDescriptor, PipelineCache},
+        render_resource::{
+            CachedComputePipelineId, ComputePass, ComputePassDescriptor, ComputePipeline,
+            PipelineCache,
+        },
         renderer::RenderContext,
+        sync_world::MainEntity,
     },
     utils::once,
 };
 
 use super::{
-    FftSchedule, FftSettings,
-    resources::{FftBindGroups, FftPipelines, FftResolveBindGroups},
+    FftRadix, FftSchedule, FftSettings,
+    diagnostics::FftTimedPass,
+    resources::{
+        FftBindGroups, FftConstantSlots, FftPipelines, FftResolveBindGroups, bluestein_batch_lines,
+    },
+    trigger::{FftSkippedRuns, FftTriggered},
 };
 
 #[repr(C)]
-#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+#[derive(Clone, Copy, Default, bytemuck::Pod, bytemuck::Zeroable)]
 struct FftPushConstants {
     stage: u32,
     axis: u32,
     src_buffer: u32,
     dst_buffer: u32,
     flags: u32,
+    /// First line across `axis` of a batched dispatch, set from [`FftPass::batch`].
+    line_base: u32,
+    /// Lines in the batch, `0` when the dispatch covers every line.
+    line_span: u32,
 }
 
 const BUF_A: u32 = 0;
 const BUF_B: u32 = 1;
 const BUF_C: u32 = 2;
+/// Bluestein workspace slots, backed by a storage buffer instead of a texture pair.
+const BUF_BLUESTEIN_0: u32 = 3;
+const BUF_BLUESTEIN_1: u32 = 4;
 
 const FLAG_INVERSE_FINALIZE: u32 = 1;
 const FLAG_FORWARD_ALPHA: u32 = 2;
+const FLAG_FORWARD_WINDOW: u32 = 4;
+const FLAG_INVERSE_CONJ_INPUT: u32 = 8;
+const FLAG_FORWARD_SCALE: u32 = 16;
 
 #[derive(PartialEq, Eq, Debug, Copy, Clone, Hash, RenderLabel)]
 pub enum FftNode {
+    /// Loads the images of [`FftPhaseCorrelation`](super::FftPhaseCorrelation) entities into **A**.
+    PhaseCorrelationInput,
     ComputeFFT,
     /// After the forward FFT the spectrum lives in **C**. The stock implementation for this label
     /// does nothing on the GPU. Use [`splice_spectrum_pass`] to insert a real compute pass here.
     SpectrumPass,
+    /// Multiplies **C** by the kernel spectrum of entities with an
+    /// [`FftConvolution`](super::FftConvolution), after [`Self::SpectrumPass`].
+    Convolve,
+    /// Multiplies **C** by the response of entities with an [`FftFilter`](super::FftFilter),
+    /// after [`Self::Convolve`].
+    Filter,
+    /// Replaces **C** with the normalized cross-power spectrum of
+    /// [`FftPhaseCorrelation`](super::FftPhaseCorrelation) entities, after [`Self::Filter`].
+    CrossPowerSpectrum,
     /// Writes `power_spectrum` from **C** while it still holds the spectrum (before inverse FFT scratch).
     ResolveSpectrum,
     ComputeIFFT,
+    /// Reduces **B** of [`FftPhaseCorrelation`](super::FftPhaseCorrelation) entities to the
+    /// correlation peak, before [`Self::ResolveOutputs`].
+    CorrelationPeak,
     /// Writes `spatial_output` from **B** after the inverse FFT.
     ResolveOutputs,
     /// Optional hook. Register a compute node with this label to run pattern generation before [`Self::ComputeFFT`].
@@ -65,7 +102,7 @@ impl Node for FftSpectrumPassthroughNode {
     }
 }
 
-/// Drops the default spectrum pass and wires `user_pass` between [`FftNode::ComputeFFT`] and [`FftNode::ResolveSpectrum`].
+/// Drops the default spectrum pass and wires `user_pass` between [`FftNode::ComputeFFT`] and [`FftNode::Convolve`].
 ///
 /// Call from `RenderApp` after registering `user_pass` on the **root** [`RenderGraph`].
 pub fn splice_spectrum_pass(world: &mut World, user_pass: impl RenderLabel) {
@@ -81,7 +118,7 @@ pub fn splice_spectrum_pass(world: &mut World, user_pass: impl RenderLabel) {
     let _ = graph.remove_node(FftNode::SpectrumPass);
     let user = user_pass.intern();
     graph.add_node_edge(FftNode::ComputeFFT, user);
-    graph.add_node_edge(user, FftNode::ResolveSpectrum);
+    graph.add_node_edge(user, FftNode::Convolve);
 }
 
 /// Runs `user_pass` after [`FftNode::ResolveOutputs`] and before [`CameraDriverLabel`].
@@ -107,7 +144,13 @@ pub fn splice_after_resolve_outputs(world: &mut World, user_pass: impl RenderLab
 }
 
 pub(super) struct FftComputeNode {
-    query: QueryState<(&'static FftBindGroups, &'static FftSettings)>,
+    query: QueryState<(
+        Entity,
+        &'static MainEntity,
+        &'static FftBindGroups,
+        &'static FftSettings,
+        Option<&'static FftTriggered>,
+    )>,
 }
 
 impl FromWorld for FftComputeNode {
@@ -118,184 +161,748 @@ impl FromWorld for FftComputeNode {
     }
 }
 
-fn fft_set_push_constants(pass: &mut ComputePass<'_>, pc: &FftPushConstants) {
-    pass.set_push_constants(0, bytemuck::bytes_of(pc));
+/// Compute pass the dispatch helpers record into, plus where [`FftPushConstants`] go: push
+/// constants, or a uniform slot at group 1 on devices without them.
+struct FftPass<'a, 'p> {
+    pass: &'a mut ComputePass<'p>,
+    constant_slots: Option<&'a FftConstantSlots>,
+    /// The constant slots ran out, so later dispatches are dropped and the transform is
+    /// incomplete. The caller reports the entity as skipped to run it again next frame.
+    overflowed: bool,
+    /// First line and line count across the axis while Bluestein passes run in batches; a zero
+    /// count covers every line.
+    batch: UVec2,
+}
+
+impl<'a, 'p> FftPass<'a, 'p> {
+    fn new(pass: &'a mut ComputePass<'p>, pipelines: &'a FftPipelines) -> Self {
+        Self {
+            pass,
+            constant_slots: pipelines.constant_slots(),
+            overflowed: false,
+            batch: UVec2::ZERO,
+        }
+    }
+
+    /// [`axis_extent`], narrowed to the current [`Self::batch`].
+    fn axis_extent(&self, settings: &FftSettings, axis: u32) -> (u32, UVec2) {
+        let (n, lines) = axis_extent(settings, axis);
+        if self.batch.y == 0 {
+            return (n, lines);
+        }
+        (n, UVec2::new(self.batch.y, lines.y))
+    }
+
+    /// Sets `pc` and dispatches `x × y × z` workgroups, unless the constant slots have run out.
+    fn dispatch(&mut self, pc: &FftPushConstants, x: u32, y: u32, z: u32) {
+        if self.overflowed {
+            return;
+        }
+        let pc = FftPushConstants {
+            line_base: self.batch.x,
+            line_span: self.batch.y,
+            ..*pc
+        };
+        let bytes = bytemuck::bytes_of(&pc);
+        match self.constant_slots {
+            Some(slots) => {
+                let Some(offset) = slots.push(bytes) else {
+                    self.overflowed = true;
+                    return;
+                };
+                self.pass.set_bind_group(1, slots.bind_group(), &[offset]);
+            }
+            None => self.pass.set_push_constants(0, bytes),
+        }
+        self.pass.dispatch_workgroups(x, y, z);
+    }
+}
+
+impl<'p> Deref for FftPass<'_, 'p> {
+    type Target = ComputePass<'p>;
+
+    fn deref(&self) -> &Self::Target {
+        self.pass
+    }
+}
+
+impl DerefMut for FftPass<'_, '_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.pass
+    }
+}
+
+/// Transform length along `axis` and the grid of lines across it, used as the `y` and `z`
+/// workgroup counts. Lines follow the logical grid; the length is the padded power of two, which
+/// only differs on Bluestein axes. Volume lines keep both cross axes so no count passes the
+/// per-dimension dispatch limit, and the lines of layered grids span every layer on `z`.
+fn axis_extent(settings: &FftSettings, axis: u32) -> (u32, UVec2) {
+    let logical = UVec3::new(settings.size.x, settings.size.y, settings.depth);
+    let transform = UVec3::new(settings.fft_size.x, settings.fft_size.y, settings.depth);
+    let (first, second) = match axis {
+        0 => (1, 2),
+        1 => (0, 2),
+        _ => (0, 1),
+    };
+    (
+        transform[axis as usize],
+        UVec2::new(logical[first], logical[second]),
+    )
+}
+
+/// Stage count along `axis`; the volume depth is always a power of two.
+fn axis_order(settings: &FftSettings, axis: u32) -> u32 {
+    match axis {
+        0 | 1 => settings.orders[axis as usize],
+        _ => settings.depth.trailing_zeros(),
+    }
+}
+
+/// Dispatches a kernel that handles one sample per invocation (`256 × 1` workgroups) along `axis`.
+#[allow(clippy::too_many_arguments)]
+fn fft_dispatch_line_kernel(
+    pass: &mut FftPass<'_, '_>,
+    pipeline: &ComputePipeline,
+    bind: &FftBindGroups,
+    settings: &FftSettings,
+    axis: u32,
+    src: u32,
+    dst: u32,
+    flags: u32,
+) {
+    pass.set_pipeline(pipeline);
+    bind.set(pass, 0);
+    let pc = FftPushConstants {
+        stage: 0,
+        axis,
+        src_buffer: src,
+        dst_buffer: dst,
+        flags,
+        ..Default::default()
+    };
+    let (n, lines) = pass.axis_extent(settings, axis);
+    pass.dispatch(&pc, n.div_ceil(256), lines.x, lines.y);
 }
 
+/// Runs one radix-2 DIT stage per order along `axis`, ping-ponging from `src`. Every line across
+/// the axis gets its own butterflies.
+#[allow(clippy::too_many_arguments)]
 fn fft_dispatch_dit_chain(
-    pass: &mut ComputePass<'_>,
-    pipeline: &bevy::render::render_resource::ComputePipeline,
-    bind: &bevy::render::render_resource::BindGroup,
-    orders: u32,
+    pass: &mut FftPass<'_, '_>,
+    pipeline: &ComputePipeline,
+    bind: &FftBindGroups,
+    settings: &FftSettings,
     axis: u32,
     mut src: u32,
     mut dst: u32,
-    n: u32,
-    forward_alpha: bool,
-    inverse_finalize_on_last: bool,
+    flags: AxisFlags,
 ) {
     pass.set_pipeline(pipeline);
-    pass.set_bind_group(0, bind, &[]);
-    let half_n = n / 2;
-    let gx = half_n.div_ceil(256);
+    bind.set(pass, 0);
+    let orders = axis_order(settings, axis);
+    let (n, lines) = pass.axis_extent(settings, axis);
+    let gx = (n / 2).div_ceil(256);
     for stage in 0..orders {
-        let mut flags = 0u32;
-        if forward_alpha {
-            flags |= FLAG_FORWARD_ALPHA;
-        }
-        if inverse_finalize_on_last && stage + 1 == orders {
-            flags |= FLAG_INVERSE_FINALIZE;
+        let mut stage_flags = flags.every;
+        if stage + 1 == orders {
+            stage_flags |= flags.last;
         }
         let pc = FftPushConstants {
             stage,
             axis,
             src_buffer: src,
             dst_buffer: dst,
-            flags,
+            flags: stage_flags,
+            ..Default::default()
         };
-        fft_set_push_constants(pass, &pc);
-        pass.dispatch_workgroups(gx, n, 1);
+        pass.dispatch(&pc, gx, lines.x, lines.y);
         std::mem::swap(&mut src, &mut dst);
     }
 }
 
-fn fft_dispatch_copy(
-    pass: &mut ComputePass<'_>,
-    pipeline: &bevy::render::render_resource::ComputePipeline,
-    bind: &bevy::render::render_resource::BindGroup,
+/// Stockham stage list for one axis of length `2^order`: a radix-2 stage first when `order` is
+/// odd, then radix-4 stages. Each item is `(radix, log2 ns)`, where `ns` is the sub-transform
+/// length finished by earlier stages (the `stage` push constant).
+fn stockham_stages(order: u32) -> impl Iterator<Item = (u32, u32)> {
+    let lead = order % 2;
+    (lead == 1)
+        .then_some((2, 0))
+        .into_iter()
+        .chain((lead..order).step_by(2).map(|ns_log2| (4, ns_log2)))
+}
+
+/// Buffer ids for one axis. The result must end in `dst`; `src` and `scratch` may be clobbered.
+#[derive(Clone, Copy, Debug)]
+struct AxisBuffers {
     src: u32,
     dst: u32,
-    n: u32,
-) {
-    pass.set_pipeline(pipeline);
-    pass.set_bind_group(0, bind, &[]);
-    let pc = FftPushConstants {
-        stage: 0,
-        axis: 0,
-        src_buffer: src,
-        dst_buffer: dst,
-        flags: 0,
-    };
-    fft_set_push_constants(pass, &pc);
-    let gx = n.div_ceil(16);
-    let gy = n.div_ceil(16);
-    pass.dispatch_workgroups(gx, gy, 1);
+    scratch: u32,
 }
 
-/// Forward 2D FFT: data must be in buffer **A**; spectrum ends in **C** (for real-to-complex style packing, put signal in A_re channel 0, A_im 0).
-pub fn run_forward_fft(
-    pipelines: &FftPipelines,
-    pipeline_cache: &PipelineCache,
-    pass: &mut ComputePass<'_>,
-    bind: &bevy::render::render_resource::BindGroup,
-    settings: &FftSettings,
-) {
-    let n = settings.size.x;
-    let orders = settings.orders;
-
-    let Some(br_h) = pipeline_cache.get_compute_pipeline(pipelines.forward_br_horizontal) else {
-        once!(error!("Missing forward_br_horizontal pipeline"));
-        return;
-    };
-    let Some(br_v) = pipeline_cache.get_compute_pipeline(pipelines.forward_br_vertical) else {
-        once!(error!("Missing forward_br_vertical pipeline"));
-        return;
+/// `(read, write)` buffer pairs for `count` out-of-place Stockham stages so the last stage writes
+/// `dst`. Odd counts ping-pong between `src` and `dst`; even counts start in `scratch`.
+fn stockham_stage_buffers(count: usize, buffers: AxisBuffers) -> impl Iterator<Item = (u32, u32)> {
+    let other = if count % 2 == 1 {
+        buffers.src
+    } else {
+        buffers.scratch
     };
-    let Some(dit) = pipeline_cache.get_compute_pipeline(pipelines.radix2_dit) else {
-        once!(error!("Missing radix2_dit pipeline"));
-        return;
-    };
-    let Some(cpy) = pipeline_cache.get_compute_pipeline(pipelines.fft_copy) else {
-        once!(error!("Missing fft_copy pipeline"));
-        return;
+    let write = move |i: usize| {
+        if (count - 1 - i).is_multiple_of(2) {
+            buffers.dst
+        } else {
+            other
+        }
     };
+    (0..count).map(move |i| {
+        let read = if i == 0 { buffers.src } else { write(i - 1) };
+        (read, write(i))
+    })
+}
 
-    {
-        pass.set_pipeline(br_h);
-        pass.set_bind_group(0, bind, &[]);
-        let gx = n.div_ceil(8);
-        let gy = n.div_ceil(8);
-        pass.dispatch_workgroups(gx, gy, 1);
+/// Flags for one axis: `first` goes on the pass that loads the input, `last` on the pass that
+/// writes the result, and `every` on all of them.
+#[derive(Clone, Copy, Default)]
+struct AxisFlags {
+    first: u32,
+    every: u32,
+    last: u32,
+}
+
+impl AxisFlags {
+    fn all(self) -> u32 {
+        self.first | self.every | self.last
     }
+}
 
-    fft_dispatch_dit_chain(pass, dit, bind, orders, 0, BUF_B, BUF_A, n, true, false);
+/// The radix-2 DIT chain: permute, one dispatch per stage, and the odd-order copy.
+struct Radix2Kernels<'a> {
+    bit_reverse: &'a ComputePipeline,
+    radix2_dit: &'a ComputePipeline,
+    copy: &'a ComputePipeline,
+}
 
-    if orders % 2 == 1 {
-        fft_dispatch_copy(pass, cpy, bind, BUF_A, BUF_B, n);
-    }
+/// Pipelines the axis drivers use, resolved from the cache once per pass.
+struct FftKernels<'a> {
+    radix2: Radix2Kernels<'a>,
+    stockham_radix2: &'a ComputePipeline,
+    stockham_radix4: &'a ComputePipeline,
+    bluestein_premultiply: &'a ComputePipeline,
+    bluestein_multiply: &'a ComputePipeline,
+    bluestein_postmultiply: &'a ComputePipeline,
+    /// `fft_shared_line` and its longest axis. `None` while compiling or when the device lacks
+    /// workgroup memory for it.
+    shared_line: Option<(&'a ComputePipeline, u32)>,
+}
 
-    {
-        pass.set_pipeline(br_v);
-        pass.set_bind_group(0, bind, &[]);
-        let gx = n.div_ceil(8);
-        let gy = n.div_ceil(8);
-        pass.dispatch_workgroups(gx, gy, 1);
+fn cached_kernel<'a>(
+    pipeline_cache: &'a PipelineCache,
+    id: CachedComputePipelineId,
+    name: &str,
+) -> Option<&'a ComputePipeline> {
+    let pipeline = pipeline_cache.get_compute_pipeline(id);
+    if pipeline.is_none() {
+        once!(error!("Missing {name} pipeline"));
     }
+    pipeline
+}
+
+fn fft_volume_kernels<'a>(
+    pipelines: &FftPipelines,
+    pipeline_cache: &'a PipelineCache,
+    settings: &FftSettings,
+) -> Option<Radix2Kernels<'a>> {
+    let get = |id, name| cached_kernel(pipeline_cache, id, name);
+    let volume = pipelines.volume_kernels(settings)?;
+    Some(Radix2Kernels {
+        bit_reverse: get(volume.bit_reverse, "volume_bit_reverse")?,
+        radix2_dit: get(volume.radix2_dit, "volume_radix2_dit")?,
+        copy: get(volume.fft_copy, "volume_copy")?,
+    })
+}
 
-    fft_dispatch_dit_chain(pass, dit, bind, orders, 1, BUF_C, BUF_B, n, true, false);
+fn fft_kernels<'a>(
+    pipelines: &FftPipelines,
+    pipeline_cache: &'a PipelineCache,
+    settings: &FftSettings,
+) -> Option<FftKernels<'a>> {
+    let get = |id, name| cached_kernel(pipeline_cache, id, name);
+    let lines = pipelines.line_kernels(settings)?;
+    let shared_line = lines
+        .shared_line
+        .and_then(|id| pipeline_cache.get_compute_pipeline(id))
+        .map(|pipeline| (pipeline, pipelines.shared_line_max_len));
+    Some(FftKernels {
+        radix2: Radix2Kernels {
+            bit_reverse: get(lines.bit_reverse, "bit_reverse")?,
+            radix2_dit: get(lines.radix2_dit, "radix2_dit")?,
+            copy: get(lines.fft_copy, "fft_copy")?,
+        },
+        stockham_radix2: get(lines.stockham_radix2, "stockham_radix2")?,
+        stockham_radix4: get(lines.stockham_radix4, "stockham_radix4")?,
+        bluestein_premultiply: get(lines.bluestein_premultiply, "bluestein_premultiply")?,
+        bluestein_multiply: get(lines.bluestein_multiply, "bluestein_multiply")?,
+        bluestein_postmultiply: get(lines.bluestein_postmultiply, "bluestein_postmultiply")?,
+        shared_line,
+    })
+}
 
-    if orders % 2 == 1 {
-        fft_dispatch_copy(pass, cpy, bind, BUF_B, BUF_C, n);
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum FftDirection {
+    Forward,
+    Inverse,
+}
+
+impl FftDirection {
+    /// Flags for the kernels that fold pre- and post-processing into their loads and stores. The
+    /// forward window only tapers the axes set in [`FftSettings::window_axes`].
+    fn axis_flags(self, settings: &FftSettings, axis: u32) -> AxisFlags {
+        match self {
+            FftDirection::Forward => AxisFlags {
+                first: if settings.window_axes & (1 << axis) != 0 {
+                    FLAG_FORWARD_WINDOW
+                } else {
+                    0
+                },
+                every: FLAG_FORWARD_ALPHA,
+                last: FLAG_FORWARD_SCALE,
+            },
+            FftDirection::Inverse => AxisFlags {
+                first: FLAG_INVERSE_CONJ_INPUT,
+                every: 0,
+                last: FLAG_INVERSE_FINALIZE,
+            },
+        }
     }
 }
 
-/// Inverse 2D FFT: spectrum in **C**; result real parts primarily in **B** after the pass.
-pub fn run_inverse_fft(
-    pipelines: &FftPipelines,
-    pipeline_cache: &PipelineCache,
-    pass: &mut ComputePass<'_>,
-    bind: &bevy::render::render_resource::BindGroup,
+fn fft_dispatch_stockham_axis(
+    pass: &mut FftPass<'_, '_>,
+    kernels: &FftKernels<'_>,
+    bind: &FftBindGroups,
     settings: &FftSettings,
+    axis: u32,
+    buffers: AxisBuffers,
+    flags: AxisFlags,
 ) {
-    let n = settings.size.x;
-    let orders = settings.orders;
-
-    let Some(br_h) = pipeline_cache.get_compute_pipeline(pipelines.inverse_br_horizontal) else {
-        once!(error!("Missing inverse_br_horizontal pipeline"));
-        return;
-    };
-    let Some(br_v) = pipeline_cache.get_compute_pipeline(pipelines.inverse_br_vertical) else {
-        once!(error!("Missing inverse_br_vertical pipeline"));
-        return;
-    };
-    let Some(dit) = pipeline_cache.get_compute_pipeline(pipelines.radix2_dit) else {
-        once!(error!("Missing radix2_dit pipeline"));
-        return;
-    };
-    let Some(cpy) = pipeline_cache.get_compute_pipeline(pipelines.fft_copy) else {
-        once!(error!("Missing fft_copy pipeline"));
+    let stages: Vec<(u32, u32)> = stockham_stages(axis_order(settings, axis)).collect();
+    if stages.is_empty() {
+        // Length-one axis: the permute is the identity and still applies the flags.
+        fft_dispatch_line_kernel(
+            pass,
+            kernels.radix2.bit_reverse,
+            bind,
+            settings,
+            axis,
+            buffers.src,
+            buffers.dst,
+            flags.all(),
+        );
         return;
+    }
+    let (n, lines) = pass.axis_extent(settings, axis);
+    let count = stages.len();
+    for (i, ((radix, ns_log2), (src, dst))) in stages
+        .into_iter()
+        .zip(stockham_stage_buffers(count, buffers))
+        .enumerate()
+    {
+        let mut stage_flags = flags.every;
+        if i == 0 {
+            stage_flags |= flags.first;
+        }
+        if i + 1 == count {
+            stage_flags |= flags.last;
+        }
+        let pipeline = if radix == 4 {
+            kernels.stockham_radix4
+        } else {
+            kernels.stockham_radix2
+        };
+        pass.set_pipeline(pipeline);
+        bind.set(pass, 0);
+        let pc = FftPushConstants {
+            stage: ns_log2,
+            axis,
+            src_buffer: src,
+            dst_buffer: dst,
+            flags: stage_flags,
+            ..Default::default()
+        };
+        pass.dispatch(&pc, (n / radix).div_ceil(256), lines.x, lines.y);
+    }
+}
+
+/// Bit-reverse into `dst`, then ping-pong DIT stages between `dst` and `src`, copying back when
+/// the stage count is odd. Never touches `scratch`.
+fn fft_dispatch_radix2_axis(
+    pass: &mut FftPass<'_, '_>,
+    kernels: &Radix2Kernels<'_>,
+    bind: &FftBindGroups,
+    settings: &FftSettings,
+    axis: u32,
+    buffers: AxisBuffers,
+    flags: AxisFlags,
+) {
+    let order = axis_order(settings, axis);
+    let mut permute_flags = flags.first | flags.every;
+    if order == 0 {
+        permute_flags |= flags.last;
+    }
+    fft_dispatch_line_kernel(
+        pass,
+        kernels.bit_reverse,
+        bind,
+        settings,
+        axis,
+        buffers.src,
+        buffers.dst,
+        permute_flags,
+    );
+    let chain_flags = AxisFlags { first: 0, ..flags };
+    fft_dispatch_dit_chain(
+        pass,
+        kernels.radix2_dit,
+        bind,
+        settings,
+        axis,
+        buffers.dst,
+        buffers.src,
+        chain_flags,
+    );
+    if order % 2 == 1 {
+        fft_dispatch_line_kernel(
+            pass,
+            kernels.copy,
+            bind,
+            settings,
+            axis,
+            buffers.src,
+            buffers.dst,
+            0,
+        );
+    }
+}
+
+/// One workgroup per line runs every stage of the axis in workgroup memory.
+fn fft_dispatch_shared_line(
+    pass: &mut FftPass<'_, '_>,
+    pipeline: &ComputePipeline,
+    bind: &FftBindGroups,
+    settings: &FftSettings,
+    axis: u32,
+    buffers: AxisBuffers,
+    flags: AxisFlags,
+) {
+    pass.set_pipeline(pipeline);
+    bind.set(pass, 0);
+    let pc = FftPushConstants {
+        stage: 0,
+        axis,
+        src_buffer: buffers.src,
+        dst_buffer: buffers.dst,
+        flags: flags.all(),
+        ..Default::default()
     };
+    let (_, lines) = pass.axis_extent(settings, axis);
+    pass.dispatch(&pc, lines.x, lines.y, 1);
+}
 
+/// Transforms a power-of-two axis. Axes that fit in workgroup memory take the single-dispatch
+/// shared kernel; longer ones use the stages `radix` picks.
+#[allow(clippy::too_many_arguments)]
+fn fft_dispatch_power_of_two_axis(
+    pass: &mut FftPass<'_, '_>,
+    kernels: &FftKernels<'_>,
+    bind: &FftBindGroups,
+    settings: &FftSettings,
+    axis: u32,
+    buffers: AxisBuffers,
+    flags: AxisFlags,
+    radix: FftRadix,
+) {
+    let (n, _) = axis_extent(settings, axis);
+    if let Some((shared_line, max_len)) = kernels.shared_line
+        && n <= max_len
     {
-        pass.set_pipeline(br_h);
-        pass.set_bind_group(0, bind, &[]);
-        let gx = n.div_ceil(8);
-        let gy = n.div_ceil(8);
-        pass.dispatch_workgroups(gx, gy, 1);
+        fft_dispatch_shared_line(pass, shared_line, bind, settings, axis, buffers, flags);
+        return;
     }
+    match radix {
+        FftRadix::Radix2 => {
+            fft_dispatch_radix2_axis(pass, &kernels.radix2, bind, settings, axis, buffers, flags);
+        }
+        FftRadix::Radix4 => {
+            fft_dispatch_stockham_axis(pass, kernels, bind, settings, axis, buffers, flags);
+        }
+    }
+}
 
-    fft_dispatch_dit_chain(pass, dit, bind, orders, 0, BUF_A, BUF_C, n, false, true);
+/// Bluestein's algorithm for a non-power-of-two axis, run in the two workspace slots so the
+/// padded lines never touch the grid textures. Lines go through in batches of
+/// [`bluestein_batch_lines`], which the workspace is sized for.
+fn fft_dispatch_bluestein_axis(
+    pass: &mut FftPass<'_, '_>,
+    kernels: &FftKernels<'_>,
+    bind: &FftBindGroups,
+    settings: &FftSettings,
+    axis: u32,
+    buffers: AxisBuffers,
+    flags: AxisFlags,
+) {
+    let (_, lines) = axis_extent(settings, axis);
+    let batch = bluestein_batch_lines(settings, axis as usize);
+    for base in (0..lines.x).step_by(batch as usize) {
+        pass.batch = UVec2::new(base, batch.min(lines.x - base));
+        fft_dispatch_bluestein_batch(pass, kernels, bind, settings, axis, buffers, flags);
+    }
+    pass.batch = UVec2::ZERO;
+}
+
+/// One batch of [`fft_dispatch_bluestein_axis`]. The inner transforms stay on radix-2 or the
+/// shared kernel because the workspace has no third slot for Stockham's scratch.
+fn fft_dispatch_bluestein_batch(
+    pass: &mut FftPass<'_, '_>,
+    kernels: &FftKernels<'_>,
+    bind: &FftBindGroups,
+    settings: &FftSettings,
+    axis: u32,
+    buffers: AxisBuffers,
+    flags: AxisFlags,
+) {
+    fft_dispatch_line_kernel(
+        pass,
+        kernels.bluestein_premultiply,
+        bind,
+        settings,
+        axis,
+        buffers.src,
+        BUF_BLUESTEIN_0,
+        flags.first,
+    );
+    let forward = AxisBuffers {
+        src: BUF_BLUESTEIN_0,
+        dst: BUF_BLUESTEIN_1,
+        scratch: BUF_BLUESTEIN_0,
+    };
+    let plain = AxisFlags::default();
+    fft_dispatch_power_of_two_axis(
+        pass,
+        kernels,
+        bind,
+        settings,
+        axis,
+        forward,
+        plain,
+        FftRadix::Radix2,
+    );
+    fft_dispatch_line_kernel(
+        pass,
+        kernels.bluestein_multiply,
+        bind,
+        settings,
+        axis,
+        BUF_BLUESTEIN_1,
+        BUF_BLUESTEIN_1,
+        0,
+    );
+    let back = AxisBuffers {
+        src: BUF_BLUESTEIN_1,
+        dst: BUF_BLUESTEIN_0,
+        scratch: BUF_BLUESTEIN_1,
+    };
+    fft_dispatch_power_of_two_axis(
+        pass,
+        kernels,
+        bind,
+        settings,
+        axis,
+        back,
+        plain,
+        FftRadix::Radix2,
+    );
+    fft_dispatch_line_kernel(
+        pass,
+        kernels.bluestein_postmultiply,
+        bind,
+        settings,
+        axis,
+        BUF_BLUESTEIN_0,
+        buffers.dst,
+        flags.every | flags.last,
+    );
+}
 
-    if orders % 2 == 1 {
-        fft_dispatch_copy(pass, cpy, bind, BUF_C, BUF_A, n);
+/// Transforms one axis from `buffers.src` into `buffers.dst`, through Bluestein when the edge is
+/// not a power of two.
+fn fft_dispatch_axis(
+    pass: &mut FftPass<'_, '_>,
+    kernels: &FftKernels<'_>,
+    bind: &FftBindGroups,
+    settings: &FftSettings,
+    axis: u32,
+    buffers: AxisBuffers,
+    direction: FftDirection,
+) {
+    let flags = direction.axis_flags(settings, axis);
+    if settings.size[axis as usize].is_power_of_two() {
+        let radix = FftRadix::try_from_bits(settings.radix).unwrap_or_default();
+        fft_dispatch_power_of_two_axis(pass, kernels, bind, settings, axis, buffers, flags, radix);
+    } else {
+        fft_dispatch_bluestein_axis(pass, kernels, bind, settings, axis, buffers, flags);
     }
+}
 
-    {
-        pass.set_pipeline(br_v);
-        pass.set_bind_group(0, bind, &[]);
-        let gx = n.div_ceil(8);
-        let gy = n.div_ceil(8);
-        pass.dispatch_workgroups(gx, gy, 1);
+/// Runs the radix-2 DIT chain along rows, columns and depth of a volume, moving through `path`
+/// one axis at a time so the result ends in its last buffer.
+fn fft_dispatch_volume(
+    pipelines: &FftPipelines,
+    pipeline_cache: &PipelineCache,
+    pass: &mut FftPass<'_, '_>,
+    bind: &FftBindGroups,
+    settings: &FftSettings,
+    path: [u32; 4],
+    direction: FftDirection,
+) -> bool {
+    let Some(kernels) = fft_volume_kernels(pipelines, pipeline_cache, settings) else {
+        return false;
+    };
+    for axis in 0..3 {
+        // The radix-2 chain never touches scratch.
+        let buffers = AxisBuffers {
+            src: path[axis],
+            dst: path[axis + 1],
+            scratch: path[axis],
+        };
+        let axis = axis as u32;
+        let flags = direction.axis_flags(settings, axis);
+        fft_dispatch_radix2_axis(pass, &kernels, bind, settings, axis, buffers, flags);
     }
+    true
+}
 
-    fft_dispatch_dit_chain(pass, dit, bind, orders, 1, BUF_B, BUF_A, n, false, true);
+/// Forward 2D FFT: data must be in buffer **A**; spectrum ends in **C** (for real-to-complex style packing, put signal in A_re channel 0, A_im 0).
+///
+/// Rows go **A** → **B** and columns **B** → **C**. With [`super::FftDimension::OneD`] only the rows
+/// run, straight from **A** into **C** with **B** as scratch. Axes short enough for
+/// [`FftLinePipelines::shared_line`](super::resources::FftLinePipelines::shared_line) run in one dispatch each; otherwise [`FftSettings::radix`] picks
+/// radix-2 DIT or radix-4 Stockham stages. Non-power-of-two axes run Bluestein's algorithm in a
+/// separate workspace. Every path leaves the same bins in **C**. Volumes
+/// ([`super::FftDimension::ThreeD`]) run radix-2 DIT along each axis through
+/// **A** → **C** → **B** → **C**, leaving **A** intact. Sources with several
+/// [`super::FftSource::layers`] transform every layer within the same dispatches.
+///
+/// Returns `false` without recording anything while the kernels for `settings` are still
+/// compiling, and after recording part of the transform when the frame ran out of constant slots
+/// on devices without push constants. Either way the entity should run again.
+pub fn run_forward_fft(
+    pipelines: &FftPipelines,
+    pipeline_cache: &PipelineCache,
+    pass: &mut ComputePass<'_>,
+    bind: &FftBindGroups,
+    settings: &FftSettings,
+) -> bool {
+    let pass = &mut FftPass::new(pass, pipelines);
+    if settings.is_volume() {
+        let path = [BUF_A, BUF_C, BUF_B, BUF_C];
+        let direction = FftDirection::Forward;
+        return fft_dispatch_volume(
+            pipelines,
+            pipeline_cache,
+            pass,
+            bind,
+            settings,
+            path,
+            direction,
+        ) && !pass.overflowed;
+    }
+    let Some(kernels) = fft_kernels(pipelines, pipeline_cache, settings) else {
+        return false;
+    };
+    let direction = FftDirection::Forward;
+    if settings.transformed_axes() == 1 {
+        let rows = AxisBuffers {
+            src: BUF_A,
+            dst: BUF_C,
+            scratch: BUF_B,
+        };
+        fft_dispatch_axis(pass, &kernels, bind, settings, 0, rows, direction);
+        return !pass.overflowed;
+    }
+    let rows = AxisBuffers {
+        src: BUF_A,
+        dst: BUF_B,
+        scratch: BUF_C,
+    };
+    let columns = AxisBuffers {
+        src: BUF_B,
+        dst: BUF_C,
+        scratch: BUF_A,
+    };
+    fft_dispatch_axis(pass, &kernels, bind, settings, 0, rows, direction);
+    fft_dispatch_axis(pass, &kernels, bind, settings, 1, columns, direction);
+    !pass.overflowed
+}
 
-    if orders % 2 == 1 {
-        fft_dispatch_copy(pass, cpy, bind, BUF_A, BUF_B, n);
+/// Inverse 2D FFT: spectrum in **C**; result real parts primarily in **B** after the pass.
+///
+/// Rows go **C** → **A** and columns **A** → **B**, with the same kernel choice as
+/// [`run_forward_fft`]. **C** is scratch. With [`super::FftDimension::OneD`] the rows go **C** → **B**
+/// with **A** as scratch. Volumes go **C** → **B** → **A** → **B** and leave **C** intact.
+/// Returns `false` like [`run_forward_fft`].
+pub fn run_inverse_fft(
+    pipelines: &FftPipelines,
+    pipeline_cache: &PipelineCache,
+    pass: &mut ComputePass<'_>,
+    bind: &FftBindGroups,
+    settings: &FftSettings,
+) -> bool {
+    let pass = &mut FftPass::new(pass, pipelines);
+    if settings.is_volume() {
+        let path = [BUF_C, BUF_B, BUF_A, BUF_B];
+        let direction = FftDirection::Inverse;
+        return fft_dispatch_volume(
+            pipelines,
+            pipeline_cache,
+            pass,
+            bind,
+            settings,
+            path,
+            direction,
+        ) && !pass.overflowed;
+    }
+    let Some(kernels) = fft_kernels(pipelines, pipeline_cache, settings) else {
+        return false;
+    };
+    let direction = FftDirection::Inverse;
+    if settings.transformed_axes() == 1 {
+        let rows = AxisBuffers {
+            src: BUF_C,
+            dst: BUF_B,
+            scratch: BUF_A,
+        };
+        fft_dispatch_axis(pass, &kernels, bind, settings, 0, rows, direction);
+        return !pass.overflowed;
     }
+    let rows = AxisBuffers {
+        src: BUF_C,
+        dst: BUF_A,
+        scratch: BUF_B,
+    };
+    let columns = AxisBuffers {
+        src: BUF_A,
+        dst: BUF_B,
+        scratch: BUF_C,
+    };
+    fft_dispatch_axis(pass, &kernels, bind, settings, 0, rows, direction);
+    fft_dispatch_axis(pass, &kernels, bind, settings, 1, columns, direction);
+    !pass.overflowed
 }
 
+/// Signature of [`run_forward_fft`] and [`run_inverse_fft`].
+type FftRun =
+    fn(&FftPipelines, &PipelineCache, &mut ComputePass<'_>, &FftBindGroups, &FftSettings) -> bool;
+
 impl Node for FftComputeNode {
     fn update(&mut self, world: &mut World) {
         self.query.update_archetypes(world);
@@ -309,66 +916,64 @@ impl Node for FftComputeNode {
     ) -> Result<(), NodeRunError> {
         let pipelines = world.resource::<FftPipelines>();
         let pipeline_cache = world.resource::<PipelineCache>();
+        let skipped = world.resource::<FftSkippedRuns>();
+        let diagnostics = render_context.diagnostic_recorder();
         let node_label = graph.label();
+        let (label, timed, run_fft) = if node_label == FftNode::ComputeFFT.intern() {
+            (
+                "fft_forward",
+                FftTimedPass::Forward,
+                run_forward_fft as FftRun,
+            )
+        } else if node_label == FftNode::ComputeIFFT.intern() {
+            (
+                "fft_inverse",
+                FftTimedPass::Inverse,
+                run_inverse_fft as FftRun,
+            )
+        } else {
+            once!(error!(
+                "FftComputeNode used with invalid label: {:?}",
+                node_label
+            ));
+            return Ok(());
+        };
 
-        for (bind_groups, settings) in self.query.iter_manual(world) {
+        for (entity, main_entity, bind_groups, settings, triggered) in self.query.iter_manual(world)
+        {
+            if FftTriggered::is_idle(triggered) {
+                continue;
+            }
             let schedule =
                 FftSchedule::try_from_bits(settings.schedule).unwrap_or(FftSchedule::Forward);
 
-            if node_label == FftNode::ComputeFFT.intern()
-                && matches!(schedule, FftSchedule::Inverse)
-            {
+            if timed == FftTimedPass::Forward && matches!(schedule, FftSchedule::Inverse) {
                 once!(info!(
                     "Skipping forward FFT because schedule is FftSchedule::Inverse"
                 ));
                 continue;
             }
 
-            if node_label == FftNode::ComputeIFFT.intern()
-                && matches!(schedule, FftSchedule::Forward)
-            {
+            if timed == FftTimedPass::Inverse && matches!(schedule, FftSchedule::Forward) {
                 continue;
             }
 
             let command_encoder = render_context.command_encoder();
-            let label = if node_label == FftNode::ComputeFFT.intern() {
-                "fft_forward"
-            } else {
-                "fft_inverse"
-            };
-
             let mut compute_pass = command_encoder.begin_compute_pass(&ComputePassDescriptor {
-                label: Some(label.into()),
+                label: Some(label),
                 timestamp_writes: None,
             });
-
-            if node_label == FftNode::ComputeFFT.intern() {
-                if !matches!(schedule, FftSchedule::Inverse) {
-                    run_forward_fft(
-                        pipelines,
-                        pipeline_cache,
-                        &mut compute_pass,
-                        &bind_groups.common,
-                        settings,
-                    );
-                }
-            } else if node_label == FftNode::ComputeIFFT.intern() {
-                if !matches!(schedule, FftSchedule::Forward) {
-                    run_inverse_fft(
-                        pipelines,
-                        pipeline_cache,
-                        &mut compute_pass,
-                        &bind_groups.common,
-                        settings,
-                    );
-                }
-            } else {
-                once!(error!(
-                    "FftComputeNode used with invalid label: {:?}",
-                    node_label
-                ));
-                return Ok(());
+            let span = diagnostics.pass_span(&mut compute_pass, timed.span(main_entity.id()));
+            if !run_fft(
+                pipelines,
+                pipeline_cache,
+                &mut compute_pass,
+                bind_groups,
+                settings,
+            ) {
+                skipped.push(entity);
             }
+            span.end(&mut compute_pass);
         }
 
         Ok(())
@@ -376,7 +981,12 @@ impl Node for FftComputeNode {
 }
 
 pub(super) struct FftResolveSpectrumNode {
-    query: QueryState<(&'static FftResolveBindGroups, &'static FftSettings)>,
+    query: QueryState<(
+        &'static MainEntity,
+        &'static FftResolveBindGroups,
+        &'static FftSettings,
+        Option<&'static FftTriggered>,
+    )>,
 }
 
 impl FromWorld for FftResolveSpectrumNode {
@@ -400,25 +1010,34 @@ impl Node for FftResolveSpectrumNode {
     ) -> Result<(), NodeRunError> {
         let pipelines = world.resource::<FftPipelines>();
         let pipeline_cache = world.resource::<PipelineCache>();
-
-        let Some(pipeline) = pipeline_cache.get_compute_pipeline(pipelines.resolve_spectrum) else {
-            return Ok(());
-        };
-
+        let diagnostics = render_context.diagnostic_recorder();
         let command_encoder = render_context.command_encoder();
-        let mut compute_pass = command_encoder.begin_compute_pass(&ComputePassDescriptor {
-            label: Some("fft_resolve_spectrum_pass".into()),
-            timestamp_writes: None,
-        });
-
-        compute_pass.set_pipeline(pipeline);
 
         let wg = 16u32;
-        for (bind, settings) in self.query.iter_manual(world) {
-            compute_pass.set_bind_group(0, &bind.group, &[]);
+        for (main_entity, bind, settings, triggered) in self.query.iter_manual(world) {
+            if FftTriggered::is_idle(triggered) {
+                continue;
+            }
+            let Some(pipeline) = pipelines
+                .line_kernels(settings)
+                .and_then(|lines| pipeline_cache.get_compute_pipeline(lines.resolve_spectrum))
+            else {
+                continue;
+            };
+            let mut compute_pass = command_encoder.begin_compute_pass(&ComputePassDescriptor {
+                label: Some("fft_resolve_spectrum_pass"),
+                timestamp_writes: None,
+            });
+            let span = diagnostics.pass_span(
+                &mut compute_pass,
+                FftTimedPass::ResolveSpectrum.span(main_entity.id()),
+            );
+            compute_pass.set_pipeline(pipeline);
+            compute_pass.set_bind_group(0, &bind.group, &[bind.settings_offset]);
             let nx = settings.size.x.div_ceil(wg);
             let ny = settings.size.y.div_ceil(wg);
-            compute_pass.dispatch_workgroups(nx, ny, 1);
+            compute_pass.dispatch_workgroups(nx, ny, settings.layers());
+            span.end(&mut compute_pass);
         }
 
         Ok(())
@@ -426,7 +1045,12 @@ impl Node for FftResolveSpectrumNode {
 }
 
 pub(super) struct FftResolveOutputsNode {
-    query: QueryState<(&'static FftResolveBindGroups, &'static FftSettings)>,
+    query: QueryState<(
+        &'static MainEntity,
+        &'static FftResolveBindGroups,
+        &'static FftSettings,
+        Option<&'static FftTriggered>,
+    )>,
 }
 
 impl FromWorld for FftResolveOutputsNode {
@@ -450,27 +1074,230 @@ impl Node for FftResolveOutputsNode {
     ) -> Result<(), NodeRunError> {
         let pipelines = world.resource::<FftPipelines>();
         let pipeline_cache = world.resource::<PipelineCache>();
+        let diagnostics = render_context.diagnostic_recorder();
+        let command_encoder = render_context.command_encoder();
 
-        let Some(pipeline) = pipeline_cache.get_compute_pipeline(pipelines.resolve_spatial) else {
-            return Ok(());
+        let wg = 16u32;
+        for (main_entity, bind, settings, triggered) in self.query.iter_manual(world) {
+            if FftTriggered::is_idle(triggered) {
+                continue;
+            }
+            let Some(pipeline) = pipelines
+                .line_kernels(settings)
+                .and_then(|lines| pipeline_cache.get_compute_pipeline(lines.resolve_spatial))
+            else {
+                continue;
+            };
+            let mut compute_pass = command_encoder.begin_compute_pass(&ComputePassDescriptor {
+                label: Some("fft_resolve_spatial_pass"),
+                timestamp_writes: None,
+            });
+            let span = diagnostics.pass_span(
+                &mut compute_pass,
+                FftTimedPass::ResolveOutputs.span(main_entity.id()),
+            );
+            compute_pass.set_pipeline(pipeline);
+            compute_pass.set_bind_group(0, &bind.group, &[bind.settings_offset]);
+            // Covers only the signal, so padded borders never reach the output.
+            let signal = settings.signal_size();
+            let nx = signal.x.div_ceil(wg);
+            let ny = signal.y.div_ceil(wg);
+            compute_pass.dispatch_workgroups(nx, ny, settings.layers());
+            span.end(&mut compute_pass);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod stockham_tests {
+    //! CPU mirrors of the `fft_radix2_dit` and `fft_stockham_radix*` index math in `fft.wgsl`,
+    //! driven by the same stage plan and buffer rotation as the dispatch code.
+
+    use super::{AxisBuffers, stockham_stage_buffers, stockham_stages};
+    use crate::complex::c32;
+    use crate::fft::forward_fft_twiddle_table;
+
+    /// `get_fft_root` in `fft_common.wgsl`.
+    fn root(roots: &[c32], stage_plus_one: u32, index: u32) -> c32 {
+        let base = 1u32 << stage_plus_one;
+        let count = (base >> 1).max(1);
+        roots[(base + index % count) as usize]
+    }
+
+    /// `get_fft_twiddle` in `fft_common.wgsl`.
+    fn twiddle(roots: &[c32], order: u32, m: u32) -> c32 {
+        if order == 0 {
+            return c32::new(1.0, 0.0);
+        }
+        let base = 1u32 << order;
+        let half = base >> 1;
+        if m < half {
+            roots[(base + m) as usize]
+        } else {
+            -roots[(base + m - half) as usize]
+        }
+    }
+
+    /// Bit-reverse permute, then one `fft_radix2_dit` pass per stage.
+    fn radix2_dit(roots: &[c32], input: &[c32], order: u32) -> Vec<c32> {
+        let n = input.len();
+        let mut src = vec![c32::new(0.0, 0.0); n];
+        for (i, v) in input.iter().enumerate() {
+            let r = (i as u32)
+                .reverse_bits()
+                .checked_shr(32 - order)
+                .unwrap_or(0);
+            src[r as usize] = *v;
+        }
+        for stage in 0..order {
+            let mut dst = src.clone();
+            let m = 1usize << (stage + 1);
+            let m2 = m >> 1;
+            for butterfly in 0..n / 2 {
+                let j = butterfly % m2;
+                let u = (butterfly / m2) * m + j;
+                let v = u + m2;
+                let a = src[u];
+                let b = src[v] * root(roots, stage + 1, j as u32);
+                dst[u] = a + b;
+                dst[v] = a - b;
+            }
+            src = dst;
+        }
+        src
+    }
+
+    /// `fft_stockham_radix2` / `fft_stockham_radix4` over three buffers, result read from `dst`.
+    fn stockham(roots: &[c32], input: &[c32], order: u32) -> Vec<c32> {
+        let n = input.len();
+        let zero = c32::new(0.0, 0.0);
+        let mut buffers = [input.to_vec(), vec![zero; n], vec![zero; n]];
+        let plan = AxisBuffers {
+            src: 0,
+            dst: 1,
+            scratch: 2,
         };
+        let stages: Vec<(u32, u32)> = stockham_stages(order).collect();
+        for ((radix, ns_log2), (read, write)) in stages
+            .iter()
+            .copied()
+            .zip(stockham_stage_buffers(stages.len(), plan))
+        {
+            assert_ne!(read, write, "Stockham stages must be out of place");
+            let src = buffers[read as usize].clone();
+            let dst = &mut buffers[write as usize];
+            let ns = 1usize << ns_log2;
+            let r = radix as usize;
+            let stride = n / r;
+            for j in 0..stride {
+                let k = j & (ns - 1);
+                let base = (j - k) * r + k;
+                let order = ns_log2 + radix.trailing_zeros();
+                let v: Vec<c32> = (0..r)
+                    .map(|q| src[j + q * stride] * twiddle(roots, order, (q * k) as u32))
+                    .collect();
+                if radix == 2 {
+                    dst[base] = v[0] + v[1];
+                    dst[base + ns] = v[0] - v[1];
+                } else {
+                    let mul_neg_i = |c: c32| c32::new(c.im, -c.re);
+                    let a0 = v[0] + v[2];
+                    let a1 = v[0] - v[2];
+                    let a2 = v[1] + v[3];
+                    let a3 = mul_neg_i(v[1] - v[3]);
+                    dst[base] = a0 + a2;
+                    dst[base + ns] = a1 + a3;
+                    dst[base + 2 * ns] = a0 - a2;
+                    dst[base + 3 * ns] = a1 - a3;
+                }
+            }
+        }
+        buffers[1].clone()
+    }
 
-        let command_encoder = render_context.command_encoder();
-        let mut compute_pass = command_encoder.begin_compute_pass(&ComputePassDescriptor {
-            label: Some("fft_resolve_spatial_pass".into()),
-            timestamp_writes: None,
-        });
+    fn test_signal(n: usize) -> Vec<c32> {
+        let mut state = 0x2545_f491u32;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            (state as f32 / u32::MAX as f32) * 2.0 - 1.0
+        };
+        (0..n).map(|_| c32::new(next(), next())).collect()
+    }
 
-        compute_pass.set_pipeline(pipeline);
+    fn naive_dft(input: &[c32]) -> Vec<c32> {
+        let n = input.len();
+        (0..n)
+            .map(|k| {
+                input
+                    .iter()
+                    .enumerate()
+                    .fold(c32::new(0.0, 0.0), |acc, (t, x)| {
+                        let theta = -2.0 * std::f32::consts::PI * ((k * t) % n) as f32 / n as f32;
+                        acc + *x * c32::cis(theta)
+                    })
+            })
+            .collect()
+    }
 
-        let wg = 16u32;
-        for (bind, settings) in self.query.iter_manual(world) {
-            compute_pass.set_bind_group(0, &bind.group, &[]);
-            let nx = settings.size.x.div_ceil(wg);
-            let ny = settings.size.y.div_ceil(wg);
-            compute_pass.dispatch_workgroups(nx, ny, 1);
+    fn assert_bins_close(expected: &[c32], actual: &[c32], what: &str) {
+        let tolerance = 1e-5 * expected.len() as f32;
+        for (i, (e, a)) in expected.iter().zip(actual).enumerate() {
+            assert!(
+                (e.re - a.re).abs() < tolerance && (e.im - a.im).abs() < tolerance,
+                "{what} n={} bin={i}: expected {e}, got {a}",
+                expected.len(),
+            );
         }
+    }
 
-        Ok(())
+    #[test]
+    fn stockham_matches_radix2_dit() {
+        let roots = forward_fft_twiddle_table(10);
+        for order in 1u32..=10 {
+            let input = test_signal(1 << order);
+            let expected = radix2_dit(&roots, &input, order);
+            if order <= 6 {
+                assert_bins_close(&naive_dft(&input), &expected, "radix-2");
+            }
+            assert_bins_close(&expected, &stockham(&roots, &input, order), "Stockham");
+        }
+    }
+
+    #[test]
+    fn stockham_stages_cover_order() {
+        assert_eq!(stockham_stages(0).count(), 0);
+        for order in 1u32..=13 {
+            let mut ns_log2 = 0;
+            for (radix, stage) in stockham_stages(order) {
+                assert_eq!(stage, ns_log2, "order={order}");
+                ns_log2 += radix.trailing_zeros();
+            }
+            assert_eq!(ns_log2, order);
+            assert_eq!(stockham_stages(order).count() as u32, order.div_ceil(2));
+        }
+    }
+
+    #[test]
+    fn stockham_stage_buffers_end_in_dst() {
+        let plan = AxisBuffers {
+            src: 0,
+            dst: 1,
+            scratch: 2,
+        };
+        for count in 1..8 {
+            let pairs: Vec<(u32, u32)> = stockham_stage_buffers(count, plan).collect();
+            assert_eq!(pairs[0].0, plan.src);
+            assert_eq!(pairs.last().unwrap().1, plan.dst);
+            for (i, (read, write)) in pairs.iter().enumerate() {
+                assert_ne!(read, write, "count={count} stage={i}");
+                if i > 0 {
+                    assert_eq!(*read, pairs[i - 1].1, "count={count} stage={i}");
+                }
+            }
+        }
     }
 }
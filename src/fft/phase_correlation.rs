@@ -0,0 +1,816 @@
+//! Image registration by phase correlation on the stock forward-then-inverse path.
+//!
+//! An [`FftPhaseCorrelation`] entity loads the luminance of its two images into lanes 0 and 1 of
+//! buffer **A** at [`FftNode::PhaseCorrelationInput`](super::FftNode::PhaseCorrelationInput).
+//! After the forward transform, [`FftNode::CrossPowerSpectrum`](super::FftNode::CrossPowerSpectrum)
+//! replaces **C** with the normalized cross-power spectrum `F·conj(G) / |F·conj(G)|`, and after
+//! the inverse transform [`FftNode::CorrelationPeak`](super::FftNode::CorrelationPeak) reduces
+//! **B** to its highest sample on the GPU. The peak and its four neighbours are read back and
+//! refined to a sub-pixel translation on the CPU, then triggered as
+//! [`FftPhaseCorrelationComplete`].
+//!
+//! With [`FftFourierMellin`] the input stage first estimates rotation and scale. It transforms
+//! both images, resamples their high-passed magnitude spectra onto a log-polar grid through the
+//! same fftshift as the power-spectrum resolve, and correlates the two grids with the same
+//! kernels, where rotation and scale show up as a translation. It then loads the moving image
+//! with that rotation and scale undone, and the stock passes find the remaining translation.
+
+use bevy::{
+    asset::AssetEvent,
+    diagnostic::FrameCount,
+    ecs::query::QueryState,
+    log::warn,
+    platform::collections::{HashMap, HashSet},
+    prelude::*,
+    render::{
+        diagnostic::RecordDiagnostics,
+        extract_component::ExtractComponent,
+        gpu_readback::{Readback, ReadbackComplete},
+        render_asset::RenderAssets,
+        render_graph::{Node, NodeRunError, RenderGraphContext},
+        render_resource::{
+            binding_types::{storage_buffer, storage_buffer_sized, texture_2d},
+            *,
+        },
+        renderer::{RenderContext, RenderDevice, RenderQueue},
+        storage::{GpuShaderStorageBuffer, ShaderStorageBuffer},
+        sync_world::MainEntity,
+        texture::GpuImage,
+    },
+    utils::once,
+};
+
+use super::{
+    FftDimension, FftInvalidSize, FftRunRequest, FftSettings, FftSource, FftTrigger, FftWindow,
+    diagnostics::FftTimedPass,
+    resources::{FftBindGroups, FftPipelines, FftWorkspaceFormat},
+    run_forward_fft, run_inverse_fft, shaders,
+    trigger::{FftSkippedRuns, FftTriggerState, FftTriggered, modified_images},
+};
+
+/// Measures how far [`Self::moving`] is shifted relative to [`Self::reference`], and triggers
+/// [`FftPhaseCorrelationComplete`] on this entity whenever the two have been correlated.
+///
+/// Add it next to an [`FftSource`] from [`Self::workspace`], or any two-channel 2D
+/// [`FftSchedule::ForwardThenInverse`](super::FftSchedule::ForwardThenInverse) source of the
+/// image size. Both images are read on the GPU, so render targets work as well as loaded
+/// textures; texels past either image's edge count as black. An [`FftTrigger`] picks the frames
+/// that correlate, with [`FftTrigger::OnChange`] also following edits to this component and its
+/// images. At most one result is read back at a time, so entities correlating every frame
+/// report every frame or two.
+#[derive(Component, ExtractComponent, Clone, Debug, Reflect)]
+pub struct FftPhaseCorrelation {
+    pub reference: Handle<Image>,
+    pub moving: Handle<Image>,
+}
+
+impl FftPhaseCorrelation {
+    pub fn new(reference: Handle<Image>, moving: Handle<Image>) -> Self {
+        Self { reference, moving }
+    }
+
+    /// Workspace for correlating `size` images: two channels, with a Hann window on both axes
+    /// so the image borders do not correlate as a hard edge.
+    pub fn workspace(size: UVec2) -> FftSource {
+        Self::try_workspace(size).expect("unsupported FFT size")
+    }
+
+    /// Like [`Self::workspace`], but returns an error when either edge is unsupported.
+    pub fn try_workspace(size: UVec2) -> Result<FftSource, FftInvalidSize> {
+        Ok(FftSource {
+            channels: 2,
+            window: FftWindow::Hann,
+            window_per_axis: true,
+            ..FftSource::try_forward_then_inverse(size)?
+        })
+    }
+}
+
+/// Triggered on an [`FftPhaseCorrelation`] entity once a correlation has been read back.
+#[derive(EntityEvent, Clone, Copy, Debug)]
+pub struct FftPhaseCorrelationComplete {
+    pub entity: Entity,
+    /// [`FrameCount`] of the frame whose render graph correlated the images.
+    pub frame: u32,
+    /// Shift of `moving` relative to `reference` in pixels, so that `moving(p)` matches
+    /// `reference(p - translation)`. Each axis lies within half the workspace extent.
+    pub translation: Vec2,
+    /// Height of the correlation peak: `1` for a pure translation, falling towards `0` as the
+    /// images stop overlapping or stop resembling each other.
+    pub peak: f32,
+}
+
+/// Estimates rotation and uniform scale as well as translation for the [`FftPhaseCorrelation`] on
+/// the same entity, which then triggers [`FftFourierMellinComplete`] instead of
+/// [`FftPhaseCorrelationComplete`].
+///
+/// Magnitude spectra are symmetric under a half-turn, so rotations are reported within
+/// `(-π/2, π/2]`. Each correlation costs three forward and two inverse transforms of the
+/// workspace instead of one of each.
+#[derive(Component, ExtractComponent, Clone, Copy, Default, Debug, Reflect)]
+pub struct FftFourierMellin;
+
+/// Triggered on an [`FftFourierMellin`] entity once a correlation has been read back.
+#[derive(EntityEvent, Clone, Copy, Debug)]
+pub struct FftFourierMellinComplete {
+    pub entity: Entity,
+    /// [`FrameCount`] of the frame whose render graph correlated the images.
+    pub frame: u32,
+    /// Rotation of `moving` relative to `reference` in radians, from `+x` towards `+y` in texel
+    /// coordinates.
+    pub rotation: f32,
+    /// Uniform scale of `moving` relative to `reference`.
+    pub scale: f32,
+    /// Shift left once rotation and scale are applied about the image centre `c`:
+    /// `moving(c + scale * R(rotation) * (p - c) + translation)` matches `reference(p)`.
+    pub translation: Vec2,
+    /// Height of the translation peak, as in [`FftPhaseCorrelationComplete::peak`].
+    pub peak: f32,
+}
+
+/// Highest sample of a correlation surface and its four neighbours, as `phase_correlation.wgsl`
+/// writes it. `valid` is `0` until this frame's reduction runs; see
+/// [`clear_fft_correlation_results`].
+#[derive(ShaderType, Clone, Copy, Default, Debug)]
+struct FftCorrelationPeak {
+    texel: UVec2,
+    value: f32,
+    valid: u32,
+    /// Left, right, above and below, wrapping around the workspace.
+    neighbors: Vec4,
+}
+
+impl FftCorrelationPeak {
+    /// Peak position refined by a parabola through each axis's neighbours, wrapped to
+    /// `(-size / 2, size / 2]`.
+    fn translation(&self, size: UVec2) -> Vec2 {
+        let refine = |below: f32, above: f32| {
+            let curvature = below - 2.0 * self.value + above;
+            if curvature < 0.0 {
+                (0.5 * (below - above) / curvature).clamp(-0.5, 0.5)
+            } else {
+                0.0
+            }
+        };
+        let wrap = |t: f32, n: u32| if t > n as f32 / 2.0 { t - n as f32 } else { t };
+        let n = self.neighbors;
+        let position = self.texel.as_vec2() + Vec2::new(refine(n.x, n.y), refine(n.z, n.w));
+        Vec2::new(wrap(position.x, size.x), wrap(position.y, size.y))
+    }
+}
+
+/// Contents of an [`FftCorrelationPeakBuffer`].
+#[derive(ShaderType, Clone, Copy, Default, Debug)]
+struct FftCorrelationResult {
+    /// Peak of the correlation the stock passes run.
+    translation: FftCorrelationPeak,
+    /// [`FftFourierMellin`] only: peak of the log-polar correlation, and the rotation and scale
+    /// `fft_fourier_mellin_load` derived from it.
+    log_polar: FftCorrelationPeak,
+    rotation: f32,
+    scale: f32,
+}
+
+impl FftCorrelationResult {
+    /// Translation of [`FftFourierMellinComplete`]. The stock passes measure it between the
+    /// reference and the moving image with rotation and scale undone, so it is mapped back
+    /// through them.
+    fn similarity_translation(&self, size: UVec2) -> Vec2 {
+        Vec2::from_angle(self.rotation).rotate(self.translation.translation(size)) * self.scale
+    }
+}
+
+/// Result buffer of an [`FftPhaseCorrelation`], written by the GPU reduction and read back.
+#[derive(Component, ExtractComponent, Clone)]
+pub(super) struct FftCorrelationPeakBuffer(Handle<ShaderStorageBuffer>);
+
+/// One in-flight read of an [`FftCorrelationPeakBuffer`], on the entity holding its
+/// [`Readback`].
+#[derive(Component)]
+pub(super) struct FftCorrelationReadback {
+    source: Entity,
+    frame: u32,
+    size: UVec2,
+    /// Peak height of a perfect match under the source's normalization.
+    gain: f32,
+    fourier_mellin: bool,
+}
+
+/// Whether the correlation passes can run on a workspace with `settings`.
+fn supports(settings: &FftSettings) -> bool {
+    settings.channels == 2
+        && settings.dimension == FftDimension::TwoD.to_bits()
+        && !settings.is_layered()
+}
+
+/// Inverse-transformed height of a unit cross-power spectrum, `N` times the inverse scaling.
+fn peak_gain(source: &FftSource) -> f32 {
+    let size = source.size + source.padding;
+    [size.x, size.y]
+        .into_iter()
+        .map(|n| source.normalization.scales(n).1 * n as f32)
+        .product()
+}
+
+pub(super) fn insert_fft_correlation_peak_buffer(
+    add: On<Add, FftPhaseCorrelation>,
+    mut commands: Commands,
+    mut buffers: ResMut<Assets<ShaderStorageBuffer>>,
+) {
+    let mut buffer = ShaderStorageBuffer::from(FftCorrelationResult::default());
+    buffer.buffer_description.usage |= BufferUsages::COPY_SRC;
+    commands
+        .entity(add.entity)
+        .insert(FftCorrelationPeakBuffer(buffers.add(buffer)));
+}
+
+/// Schedules another run of [`FftTrigger::OnChange`] entities when their images change.
+pub(super) fn request_fft_phase_correlation_runs(
+    mut commands: Commands,
+    mut image_events: MessageReader<AssetEvent<Image>>,
+    query: Query<(Entity, Ref<FftPhaseCorrelation>, &FftTrigger)>,
+) {
+    let modified = modified_images(&mut image_events);
+    for (entity, correlation, trigger) in &query {
+        if *trigger == FftTrigger::OnChange
+            && (correlation.is_changed()
+                || modified.contains(&correlation.reference.id())
+                || modified.contains(&correlation.moving.id()))
+        {
+            commands.entity(entity).insert(FftRunRequest);
+        }
+    }
+}
+
+type FftCorrelationSourceQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static FftSource,
+        &'static FftCorrelationPeakBuffer,
+        Option<&'static FftTriggerState>,
+        Has<FftFourierMellin>,
+    ),
+    With<FftPhaseCorrelation>,
+>;
+
+/// Reads back the peak of every entity that correlates this frame and has no read in flight.
+pub(super) fn request_fft_correlation_readbacks(
+    mut commands: Commands,
+    frame: Res<FrameCount>,
+    sources: FftCorrelationSourceQuery,
+    pending: Query<&FftCorrelationReadback>,
+) {
+    let in_flight: HashSet<Entity> = pending.iter().map(|readback| readback.source).collect();
+    for (entity, source, buffer, trigger, fourier_mellin) in &sources {
+        if in_flight.contains(&entity) || trigger.is_some_and(FftTriggerState::is_idle) {
+            continue;
+        }
+        if !supports(&FftSettings::from_fft_source(source)) {
+            once!(warn!(
+                "FftPhaseCorrelation needs a two-channel 2D FftSource without layers; see FftPhaseCorrelation::workspace"
+            ));
+            continue;
+        }
+        commands.spawn((
+            Readback::buffer(buffer.0.clone()),
+            FftCorrelationReadback {
+                source: entity,
+                frame: frame.0,
+                size: source.size + source.padding,
+                gain: peak_gain(source),
+                fourier_mellin,
+            },
+        ));
+    }
+}
+
+/// Decodes a finished peak read and triggers [`FftPhaseCorrelationComplete`] or
+/// [`FftFourierMellinComplete`].
+pub(super) fn on_fft_correlation_readback(
+    complete: On<ReadbackComplete>,
+    mut commands: Commands,
+    readbacks: Query<&FftCorrelationReadback>,
+) {
+    let Ok(readback) = readbacks.get(complete.entity) else {
+        return;
+    };
+    // `Readback` copies every frame until the despawn lands; keep the first result.
+    commands.entity(complete.entity).despawn();
+    let result: FftCorrelationResult = complete.to_shader_type();
+    let peak = result.translation;
+    if peak.valid == 0 || (readback.fourier_mellin && result.log_polar.valid == 0) {
+        return;
+    }
+    if readback.fourier_mellin {
+        commands.trigger(FftFourierMellinComplete {
+            entity: readback.source,
+            frame: readback.frame,
+            rotation: result.rotation,
+            scale: result.scale,
+            translation: result.similarity_translation(readback.size),
+            peak: peak.value / readback.gain,
+        });
+    } else {
+        commands.trigger(FftPhaseCorrelationComplete {
+            entity: readback.source,
+            frame: readback.frame,
+            translation: peak.translation(readback.size),
+            peak: peak.value / readback.gain,
+        });
+    }
+}
+
+/// Entry points of `phase_correlation.wgsl`, in [`FftCorrelationKernels`] order.
+const ENTRY_POINTS: [&str; 7] = [
+    "fft_phase_correlation_load",
+    "fft_cross_power_spectrum",
+    "fft_correlation_row_peaks",
+    "fft_correlation_peak",
+    "fft_log_polar_magnitude",
+    "fft_log_polar_peak",
+    "fft_fourier_mellin_load",
+];
+
+/// Compiled correlation kernels for one workspace format.
+struct FftCorrelationKernels<'a> {
+    load: &'a ComputePipeline,
+    cross_power: &'a ComputePipeline,
+    row_peaks: &'a ComputePipeline,
+    peak: &'a ComputePipeline,
+    log_polar: &'a ComputePipeline,
+    log_polar_peak: &'a ComputePipeline,
+    fourier_mellin_load: &'a ComputePipeline,
+}
+
+/// Correlation kernels per [`FftWorkspaceFormat`], one per [`ENTRY_POINTS`] entry.
+#[derive(Resource)]
+pub(super) struct FftPhaseCorrelationPipelines {
+    layout: BindGroupLayoutDescriptor,
+    pipelines: HashMap<FftWorkspaceFormat, [CachedComputePipelineId; 7]>,
+}
+
+impl Default for FftPhaseCorrelationPipelines {
+    fn default() -> Self {
+        let entries = BindGroupLayoutEntries::sequential(
+            ShaderStages::COMPUTE,
+            (
+                texture_2d(TextureSampleType::Float { filterable: false }),
+                texture_2d(TextureSampleType::Float { filterable: false }),
+                storage_buffer_sized(false, None),
+                storage_buffer::<FftCorrelationResult>(false),
+            ),
+        );
+        Self {
+            layout: BindGroupLayoutDescriptor::new(
+                "fft_phase_correlation_bind_group_layout",
+                &entries,
+            ),
+            pipelines: HashMap::default(),
+        }
+    }
+}
+
+impl FftPhaseCorrelationPipelines {
+    /// Kernels for the workspace format of `settings`, once all of them have compiled.
+    fn pipelines<'a>(
+        &self,
+        fft_pipelines: &FftPipelines,
+        pipeline_cache: &'a PipelineCache,
+        settings: &FftSettings,
+    ) -> Option<FftCorrelationKernels<'a>> {
+        let ids = self.pipelines.get(&fft_pipelines.format(settings))?;
+        let [
+            load,
+            cross_power,
+            row_peaks,
+            peak,
+            log_polar,
+            log_polar_peak,
+            fourier_mellin_load,
+        ] = ids.map(|id| pipeline_cache.get_compute_pipeline(id));
+        Some(FftCorrelationKernels {
+            load: load?,
+            cross_power: cross_power?,
+            row_peaks: row_peaks?,
+            peak: peak?,
+            log_polar: log_polar?,
+            log_polar_peak: log_polar_peak?,
+            fourier_mellin_load: fourier_mellin_load?,
+        })
+    }
+}
+
+pub(super) fn prepare_fft_phase_correlation_pipelines(
+    mut correlation: ResMut<FftPhaseCorrelationPipelines>,
+    fft_pipelines: Res<FftPipelines>,
+    pipeline_cache: Res<PipelineCache>,
+    query: Query<&FftSettings, With<FftPhaseCorrelation>>,
+) {
+    let correlation = &mut *correlation;
+    for settings in query.iter().filter(|settings| supports(settings)) {
+        let format = fft_pipelines.format(settings);
+        correlation.pipelines.entry(format).or_insert_with(|| {
+            ENTRY_POINTS.map(|entry_point| {
+                pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
+                    label: Some(format!("{entry_point}_pipeline").into()),
+                    layout: vec![format.layout(), correlation.layout.clone()],
+                    push_constant_ranges: vec![],
+                    shader: shaders::PHASE_CORRELATION.clone(),
+                    shader_defs: format.shader_defs(),
+                    entry_point: Some(entry_point.into()),
+                    zero_initialize_workgroup_memory: false,
+                })
+            })
+        });
+    }
+}
+
+/// Images, per-row maxima and result buffer of an [`FftPhaseCorrelation`] entity.
+#[derive(Component)]
+pub(super) struct FftPhaseCorrelationBindGroup {
+    group: BindGroup,
+    rows: Buffer,
+}
+
+/// Bytes per entry of `row_peaks` in `phase_correlation.wgsl`: the value, then its column.
+const ROW_PEAK_BYTES: u64 = 8;
+
+/// Zeroes the result buffer of every entity that correlates this frame, before any pass runs. A
+/// run skipped along the way then reads back as invalid rather than as the previous peak.
+pub(super) fn clear_fft_correlation_results(
+    queue: Res<RenderQueue>,
+    buffers: Res<RenderAssets<GpuShaderStorageBuffer>>,
+    query: Query<(&FftCorrelationPeakBuffer, Option<&FftTriggered>)>,
+) {
+    for (peak, triggered) in &query {
+        if FftTriggered::is_idle(triggered) {
+            continue;
+        }
+        if let Some(buffer) = buffers.get(&peak.0) {
+            queue.write_buffer(&buffer.buffer, 0, &vec![0; buffer.buffer.size() as usize]);
+        }
+    }
+}
+
+/// Rebuilds the bind group every frame, since either image may be reallocated, and keeps the
+/// row buffer while the workspace height holds.
+pub(super) fn prepare_fft_phase_correlation_bind_groups(
+    mut commands: Commands,
+    device: Res<RenderDevice>,
+    pipeline_cache: Res<PipelineCache>,
+    pipelines: Res<FftPhaseCorrelationPipelines>,
+    images: Res<RenderAssets<GpuImage>>,
+    buffers: Res<RenderAssets<GpuShaderStorageBuffer>>,
+    query: Query<(
+        Entity,
+        &FftPhaseCorrelation,
+        &FftCorrelationPeakBuffer,
+        &FftSettings,
+        Option<&FftPhaseCorrelationBindGroup>,
+    )>,
+) {
+    for (entity, correlation, peak, settings, existing) in &query {
+        let (Some(reference), Some(moving), Some(peak)) = (
+            images.get(&correlation.reference),
+            images.get(&correlation.moving),
+            buffers.get(&peak.0),
+        ) else {
+            if existing.is_some() {
+                commands
+                    .entity(entity)
+                    .remove::<FftPhaseCorrelationBindGroup>();
+            }
+            continue;
+        };
+        let rows_size = u64::from(settings.size.y) * ROW_PEAK_BYTES;
+        let rows = match existing {
+            Some(existing) if existing.rows.size() == rows_size => existing.rows.clone(),
+            _ => device.create_buffer(&BufferDescriptor {
+                label: Some("fft_correlation_row_peaks"),
+                size: rows_size,
+                usage: BufferUsages::STORAGE,
+                mapped_at_creation: false,
+            }),
+        };
+        let group = device.create_bind_group(
+            "fft_phase_correlation_bind_group",
+            &pipeline_cache.get_bind_group_layout(&pipelines.layout),
+            &BindGroupEntries::sequential((
+                &reference.texture_view,
+                &moving.texture_view,
+                rows.as_entire_binding(),
+                peak.buffer.as_entire_binding(),
+            )),
+        );
+        commands
+            .entity(entity)
+            .insert(FftPhaseCorrelationBindGroup { group, rows });
+    }
+}
+
+/// Compute pass of one entity, with the bind groups every correlation kernel shares.
+struct FftCorrelationPass<'p, 'a> {
+    pass: &'p mut ComputePass<'a>,
+    common: &'p FftBindGroups,
+    group: &'p BindGroup,
+    settings: &'p FftSettings,
+}
+
+impl FftCorrelationPass<'_, '_> {
+    fn dispatch(&mut self, pipeline: &ComputePipeline, x: u32, y: u32) {
+        self.pass.set_pipeline(pipeline);
+        self.common.set(self.pass, 0);
+        self.pass.set_bind_group(1, self.group, &[]);
+        self.pass.dispatch_workgroups(x, y, 1);
+    }
+
+    /// One 16×16 workgroup per tile of the workspace.
+    fn grid(&mut self, pipeline: &ComputePipeline) {
+        let size = self.settings.size;
+        self.dispatch(pipeline, size.x.div_ceil(16), size.y.div_ceil(16));
+    }
+
+    /// Row maxima of **B**, then `peak` over the rows.
+    fn reduce(&mut self, row_peaks: &ComputePipeline, peak: &ComputePipeline) {
+        self.dispatch(row_peaks, 1, self.settings.size.y);
+        self.dispatch(peak, 1, 1);
+    }
+
+    /// Correlates the log-polar magnitude spectra of both images, then loads **A** with the
+    /// moving image's rotation and scale undone. Returns `false` while the FFT kernels compile.
+    fn record_log_polar_correlation(
+        &mut self,
+        kernels: &FftCorrelationKernels,
+        fft_pipelines: &FftPipelines,
+        pipeline_cache: &PipelineCache,
+    ) -> bool {
+        let (common, settings) = (self.common, self.settings);
+        self.grid(kernels.load);
+        if !run_forward_fft(fft_pipelines, pipeline_cache, self.pass, common, settings) {
+            return false;
+        }
+        self.grid(kernels.log_polar);
+        if !run_forward_fft(fft_pipelines, pipeline_cache, self.pass, common, settings) {
+            return false;
+        }
+        self.grid(kernels.cross_power);
+        if !run_inverse_fft(fft_pipelines, pipeline_cache, self.pass, common, settings) {
+            return false;
+        }
+        self.reduce(kernels.row_peaks, kernels.log_polar_peak);
+        self.grid(kernels.fourier_mellin_load);
+        true
+    }
+}
+
+/// Which part of the correlation an [`FftPhaseCorrelationNode`] records.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum FftPhaseCorrelationStage {
+    /// Images into **A**, before the forward FFT. [`FftFourierMellin`] entities run their
+    /// log-polar correlation here first.
+    Input,
+    /// Cross-power spectrum in **C**, between the transforms.
+    CrossPower,
+    /// Peak of **B**, after the inverse FFT.
+    Peak,
+}
+
+type FftPhaseCorrelationNodeQuery = (
+    Entity,
+    &'static MainEntity,
+    &'static FftBindGroups,
+    &'static FftSettings,
+    Option<&'static FftPhaseCorrelationBindGroup>,
+    Option<&'static FftTriggered>,
+    Has<FftFourierMellin>,
+);
+
+/// Runs one [`FftPhaseCorrelationStage`] for every [`FftPhaseCorrelation`] entity.
+pub(super) struct FftPhaseCorrelationNode {
+    stage: FftPhaseCorrelationStage,
+    query: QueryState<FftPhaseCorrelationNodeQuery, With<FftPhaseCorrelation>>,
+}
+
+impl FftPhaseCorrelationNode {
+    pub(super) fn new(world: &mut World, stage: FftPhaseCorrelationStage) -> Self {
+        Self {
+            stage,
+            query: world.query_filtered(),
+        }
+    }
+}
+
+impl Node for FftPhaseCorrelationNode {
+    fn update(&mut self, world: &mut World) {
+        self.query.update_archetypes(world);
+    }
+
+    fn run(
+        &self,
+        _graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        let fft_pipelines = world.resource::<FftPipelines>();
+        let pipelines = world.resource::<FftPhaseCorrelationPipelines>();
+        let pipeline_cache = world.resource::<PipelineCache>();
+        let skipped = world.resource::<FftSkippedRuns>();
+        let diagnostics = render_context.diagnostic_recorder();
+        let timed = match self.stage {
+            FftPhaseCorrelationStage::Input => FftTimedPass::PhaseCorrelationInput,
+            FftPhaseCorrelationStage::CrossPower => FftTimedPass::CrossPowerSpectrum,
+            FftPhaseCorrelationStage::Peak => FftTimedPass::CorrelationPeak,
+        };
+
+        let command_encoder = render_context.command_encoder();
+
+        for (
+            entity,
+            main_entity,
+            bind_groups,
+            settings,
+            correlation_group,
+            triggered,
+            fourier_mellin,
+        ) in self.query.iter_manual(world)
+        {
+            if FftTriggered::is_idle(triggered) || !supports(settings) {
+                continue;
+            }
+            // A skipped transform leaves **B** stale; its peak must not become valid.
+            if self.stage == FftPhaseCorrelationStage::Peak && skipped.contains(entity) {
+                continue;
+            }
+            let kernels = pipelines.pipelines(fft_pipelines, pipeline_cache, settings);
+            let (Some(kernels), Some(correlation_group)) = (kernels, correlation_group) else {
+                // Images or kernels are still loading; the trigger runs the entity again.
+                skipped.push(entity);
+                continue;
+            };
+            let mut compute_pass = command_encoder.begin_compute_pass(&ComputePassDescriptor {
+                label: Some("fft_phase_correlation_pass"),
+                timestamp_writes: None,
+            });
+            let span = diagnostics.pass_span(&mut compute_pass, timed.span(main_entity.id()));
+            let pass = &mut FftCorrelationPass {
+                pass: &mut compute_pass,
+                common: bind_groups,
+                group: &correlation_group.group,
+                settings,
+            };
+            let ready = match self.stage {
+                FftPhaseCorrelationStage::Input if fourier_mellin => {
+                    pass.record_log_polar_correlation(&kernels, fft_pipelines, pipeline_cache)
+                }
+                FftPhaseCorrelationStage::Input => {
+                    pass.grid(kernels.load);
+                    true
+                }
+                FftPhaseCorrelationStage::CrossPower => {
+                    pass.grid(kernels.cross_power);
+                    true
+                }
+                FftPhaseCorrelationStage::Peak => {
+                    pass.reduce(kernels.row_peaks, kernels.peak);
+                    true
+                }
+            };
+            span.end(&mut compute_pass);
+            if !ready {
+                skipped.push(entity);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        math::{UVec2, Vec2, Vec4},
+        render::render_resource::ShaderType,
+    };
+
+    use super::{FftCorrelationPeak, FftCorrelationResult, FftPhaseCorrelation, peak_gain};
+    use crate::{
+        complex::c32,
+        fft::{FftSettings, cpu},
+    };
+
+    /// Mirrors `fft_phase_correlation_load`, `fft_cross_power_spectrum` and the peak reduction
+    /// with the CPU reference transform.
+    fn correlate(size: UVec2, reference: &[f32], moving: &[f32]) -> FftCorrelationPeak {
+        let source = FftPhaseCorrelation::workspace(size);
+        let settings = FftSettings::from_fft_source(&source);
+        let mut data: Vec<c32> = moving
+            .iter()
+            .zip(reference)
+            .flat_map(|(&m, &r)| [c32::new(m, 0.0), c32::new(r, 0.0)])
+            .collect();
+        cpu::forward(&settings, &mut data);
+        for texel in data.chunks_exact_mut(2) {
+            let (f, g) = (texel[0], texel[1]);
+            let cross = c32::new(f.re * g.re + f.im * g.im, f.im * g.re - f.re * g.im);
+            let magnitude = (cross.re * cross.re + cross.im * cross.im).sqrt();
+            texel[0] = if magnitude > 1e-12 {
+                c32::new(cross.re / magnitude, cross.im / magnitude)
+            } else {
+                c32::new(0.0, 0.0)
+            };
+            texel[1] = c32::new(0.0, 0.0);
+        }
+        cpu::inverse(&settings, &mut data);
+
+        let (w, h) = (size.x as usize, size.y as usize);
+        let at = |x: usize, y: usize| data[(y * w + x) * 2].re;
+        let (x, y) = (0..h)
+            .flat_map(|y| (0..w).map(move |x| (x, y)))
+            .max_by(|a, b| at(a.0, a.1).total_cmp(&at(b.0, b.1)))
+            .unwrap();
+        FftCorrelationPeak {
+            texel: UVec2::new(x as u32, y as u32),
+            value: at(x, y) / peak_gain(&source),
+            valid: 1,
+            neighbors: Vec4::new(
+                at((x + w - 1) % w, y),
+                at((x + 1) % w, y),
+                at(x, (y + h - 1) % h),
+                at(x, (y + 1) % h),
+            ),
+        }
+    }
+
+    /// Broadband texture: white noise hashed from the pixel position.
+    fn scene(p: Vec2) -> f32 {
+        let hash = (p.x as i32 as u32).wrapping_mul(0x9e37_79b9)
+            ^ (p.y as i32 as u32).wrapping_mul(0x85eb_ca6b);
+        let hash = (hash ^ (hash >> 15)).wrapping_mul(0x2c1b_3c6d);
+        (hash >> 8) as f32 / (1 << 24) as f32
+    }
+
+    #[test]
+    fn shifted_image_peaks_at_the_shift() {
+        let size = UVec2::new(64, 48);
+        let shift = Vec2::new(5.0, -3.0);
+        let pixels = |offset: Vec2| -> Vec<f32> {
+            (0..size.y)
+                .flat_map(|y| (0..size.x).map(move |x| Vec2::new(x as f32, y as f32)))
+                .map(|p| scene(p - offset))
+                .collect()
+        };
+        let peak = correlate(size, &pixels(Vec2::ZERO), &pixels(shift));
+        let translation = peak.translation(size);
+        assert!(
+            translation.distance(shift) < 0.5,
+            "expected {shift}, got {translation}"
+        );
+        assert!(peak.value > 0.3 && peak.value <= 1.0 + 1e-4);
+    }
+
+    #[test]
+    fn translation_refines_between_samples_and_wraps_negative() {
+        let size = UVec2::new(32, 16);
+        let peak = FftCorrelationPeak {
+            texel: UVec2::new(30, 2),
+            value: 1.0 - 0.25 * 0.25,
+            valid: 1,
+            // A parabola peaking a quarter sample right of the texel, flat in y.
+            neighbors: Vec4::new(1.0 - 1.25 * 1.25, 1.0 - 0.75 * 0.75, 1.0 - 1.0, 1.0 - 1.0),
+        };
+        let translation = peak.translation(size);
+        assert!((translation.x - (30.25 - 32.0)).abs() < 1e-5);
+        assert!((translation.y - 2.0).abs() < 1e-5);
+
+        let flat = FftCorrelationPeak {
+            neighbors: Vec4::ONE,
+            ..peak
+        };
+        assert_eq!(flat.translation(size), Vec2::new(-2.0, 2.0));
+    }
+
+    #[test]
+    fn similarity_translation_undoes_rotation_and_scale() {
+        let result = FftCorrelationResult {
+            translation: FftCorrelationPeak {
+                texel: UVec2::new(3, 0),
+                value: 1.0,
+                valid: 1,
+                neighbors: Vec4::ONE,
+            },
+            rotation: std::f32::consts::FRAC_PI_2,
+            scale: 2.0,
+            ..Default::default()
+        };
+        let translation = result.similarity_translation(UVec2::splat(32));
+        assert!((translation - Vec2::new(0.0, 6.0)).length() < 1e-5);
+    }
+
+    #[test]
+    fn result_layout_matches_wgsl() {
+        // Two 32-byte `CorrelationPeak`s and two scalars, padded to the struct's 16-byte alignment.
+        assert_eq!(FftCorrelationResult::min_size().get(), 80);
+    }
+}
@@ -0,0 +1,283 @@
+#import bevy_fft::{
+    complex::c32_2,
+    bindings::settings,
+    buffer::{
+        read_buffer_b,
+        read_buffer_c,
+        write_buffer_a,
+        write_buffer_c,
+    },
+    plot::fft_shift,
+}
+
+// Highest sample of one row of the correlation surface.
+struct RowPeak {
+    value: f32,
+    x: u32,
+}
+
+// Mirrors `FftCorrelationPeak` in `phase_correlation.rs`.
+struct CorrelationPeak {
+    texel: vec2<u32>,
+    value: f32,
+    valid: u32,
+    // Left, right, above and below, wrapping around the workspace.
+    neighbors: vec4<f32>,
+}
+
+// Mirrors `FftCorrelationResult` in `phase_correlation.rs`.
+struct CorrelationResult {
+    translation: CorrelationPeak,
+    log_polar: CorrelationPeak,
+    rotation: f32,
+    scale: f32,
+}
+
+@group(1) @binding(0) var reference_texture: texture_2d<f32>;
+@group(1) @binding(1) var moving_texture: texture_2d<f32>;
+@group(1) @binding(2) var<storage, read_write> row_peaks: array<RowPeak>;
+@group(1) @binding(3) var<storage, read_write> result: CorrelationResult;
+
+const LUMINANCE = vec3<f32>(0.2126, 0.7152, 0.0722);
+const PI: f32 = 3.141592653589793;
+const PEAK_WORKGROUP: u32 = 256u;
+
+var<workgroup> best_value: array<f32, PEAK_WORKGROUP>;
+var<workgroup> best_index: array<u32, PEAK_WORKGROUP>;
+
+fn luminance(image: texture_2d<f32>, p: vec2<u32>) -> f32 {
+    if (any(p >= textureDimensions(image))) {
+        return 0.0;
+    }
+    return dot(textureLoad(image, p, 0).rgb, LUMINANCE);
+}
+
+// Bilinear luminance at texel coordinates `p`, zero outside the image.
+fn luminance_bilinear(image: texture_2d<f32>, p: vec2<f32>) -> f32 {
+    let base = floor(p);
+    let f = p - base;
+    var corners = vec4<f32>(0.0);
+    for (var k = 0u; k < 4u; k++) {
+        let texel = vec2<i32>(base) + vec2<i32>(i32(k & 1u), i32(k >> 1u));
+        if (all(texel >= vec2<i32>(0))) {
+            corners[k] = luminance(image, vec2<u32>(texel));
+        }
+    }
+    return mix(mix(corners.x, corners.y, f.x), mix(corners.z, corners.w, f.x), f.y);
+}
+
+// Moving image into lane 0 of **A**, reference into lane 1, zero in the padding.
+@compute
+@workgroup_size(16, 16, 1)
+fn fft_phase_correlation_load(@builtin(global_invocation_id) gid: vec3<u32>) {
+    if (any(gid.xy >= settings.size)) {
+        return;
+    }
+    var samples = vec2<f32>(0.0);
+    if (all(gid.xy < settings.size - settings.padding)) {
+        samples = vec2<f32>(luminance(moving_texture, gid.xy), luminance(reference_texture, gid.xy));
+    }
+    write_buffer_a(vec3<u32>(gid.xy, 0u), c32_2(samples, vec2<f32>(0.0)));
+}
+
+// `F·conj(G) / |F·conj(G)|` into lane 0 of **C**, with `F` the moving and `G` the reference
+// spectrum. Bins where either spectrum vanishes carry no phase and are zeroed.
+@compute
+@workgroup_size(16, 16, 1)
+fn fft_cross_power_spectrum(@builtin(global_invocation_id) gid: vec3<u32>) {
+    if (any(gid.xy >= settings.size)) {
+        return;
+    }
+    let pos = vec3<u32>(gid.xy, 0u);
+    let s = read_buffer_c(pos);
+    let cross = vec2<f32>(
+        s.re.x * s.re.y + s.im.x * s.im.y,
+        s.im.x * s.re.y - s.re.x * s.im.y,
+    );
+    let magnitude = length(cross);
+    var normalized = vec2<f32>(0.0);
+    if (magnitude > 1e-12) {
+        normalized = cross / magnitude;
+    }
+    write_buffer_c(pos, c32_2(vec2<f32>(normalized.x, 0.0), vec2<f32>(normalized.y, 0.0)));
+}
+
+fn correlation(p: vec2<u32>) -> f32 {
+    return read_buffer_b(vec3<u32>(p, 0u)).re.x;
+}
+
+// Tree reduction of the workgroup's candidates, leaving the highest in slot 0.
+fn reduce_workgroup(local: u32) {
+    for (var stride = PEAK_WORKGROUP / 2u; stride > 0u; stride >>= 1u) {
+        workgroupBarrier();
+        if (local < stride && best_value[local + stride] > best_value[local]) {
+            best_value[local] = best_value[local + stride];
+            best_index[local] = best_index[local + stride];
+        }
+    }
+    workgroupBarrier();
+}
+
+// One workgroup per row of **B**.
+@compute
+@workgroup_size(256, 1, 1)
+fn fft_correlation_row_peaks(
+    @builtin(local_invocation_index) local: u32,
+    @builtin(workgroup_id) group: vec3<u32>,
+) {
+    let y = group.y;
+    var value = -3.4e38;
+    var index = 0u;
+    for (var x = local; x < settings.size.x; x += PEAK_WORKGROUP) {
+        let sample = correlation(vec2<u32>(x, y));
+        if (sample > value) {
+            value = sample;
+            index = x;
+        }
+    }
+    best_value[local] = value;
+    best_index[local] = index;
+    reduce_workgroup(local);
+    if (local == 0u) {
+        row_peaks[y] = RowPeak(best_value[0], best_index[0]);
+    }
+}
+
+// Highest row maximum and its neighbours. Every invocation of the single workgroup must call it.
+fn find_peak(local: u32) -> CorrelationPeak {
+    var value = -3.4e38;
+    var index = 0u;
+    for (var y = local; y < settings.size.y; y += PEAK_WORKGROUP) {
+        let row = row_peaks[y];
+        if (row.value > value) {
+            value = row.value;
+            index = y;
+        }
+    }
+    best_value[local] = value;
+    best_index[local] = index;
+    reduce_workgroup(local);
+    let size = settings.size;
+    let y = best_index[0];
+    let x = row_peaks[y].x;
+    return CorrelationPeak(
+        vec2<u32>(x, y),
+        best_value[0],
+        1u,
+        vec4<f32>(
+            correlation(vec2<u32>((x + size.x - 1u) % size.x, y)),
+            correlation(vec2<u32>((x + 1u) % size.x, y)),
+            correlation(vec2<u32>(x, (y + size.y - 1u) % size.y)),
+            correlation(vec2<u32>(x, (y + 1u) % size.y)),
+        ),
+    );
+}
+
+@compute
+@workgroup_size(256, 1, 1)
+fn fft_correlation_peak(@builtin(local_invocation_index) local: u32) {
+    let peak = find_peak(local);
+    if (local == 0u) {
+        result.translation = peak;
+    }
+}
+
+@compute
+@workgroup_size(256, 1, 1)
+fn fft_log_polar_peak(@builtin(local_invocation_index) local: u32) {
+    let peak = find_peak(local);
+    if (local == 0u) {
+        result.log_polar = peak;
+    }
+}
+
+// Log-radius and angle per sample of the log-polar grid. Columns run from one bin of the
+// shorter axis to its Nyquist frequency, rows over the half-turn `[0, π)`.
+fn log_polar_steps() -> vec2<f32> {
+    let size = settings.size;
+    return vec2<f32>(log(f32(min(size.x, size.y)) * 0.5) / f32(size.x), PI / f32(size.y));
+}
+
+// Magnitudes of both lanes of **C** at `frequency` in cycles per texel, interpolated over the
+// centred spectrum.
+fn magnitude_bilinear(frequency: vec2<f32>) -> vec2<f32> {
+    let size = settings.size;
+    let centred = frequency * vec2<f32>(size) + vec2<f32>(size / 2u);
+    let base = floor(centred);
+    let f = centred - base;
+    var corners: array<vec2<f32>, 4>;
+    for (var k = 0u; k < 4u; k++) {
+        let offset = vec2<i32>(base) + vec2<i32>(i32(k & 1u), i32(k >> 1u));
+        let display = vec2<u32>((offset + vec2<i32>(size)) % vec2<i32>(size));
+        let s = read_buffer_c(vec3<u32>(fft_shift(display, size, false), 0u));
+        corners[k] = sqrt(s.re * s.re + s.im * s.im);
+    }
+    return mix(mix(corners[0], corners[1], f.x), mix(corners[2], corners[3], f.x), f.y);
+}
+
+// Log-polar resampling of both magnitude spectra from **C** into **A**, where rotation and scale
+// become a shift along the rows and columns. The `(1 - X)(2 - X)` high-pass suppresses the
+// low frequencies the image borders dominate.
+@compute
+@workgroup_size(16, 16, 1)
+fn fft_log_polar_magnitude(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let size = settings.size;
+    if (any(gid.xy >= size)) {
+        return;
+    }
+    let steps = log_polar_steps();
+    let radius = exp(f32(gid.x) * steps.x) / f32(min(size.x, size.y));
+    let angle = f32(gid.y) * steps.y;
+    let frequency = radius * vec2<f32>(cos(angle), sin(angle));
+    let x = cos(PI * frequency.x) * cos(PI * frequency.y);
+    let high_pass = (1.0 - x) * (2.0 - x);
+    let samples = log(vec2<f32>(1.0) + high_pass * magnitude_bilinear(frequency));
+    write_buffer_a(vec3<u32>(gid.xy, 0u), c32_2(samples, vec2<f32>(0.0)));
+}
+
+fn refine_peak(below: f32, peak: f32, above: f32) -> f32 {
+    let curvature = below - 2.0 * peak + above;
+    if (curvature < 0.0) {
+        return clamp(0.5 * (below - above) / curvature, -0.5, 0.5);
+    }
+    return 0.0;
+}
+
+// Sub-sample peak position, wrapped to signed shifts. Mirrors `FftCorrelationPeak::translation`.
+fn peak_shift(peak: CorrelationPeak) -> vec2<f32> {
+    let n = peak.neighbors;
+    let position = vec2<f32>(peak.texel) + vec2<f32>(
+        refine_peak(n.x, peak.value, n.y),
+        refine_peak(n.z, peak.value, n.w),
+    );
+    let size = vec2<f32>(settings.size);
+    return select(position, position - size, position > size * 0.5);
+}
+
+// Rotation and scale from the log-polar peak, then the moving image with both undone about the
+// image centre into lane 0 of **A** and the reference into lane 1, as the stock load does.
+@compute
+@workgroup_size(16, 16, 1)
+fn fft_fourier_mellin_load(@builtin(global_invocation_id) gid: vec3<u32>) {
+    if (any(gid.xy >= settings.size)) {
+        return;
+    }
+    let shift = peak_shift(result.log_polar) * log_polar_steps();
+    let rotation = shift.y;
+    let scale = exp(-shift.x);
+    if (all(gid.xy == vec2<u32>(0u))) {
+        result.rotation = rotation;
+        result.scale = scale;
+    }
+    var samples = vec2<f32>(0.0);
+    let signal = settings.size - settings.padding;
+    if (all(gid.xy < signal)) {
+        let centre = (vec2<f32>(signal) - 1.0) * 0.5;
+        let q = vec2<f32>(gid.xy) - centre;
+        let c = cos(rotation);
+        let s = sin(rotation);
+        let p = centre + scale * vec2<f32>(c * q.x - s * q.y, s * q.x + c * q.y);
+        samples = vec2<f32>(luminance_bilinear(moving_texture, p), luminance(reference_texture, gid.xy));
+    }
+    write_buffer_a(vec3<u32>(gid.xy, 0u), c32_2(samples, vec2<f32>(0.0)));
+}
@@ -16,53 +16,60 @@ fn viridis_quintic(x: f32) -> vec3<f32> {
     );
 }
 
-// Spatial-domain taper chosen by `window_type` / `window_strength` in FFT settings.
-fn apply_window(pos: vec2<u32>, size: vec2<u32>, window_type: u32, strength: f32) -> f32 {
-    let x_norm = f32(pos.x) / f32(size.x - 1u);
-    let y_norm = f32(pos.y) / f32(size.y - 1u);
-    
-    var window_value = 1.0;
-    
-    if (window_type == 0u) {
-        window_value = 1.0;
-    }
-    // Tukey: gentle edges and a wide flat center; works well on large height-field tiles.
-    else if (window_type == 1u) {
-        let alpha = 0.1; // small alpha keeps most of the interior untapered
-        let x_window = tukey_1d(x_norm, alpha);
-        let y_window = tukey_1d(y_norm, alpha);
-        window_value = x_window * y_window;
-    }
-    // Blackman: strong edge roll-off when you want sidelobe suppression in both axes.
-    else if (window_type == 2u) {
-        let a0 = 0.42;
-        let a1 = 0.5;
-        let a2 = 0.08;
-        
-        let x_window = a0 - a1 * cos(2.0 * 3.14159265359 * x_norm) + a2 * cos(4.0 * 3.14159265359 * x_norm);
-        let y_window = a0 - a1 * cos(2.0 * 3.14159265359 * y_norm) + a2 * cos(4.0 * 3.14159265359 * y_norm);
-        
-        window_value = x_window * y_window;
+// Bin displayed at `pos` once the zero frequency is moved to the centre, as numpy's `fftshift`
+// lays a spectrum out: DC lands at `size / 2`, so odd axes shift by the larger half. `rows_only`
+// leaves the second axis in FFT order, for 1D batches.
+fn fft_shift(pos: vec2<u32>, size: vec2<u32>, rows_only: bool) -> vec2<u32> {
+    let half = size - size / 2u;
+    return (pos + vec2<u32>(half.x, select(half.y, 0u, rows_only))) % size;
+}
+
+// Spatial-domain taper chosen by `window_type` in FFT settings, evaluated at sample `i` of an
+// `n`-sample axis. Encodings follow `FftWindow` on the Rust side; `param` carries Tukey alpha,
+// Kaiser beta or the Gaussian sigma (relative to the half-width).
+fn apply_window(i: u32, n: u32, window_type: u32, param: f32, strength: f32) -> f32 {
+    if (n <= 1u) {
+        return 1.0;
     }
-    // Kaiser: trades main-lobe width against side lobes via `beta`.
-    else if (window_type == 3u) {
-        let beta = 2.0;
-        let x_centered = 2.0 * x_norm - 1.0;
-        let y_centered = 2.0 * y_norm - 1.0;
-        let r_squared = x_centered * x_centered + y_centered * y_centered;
-        
-        if (r_squared < 1.0) {
-            let term = 1.0 - r_squared;
-            window_value = exp(beta * (sqrt(term) - 1.0));
-        } else {
-            window_value = 0.0;
+    let x = f32(i) / f32(n - 1u);
+    let tau = 2.0 * 3.14159265359;
+
+    var window_value = 1.0;
+    switch window_type {
+        // Tukey: flat centre, cosine edges over the `alpha` fraction of the axis.
+        case 1u: { window_value = tukey_1d(x, param); }
+        // Blackman: strong edge roll-off when you want sidelobe suppression.
+        case 2u: { window_value = 0.42 - 0.5 * cos(tau * x) + 0.08 * cos(2.0 * tau * x); }
+        // Kaiser: trades main-lobe width against side lobes via `beta`.
+        case 3u: {
+            let r = 2.0 * x - 1.0;
+            window_value = bessel_i0(param * sqrt(max(0.0, 1.0 - r * r))) / bessel_i0(param);
         }
+        case 4u: { window_value = 0.5 - 0.5 * cos(tau * x); }
+        case 5u: { window_value = 0.54 - 0.46 * cos(tau * x); }
+        case 6u: {
+            let r = (2.0 * x - 1.0) / max(param, 1e-4);
+            window_value = exp(-0.5 * r * r);
+        }
+        default: {}
     }
-    
+
     // `strength` mixes identity (0) with the full taper (1).
     return (1.0 - strength) + strength * window_value;
 }
 
+// Modified Bessel function of the first kind, order zero, by its power series.
+fn bessel_i0(x: f32) -> f32 {
+    let q = 0.25 * x * x;
+    var term = 1.0;
+    var sum = 1.0;
+    for (var k = 1u; k < 48u; k++) {
+        term *= q / f32(k * k);
+        sum += term;
+    }
+    return sum;
+}
+
 fn tukey_1d(x: f32, alpha: f32) -> f32 {
     let safe_alpha = max(0.0001, min(1.0, alpha));
     
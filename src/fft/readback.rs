@@ -0,0 +1,352 @@
+//! CPU readback of [`FftTextures`] images, built on Bevy's [`Readback`].
+//!
+//! Insert [`FftReadback`] next to an [`FftSource`] and observe [`FftReadbackComplete`] on the
+//! same entity. Copies run after the render graph of the frame that requested them and arrive a
+//! frame or two later.
+
+use bevy::{
+    diagnostic::FrameCount,
+    log::warn,
+    math::{UVec3, Vec4},
+    platform::collections::HashSet,
+    prelude::*,
+    render::{
+        gpu_readback::{Readback, ReadbackComplete},
+        render_resource::TextureFormat,
+        renderer::RenderDevice,
+    },
+    utils::once,
+};
+
+use super::{FftSource, FftTextures};
+use crate::complex::c32;
+
+/// Which [`FftTextures`] image an [`FftReadback`] copies.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Reflect)]
+pub enum FftReadbackTarget {
+    /// [`FftTextures::spatial_output`], delivered as [`FftReadbackData::Texels`].
+    SpatialOutput,
+    /// [`FftTextures::power_spectrum`], delivered as [`FftReadbackData::Texels`].
+    PowerSpectrum,
+    /// Buffer **C**, delivered as [`FftReadbackData::Complex`]. **C** holds the spectrum at the
+    /// end of the frame under [`super::FftSchedule::Forward`]; the inverse pass may use it as
+    /// scratch. Not available under [`super::FftBackend::StorageBuffers`].
+    BufferC,
+}
+
+/// Requests copies of [`FftTextures`] images back to the CPU. Each result is triggered as
+/// [`FftReadbackComplete`] on this entity.
+#[derive(Component, Clone, Debug, Reflect)]
+pub struct FftReadback {
+    pub targets: Vec<FftReadbackTarget>,
+    /// Request a new copy once the previous one has arrived instead of removing this component
+    /// after the first.
+    pub continuous: bool,
+}
+
+impl FftReadback {
+    /// Reads every target once, then removes the component.
+    pub fn once(targets: impl IntoIterator<Item = FftReadbackTarget>) -> Self {
+        Self {
+            targets: targets.into_iter().collect(),
+            continuous: false,
+        }
+    }
+
+    /// Keeps one copy of every target in flight for as long as the component stays.
+    pub fn continuous(targets: impl IntoIterator<Item = FftReadbackTarget>) -> Self {
+        Self {
+            targets: targets.into_iter().collect(),
+            continuous: true,
+        }
+    }
+}
+
+/// Decoded image contents, row-major with `x` fastest, then `y`, then volume depth or layer.
+#[derive(Clone, Debug)]
+pub enum FftReadbackData {
+    /// One RGBA texel per pixel.
+    Texels(Vec<Vec4>),
+    /// `channels` complex lanes per texel: lane `k` of texel `i` is `samples[i * channels + k]`.
+    /// Half-precision workspaces are widened to f32.
+    Complex { channels: u32, samples: Vec<c32> },
+}
+
+/// Triggered on the [`FftSource`] entity when an [`FftReadback`] copy has been decoded.
+#[derive(EntityEvent, Clone, Debug)]
+pub struct FftReadbackComplete {
+    pub entity: Entity,
+    pub target: FftReadbackTarget,
+    /// [`FrameCount`] of the frame that requested the copy. The copy runs after that frame's
+    /// render graph once the images exist on the GPU.
+    pub frame: u32,
+    /// Image extent in texels; `z` is the volume depth or layer count, or `1`.
+    pub size: UVec3,
+    pub data: FftReadbackData,
+}
+
+/// One in-flight copy, on the entity holding the [`Readback`] of the real part or the whole
+/// image.
+#[derive(Component)]
+pub(super) struct FftReadbackRequest {
+    source: Entity,
+    target: FftReadbackTarget,
+    frame: u32,
+    format: TextureFormat,
+    size: UVec3,
+    channels: u32,
+    /// Entity reading the imaginary texture of a full-precision buffer **C**.
+    imaginary: Option<Entity>,
+    re: Option<Vec<u8>>,
+    im: Option<Vec<u8>>,
+}
+
+/// Imaginary half of a split [`FftReadbackRequest`].
+#[derive(Component)]
+pub(super) struct FftReadbackImaginary {
+    request: Entity,
+}
+
+/// Spawns [`Readback`] entities for every [`FftReadback`] without a copy in flight.
+pub(super) fn request_fft_readbacks(
+    mut commands: Commands,
+    frame: Res<FrameCount>,
+    images: Res<Assets<Image>>,
+    sources: Query<(Entity, &FftReadback, &FftTextures, &FftSource)>,
+    pending: Query<&FftReadbackRequest>,
+) {
+    let in_flight: HashSet<Entity> = pending.iter().map(|request| request.source).collect();
+    for (entity, readback, textures, source) in &sources {
+        if in_flight.contains(&entity) {
+            continue;
+        }
+        for &target in &readback.targets {
+            let (re, im) = match target {
+                FftReadbackTarget::SpatialOutput => (&textures.spatial_output, None),
+                FftReadbackTarget::PowerSpectrum => (&textures.power_spectrum, None),
+                FftReadbackTarget::BufferC => (&textures.buffer_c_re, Some(&textures.buffer_c_im)),
+            };
+            // Storage-buffer workspaces leave the buffer handles at their defaults.
+            let image = (*re != Handle::default()).then(|| images.get(re)).flatten();
+            let Some(image) = image else {
+                once!(warn!(
+                    "FftReadback: no image backs {target:?}; storage-buffer workspaces cannot be read back"
+                ));
+                continue;
+            };
+            let extent = image.texture_descriptor.size;
+            let request = commands.spawn(Readback::texture(re.clone())).id();
+            let imaginary = im.filter(|im| *im != re).map(|im| {
+                commands
+                    .spawn((
+                        Readback::texture(im.clone()),
+                        FftReadbackImaginary { request },
+                    ))
+                    .id()
+            });
+            commands.entity(request).insert(FftReadbackRequest {
+                source: entity,
+                target,
+                frame: frame.0,
+                format: image.texture_descriptor.format,
+                size: UVec3::new(extent.width, extent.height, extent.depth_or_array_layers),
+                channels: source.channels.clamp(1, 4),
+                imaginary,
+                re: None,
+                im: None,
+            });
+        }
+        if !readback.continuous {
+            commands.entity(entity).remove::<FftReadback>();
+        }
+    }
+}
+
+/// Collects [`ReadbackComplete`] bytes for FFT requests and triggers [`FftReadbackComplete`]
+/// once every part has arrived.
+pub(super) fn on_fft_readback_complete(
+    complete: On<ReadbackComplete>,
+    mut commands: Commands,
+    mut requests: Query<&mut FftReadbackRequest>,
+    imaginary: Query<&FftReadbackImaginary>,
+) {
+    let part = complete.entity;
+    let (request_entity, is_imaginary) = match imaginary.get(part) {
+        Ok(imaginary) => (imaginary.request, true),
+        Err(_) => (part, false),
+    };
+    let Ok(mut request) = requests.get_mut(request_entity) else {
+        return;
+    };
+    // `Readback` copies every frame until the despawn lands; keep the first result.
+    let slot = if is_imaginary {
+        &mut request.im
+    } else {
+        &mut request.re
+    };
+    if slot.is_none() {
+        *slot = Some(complete.data.clone());
+    }
+    let Some(re) = request.re.as_deref() else {
+        return;
+    };
+    if request.imaginary.is_some() && request.im.is_none() {
+        return;
+    }
+
+    let data = match request.target {
+        FftReadbackTarget::SpatialOutput | FftReadbackTarget::PowerSpectrum => {
+            FftReadbackData::Texels(decode_texels(&unpad_rows(re, request.size, 16)))
+        }
+        FftReadbackTarget::BufferC => {
+            let Some((components, _)) = workspace_components(request.format) else {
+                once!(warn!(
+                    "FftReadback: unexpected workspace format {:?}",
+                    request.format
+                ));
+                return;
+            };
+            let texel_bytes = components as usize * 4;
+            let re = unpad_rows(re, request.size, texel_bytes);
+            let im = request
+                .im
+                .as_deref()
+                .map(|im| unpad_rows(im, request.size, texel_bytes));
+            let channels = request.channels.min(components);
+            let Some(samples) = decode_complex(&re, im.as_deref(), request.format, channels) else {
+                return;
+            };
+            FftReadbackData::Complex { channels, samples }
+        }
+    };
+    commands.trigger(FftReadbackComplete {
+        entity: request.source,
+        target: request.target,
+        frame: request.frame,
+        size: request.size,
+        data,
+    });
+    commands.entity(request_entity).despawn();
+    if let Some(imaginary) = request.imaginary {
+        commands.entity(imaginary).despawn();
+    }
+}
+
+/// Drops the padding Bevy's texture copy adds to every row, leaving tightly packed texels.
+fn unpad_rows(bytes: &[u8], size: UVec3, texel_bytes: usize) -> Vec<u8> {
+    let row = size.x as usize * texel_bytes;
+    let stride = RenderDevice::align_copy_bytes_per_row(row);
+    let rows = (size.y * size.z) as usize;
+    let mut packed = Vec::with_capacity(row * rows);
+    for chunk in bytes.chunks(stride).take(rows) {
+        packed.extend_from_slice(&chunk[..row.min(chunk.len())]);
+    }
+    packed
+}
+
+fn read_f32(bytes: &[u8], word: usize) -> f32 {
+    f32::from_le_bytes(bytes[word * 4..word * 4 + 4].try_into().unwrap())
+}
+
+fn decode_texels(bytes: &[u8]) -> Vec<Vec4> {
+    (0..bytes.len() / 16)
+        .map(|texel| {
+            Vec4::from_array(std::array::from_fn(|lane| {
+                read_f32(bytes, texel * 4 + lane)
+            }))
+        })
+        .collect()
+}
+
+/// Components per texel of a workspace texture and whether they are packed f16 pairs.
+fn workspace_components(format: TextureFormat) -> Option<(u32, bool)> {
+    match format {
+        TextureFormat::R32Float => Some((1, false)),
+        TextureFormat::Rg32Float => Some((2, false)),
+        TextureFormat::Rgba32Float => Some((4, false)),
+        TextureFormat::R32Uint => Some((1, true)),
+        TextureFormat::Rg32Uint => Some((2, true)),
+        TextureFormat::Rgba32Uint => Some((4, true)),
+        _ => None,
+    }
+}
+
+/// Decodes the first `channels` lanes of every texel. Full-precision textures take their
+/// imaginary parts from `im`, or zero without it; packed half textures hold both parts.
+fn decode_complex(
+    re: &[u8],
+    im: Option<&[u8]>,
+    format: TextureFormat,
+    channels: u32,
+) -> Option<Vec<c32>> {
+    let (components, half) = workspace_components(format)?;
+    let (components, channels) = (components as usize, channels as usize);
+    if im.is_some_and(|im| im.len() != re.len()) {
+        return None;
+    }
+    let texels = re.len() / (components * 4);
+    let mut samples = Vec::with_capacity(texels * channels);
+    for texel in 0..texels {
+        for lane in 0..channels {
+            let word = texel * components + lane;
+            let sample = if half {
+                let bits = u32::from_le_bytes(re[word * 4..word * 4 + 4].try_into().unwrap());
+                c32::new(
+                    half::f16::from_bits(bits as u16).to_f32(),
+                    half::f16::from_bits((bits >> 16) as u16).to_f32(),
+                )
+            } else {
+                c32::new(read_f32(re, word), im.map_or(0.0, |im| read_f32(im, word)))
+            };
+            samples.push(sample);
+        }
+    }
+    Some(samples)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fft::resources::pack_half_texels;
+
+    fn f32_bytes(values: &[f32]) -> Vec<u8> {
+        values.iter().flat_map(|v| v.to_le_bytes()).collect()
+    }
+
+    #[test]
+    fn unpad_rows_strips_copy_alignment() {
+        // Three Rgba32Float texels per row are 48 bytes, padded to 256.
+        let size = UVec3::new(3, 2, 1);
+        let mut bytes = vec![0xff; 512];
+        bytes[..48].copy_from_slice(&[1; 48]);
+        bytes[256..304].copy_from_slice(&[2; 48]);
+        let packed = unpad_rows(&bytes, size, 16);
+        assert_eq!(packed.len(), 96);
+        assert!(packed[..48].iter().all(|&b| b == 1));
+        assert!(packed[48..].iter().all(|&b| b == 2));
+    }
+
+    #[test]
+    fn split_workspace_decodes_requested_lanes() {
+        let re = f32_bytes(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0]);
+        let im = f32_bytes(&[-1.0, -2.0, -3.0, -4.0, -5.0, -6.0, -7.0, -8.0]);
+        let samples = decode_complex(&re, Some(&im), TextureFormat::Rgba32Float, 2).unwrap();
+        let expected = [(1.0, -1.0), (2.0, -2.0), (5.0, -5.0), (6.0, -6.0)];
+        assert_eq!(samples.len(), expected.len());
+        for (sample, (re, im)) in samples.iter().zip(expected) {
+            assert_eq!((sample.re, sample.im), (re, im));
+        }
+    }
+
+    #[test]
+    fn packed_half_round_trips_through_upload_packing() {
+        let re = f32_bytes(&[0.5, 1.5, 0.0, 0.0, -2.0, 0.25, 0.0, 0.0]);
+        let im = f32_bytes(&[0.125, -4.0, 0.0, 0.0, 3.0, 0.75, 0.0, 0.0]);
+        let packed = pack_half_texels(&re, Some(&im), 2).unwrap();
+        let samples = decode_complex(&packed, None, TextureFormat::Rg32Uint, 2).unwrap();
+        let expected = [(0.5, 0.125), (1.5, -4.0), (-2.0, 3.0), (0.25, 0.75)];
+        for (sample, (re, im)) in samples.iter().zip(expected) {
+            assert_eq!((sample.re, sample.im), (re, im));
+        }
+    }
+}
@@ -1,24 +1,186 @@
 #define_import_path bevy_fft::resolve_outputs
 
+#import bevy_fft::plot::fft_shift
+
 // Mirror `FftSettings` from `bindings.wgsl` whenever the uniform changes.
 struct FftSettings {
     size: vec2<u32>,
-    orders: u32,
+    orders: vec2<u32>,
+    fft_size: vec2<u32>,
     padding: vec2<u32>,
     schedule: u32,
     pattern_target: u32,
     window_type: u32,
     window_strength: f32,
-    radial_falloff: f32,
+    window_param: f32,
     normalization: f32,
+    radix: u32,
+    dimension: u32,
+    depth: u32,
+    fft_normalization: u32,
+    window_axes: u32,
+    storage_precision: u32,
+    channels: u32,
 }
 
 @group(0) @binding(0) var<uniform> settings: FftSettings;
+// `FFT_HALF` binds the packed workspaces of `FftPrecision::Half` and skips binding 2.
+// `FFT_TEXEL_R` / `FFT_TEXEL_RG` match the narrow workspace formats of one- and two-channel sources.
+// `FFT_STORAGE_BUFFERS` reads **C** and **B** from the storage-buffer workspaces, two
+// `vec4<f32>` (real, imaginary) per sample. `FFT_LAYERS` binds 2D array textures and resolves
+// layer `gid.z` of each.
+#ifdef FFT_STORAGE_BUFFERS
+@group(0) @binding(1) var<storage, read> spectrum_c: array<vec4<f32>>;
+@group(0) @binding(3) var<storage, read> spatial_b: array<vec4<f32>>;
+#else
+#ifdef FFT_LAYERS
+#ifdef FFT_HALF
+#ifdef FFT_TEXEL_R
+@group(0) @binding(1) var spectrum_c: texture_storage_2d_array<r32uint, read>;
+@group(0) @binding(3) var spatial_b: texture_storage_2d_array<r32uint, read>;
+#else
+#ifdef FFT_TEXEL_RG
+@group(0) @binding(1) var spectrum_c: texture_storage_2d_array<rg32uint, read>;
+@group(0) @binding(3) var spatial_b: texture_storage_2d_array<rg32uint, read>;
+#else
+@group(0) @binding(1) var spectrum_c: texture_storage_2d_array<rgba32uint, read>;
+@group(0) @binding(3) var spatial_b: texture_storage_2d_array<rgba32uint, read>;
+#endif
+#endif
+#else
+#ifdef FFT_TEXEL_R
+@group(0) @binding(1) var spectrum_c_re: texture_storage_2d_array<r32float, read>;
+@group(0) @binding(2) var spectrum_c_im: texture_storage_2d_array<r32float, read>;
+@group(0) @binding(3) var spatial_b_re: texture_storage_2d_array<r32float, read>;
+#else
+#ifdef FFT_TEXEL_RG
+@group(0) @binding(1) var spectrum_c_re: texture_storage_2d_array<rg32float, read>;
+@group(0) @binding(2) var spectrum_c_im: texture_storage_2d_array<rg32float, read>;
+@group(0) @binding(3) var spatial_b_re: texture_storage_2d_array<rg32float, read>;
+#else
+@group(0) @binding(1) var spectrum_c_re: texture_storage_2d_array<rgba32float, read>;
+@group(0) @binding(2) var spectrum_c_im: texture_storage_2d_array<rgba32float, read>;
+@group(0) @binding(3) var spatial_b_re: texture_storage_2d_array<rgba32float, read>;
+#endif
+#endif
+#endif
+#else
+#ifdef FFT_HALF
+#ifdef FFT_TEXEL_R
+@group(0) @binding(1) var spectrum_c: texture_storage_2d<r32uint, read>;
+@group(0) @binding(3) var spatial_b: texture_storage_2d<r32uint, read>;
+#else
+#ifdef FFT_TEXEL_RG
+@group(0) @binding(1) var spectrum_c: texture_storage_2d<rg32uint, read>;
+@group(0) @binding(3) var spatial_b: texture_storage_2d<rg32uint, read>;
+#else
+@group(0) @binding(1) var spectrum_c: texture_storage_2d<rgba32uint, read>;
+@group(0) @binding(3) var spatial_b: texture_storage_2d<rgba32uint, read>;
+#endif
+#endif
+#else
+#ifdef FFT_TEXEL_R
+@group(0) @binding(1) var spectrum_c_re: texture_storage_2d<r32float, read>;
+@group(0) @binding(2) var spectrum_c_im: texture_storage_2d<r32float, read>;
+@group(0) @binding(3) var spatial_b_re: texture_storage_2d<r32float, read>;
+#else
+#ifdef FFT_TEXEL_RG
+@group(0) @binding(1) var spectrum_c_re: texture_storage_2d<rg32float, read>;
+@group(0) @binding(2) var spectrum_c_im: texture_storage_2d<rg32float, read>;
+@group(0) @binding(3) var spatial_b_re: texture_storage_2d<rg32float, read>;
+#else
 @group(0) @binding(1) var spectrum_c_re: texture_storage_2d<rgba32float, read>;
 @group(0) @binding(2) var spectrum_c_im: texture_storage_2d<rgba32float, read>;
 @group(0) @binding(3) var spatial_b_re: texture_storage_2d<rgba32float, read>;
+#endif
+#endif
+#endif
+#endif
+#endif
+#ifdef FFT_LAYERS
+@group(0) @binding(4) var power_spectrum_out: texture_storage_2d_array<rgba32float, write>;
+@group(0) @binding(5) var spatial_output_out: texture_storage_2d_array<rgba32float, write>;
+#else
 @group(0) @binding(4) var power_spectrum_out: texture_storage_2d<rgba32float, write>;
 @group(0) @binding(5) var spatial_output_out: texture_storage_2d<rgba32float, write>;
+#endif
+
+// Display form of a workspace texel. One channel is shown as grey, and sources without an alpha
+// channel resolve opaque. Lanes past `CHANNELS` are zero in the workspace.
+fn spread_channels(v: vec4<f32>) -> vec4<f32> {
+#if CHANNELS == 1
+    return vec4<f32>(v.xxx, 1.0);
+#else if CHANNELS == 4
+    return v;
+#else
+    return vec4<f32>(v.xyz, 1.0);
+#endif
+}
+
+// First `vec4<f32>` of the sample at `p` of `layer` in a storage-buffer workspace.
+fn sample_index(p: vec2<i32>, layer: u32) -> u32 {
+    return ((layer * settings.size.y + u32(p.y)) * settings.size.x + u32(p.x)) * 2u;
+}
+
+// Real (`[0]`) and imaginary (`[1]`) channels of spectrum **C**.
+fn load_spectrum(p: vec2<i32>, layer: u32) -> array<vec4<f32>, 2> {
+#ifdef FFT_STORAGE_BUFFERS
+    let i = sample_index(p, layer);
+    return array<vec4<f32>, 2>(spectrum_c[i], spectrum_c[i + 1u]);
+#else
+#ifdef FFT_HALF
+#ifdef FFT_LAYERS
+    let packed = textureLoad(spectrum_c, p, layer);
+#else
+    let packed = textureLoad(spectrum_c, p);
+#endif
+    var re = vec4<f32>(0.0);
+    var im = vec4<f32>(0.0);
+    for (var ch = 0u; ch < 4u; ch++) {
+        let v = unpack2x16float(packed[ch]);
+        re[ch] = v.x;
+        im[ch] = v.y;
+    }
+    return array<vec4<f32>, 2>(re, im);
+#else
+#ifdef FFT_LAYERS
+    return array<vec4<f32>, 2>(
+        textureLoad(spectrum_c_re, p, layer),
+        textureLoad(spectrum_c_im, p, layer),
+    );
+#else
+    return array<vec4<f32>, 2>(textureLoad(spectrum_c_re, p), textureLoad(spectrum_c_im, p));
+#endif
+#endif
+#endif
+}
+
+// Real channels of spatial buffer **B**.
+fn load_spatial(p: vec2<i32>, layer: u32) -> vec4<f32> {
+#ifdef FFT_STORAGE_BUFFERS
+    return spatial_b[sample_index(p, layer)];
+#else
+#ifdef FFT_HALF
+#ifdef FFT_LAYERS
+    let packed = textureLoad(spatial_b, p, layer);
+#else
+    let packed = textureLoad(spatial_b, p);
+#endif
+    return vec4<f32>(
+        unpack2x16float(packed.x).x,
+        unpack2x16float(packed.y).x,
+        unpack2x16float(packed.z).x,
+        unpack2x16float(packed.w).x,
+    );
+#else
+#ifdef FFT_LAYERS
+    return textureLoad(spatial_b_re, p, layer);
+#else
+    return textureLoad(spatial_b_re, p);
+#endif
+#endif
+#endif
+}
 
 // fftshifted log magnitude for RGB. Run after the spectrum stage while **C** is still the spectrum.
 // The inverse pass rewrites **C** as scratch memory, so this runs before that pass in the graph.
@@ -31,13 +193,13 @@ fn resolve_power_spectrum_from_c(@builtin(global_invocation_id) gid: vec3<u32>)
         return;
     }
 
-    let hx = dims.x >> 1u;
-    let hy = dims.y >> 1u;
-    let sp = vec2<u32>((pos.x + hx) % dims.x, (pos.y + hy) % dims.y);
+    // 1D batches keep one signal per row, so only the frequency axis is shifted.
+    let sp = fft_shift(pos, dims, settings.dimension == 1u);
     let isp = vec2<i32>(i32(sp.x), i32(sp.y));
 
-    let cre = textureLoad(spectrum_c_re, isp);
-    let cim = textureLoad(spectrum_c_im, isp);
+    let c_pair = load_spectrum(isp, gid.z);
+    let cre = spread_channels(c_pair[0]);
+    let cim = spread_channels(c_pair[1]);
 
     var mag = vec3<f32>(0.0);
     for (var ch = 0u; ch < 3u; ch++) {
@@ -58,22 +220,31 @@ fn resolve_power_spectrum_from_c(@builtin(global_invocation_id) gid: vec3<u32>)
     } else {
         c = vec3<f32>(0.0);
     }
+#ifdef FFT_LAYERS
+    textureStore(power_spectrum_out, pos, gid.z, vec4<f32>(c, 1.0));
+#else
     textureStore(power_spectrum_out, pos, vec4<f32>(c, 1.0));
+#endif
 }
 
 // Spatial output from **B** after inverse FFT. RGB is slopes and elevation; alpha is simulation data (e.g. ocean chop).
+// Only the signal region is written, which crops away any zero-padding border.
 @compute
 @workgroup_size(16, 16, 1)
 fn resolve_spatial_from_b(@builtin(global_invocation_id) gid: vec3<u32>) {
-    let dims = settings.size;
+    let dims = settings.size - settings.padding;
     let pos = gid.xy;
     if (pos.x >= dims.x || pos.y >= dims.y) {
         return;
     }
 
     let ip = vec2<i32>(i32(pos.x), i32(pos.y));
-    let spatial = textureLoad(spatial_b_re, ip);
+    let spatial = spread_channels(load_spatial(ip, gid.z));
     let n = settings.normalization;
     let s = vec4<f32>(spatial.x * n, spatial.y * n, spatial.z * n, spatial.w * n);
+#ifdef FFT_LAYERS
+    textureStore(spatial_output_out, pos, gid.z, s);
+#else
     textureStore(spatial_output_out, pos, s);
+#endif
 }
@@ -1,197 +1,699 @@
-use std::num::NonZero;
+use std::{
+    num::NonZeroU64,
+    sync::atomic::{AtomicU32, Ordering},
+};
 
 use bevy::{
     asset::{AssetServer, Assets, Handle, RenderAssetUsages},
     image::Image,
-    log::{info, trace, warn},
+    log::{error, trace, warn},
+    platform::{collections::HashMap, sync::Arc},
     prelude::*,
     render::{
-        extract_component::{ComponentUniforms, ExtractComponent},
+        extract_component::{ComponentUniforms, DynamicUniformIndex, ExtractComponent},
         globals::{GlobalsBuffer, GlobalsUniform},
         render_asset::RenderAssets,
         render_resource::{binding_types::*, *},
+        renderer::RenderAdapter,
         renderer::{RenderDevice, RenderQueue},
+        settings::{WgpuFeatures, WgpuLimits},
         texture::GpuImage,
     },
     shader::ShaderDefVal,
     utils::once,
 };
 
-use super::{FftInputDomain, FftRoots, FftSettings, FftSkipStockPipeline, FftSource};
+use super::trigger::FftTriggerState;
+use super::{
+    FFT_MAX_TRANSFORM_LEN, FftBackend, FftDimension, FftInputDomain, FftRoots, FftSettings,
+    FftSkipStockPipeline, FftSource, bluestein::bluestein_kernel_spectrum,
+    forward_fft_twiddle_table,
+};
 use crate::{complex::c32, fft::FftInputTexture};
 
-#[derive(Resource)]
-pub struct FftBindGroupLayouts {
-    pub common: BindGroupLayoutDescriptor,
-    pub resolve_outputs: BindGroupLayoutDescriptor,
+/// Which narrow storage formats the adapter can bind read-write, sampled once from
+/// [`RenderAdapter`] and shared by the main and render worlds so both pick the same
+/// [`FftWorkspaceFormat`]. Without an adapter every flag is `false` and workspaces stay RGBA.
+#[derive(Resource, Clone, Copy, Default, Debug)]
+pub struct FftTexelSupport {
+    pub r32_float: bool,
+    pub rg32_float: bool,
+    pub r32_uint: bool,
+    pub rg32_uint: bool,
 }
 
-impl FromWorld for FftBindGroupLayouts {
-    fn from_world(_world: &mut World) -> Self {
-        let entries = BindGroupLayoutEntries::sequential(
-            ShaderStages::COMPUTE,
+impl FftTexelSupport {
+    /// Reads the storage capabilities of `adapter`; `None` gives [`Self::default`].
+    pub fn from_adapter(adapter: Option<&RenderAdapter>) -> Self {
+        let Some(adapter) = adapter else {
+            return Self::default();
+        };
+        let read_write = |format| {
+            adapter
+                .get_texture_format_features(format)
+                .flags
+                .contains(TextureFormatFeatureFlags::STORAGE_READ_WRITE)
+        };
+        Self {
+            r32_float: read_write(TextureFormat::R32Float),
+            rg32_float: read_write(TextureFormat::Rg32Float),
+            r32_uint: read_write(TextureFormat::R32Uint),
+            rg32_uint: read_write(TextureFormat::Rg32Uint),
+        }
+    }
+
+    /// Components per workspace texel for `channels` channels: the narrowest supported format,
+    /// or `4` for three channels and whenever the narrow format is unavailable.
+    pub fn texel_width(&self, channels: u32, half: bool) -> u32 {
+        let (r, rg) = if half {
+            (self.r32_uint, self.rg32_uint)
+        } else {
+            (self.r32_float, self.rg32_float)
+        };
+        match channels {
+            1 if r => 1,
+            1 | 2 if rg => 2,
+            _ => 4,
+        }
+    }
+}
+
+/// Storage layout of one entity's workspace buffers. It picks the texture format, the shader
+/// defs, the bind group layouts and the [`FftPipelines`] entry the entity runs with.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct FftWorkspaceFormat {
+    /// [`FftSettings::channels`], compiled in as the `CHANNELS` shader def.
+    pub channels: u32,
+    /// Components per texel, `1`, `2` or `4` (`FFT_TEXEL_R`, `FFT_TEXEL_RG`, or neither).
+    pub texel_width: u32,
+    /// Packed f16 texels, see [`super::FftPrecision::Half`] (`FFT_HALF`).
+    pub half: bool,
+    /// 3D workspace textures, see [`FftDimension::ThreeD`] (`FFT_VOLUME`).
+    pub volume: bool,
+    /// 2D array workspace textures, one layer per [`FftSource::layers`] (`FFT_LAYERS`).
+    pub layered: bool,
+    /// Storage buffers instead of textures, see [`FftBackend::StorageBuffers`]
+    /// (`FFT_STORAGE_BUFFERS`). Such formats are always f32 with four lanes per sample.
+    pub storage_buffers: bool,
+}
+
+impl FftWorkspaceFormat {
+    /// Four-channel f32 RGBA workspaces, the layout of [`FftBindGroupLayouts::common`].
+    pub const RGBA: Self = Self {
+        channels: 4,
+        texel_width: 4,
+        half: false,
+        volume: false,
+        layered: false,
+        storage_buffers: false,
+    };
+
+    /// Format of an entity with the extracted `settings` under `backend`.
+    pub fn from_settings(
+        settings: &FftSettings,
+        support: &FftTexelSupport,
+        backend: FftBackend,
+    ) -> Self {
+        if backend == FftBackend::StorageBuffers {
+            return Self {
+                channels: settings.channels,
+                volume: settings.is_volume(),
+                layered: settings.is_layered(),
+                storage_buffers: true,
+                ..Self::RGBA
+            };
+        }
+        let half = settings.is_half();
+        Self {
+            channels: settings.channels,
+            texel_width: support.texel_width(settings.channels, half),
+            half,
+            volume: settings.is_volume(),
+            layered: settings.is_layered(),
+            storage_buffers: false,
+        }
+    }
+
+    /// Format the main world allocates for `source`; matches [`Self::from_settings`].
+    pub fn from_source(source: &FftSource, support: &FftTexelSupport, backend: FftBackend) -> Self {
+        Self::from_settings(&FftSettings::from_fft_source(source), support, backend)
+    }
+
+    /// Texture format of each workspace image.
+    pub fn texture_format(&self) -> TextureFormat {
+        match (self.half, self.texel_width) {
+            (true, 1) => TextureFormat::R32Uint,
+            (true, 2) => TextureFormat::Rg32Uint,
+            (true, _) => TextureFormat::Rgba32Uint,
+            (false, 1) => TextureFormat::R32Float,
+            (false, 2) => TextureFormat::Rg32Float,
+            (false, _) => TextureFormat::Rgba32Float,
+        }
+    }
+
+    /// Bytes per workspace texel.
+    pub fn texel_bytes(&self) -> usize {
+        self.texel_width as usize * 4
+    }
+
+    /// Shader defs selecting this format in `fft.wgsl` and `resolve_outputs.wgsl`.
+    pub fn shader_defs(&self) -> Vec<ShaderDefVal> {
+        let mut defs = vec![ShaderDefVal::UInt("CHANNELS".into(), self.channels)];
+        match self.texel_width {
+            1 => defs.push("FFT_TEXEL_R".into()),
+            2 => defs.push("FFT_TEXEL_RG".into()),
+            _ => {}
+        }
+        if self.half {
+            defs.push("FFT_HALF".into());
+        }
+        if self.storage_buffers {
+            defs.push("FFT_STORAGE_BUFFERS".into());
+        }
+        if self.volume {
+            defs.push("FFT_VOLUME".into());
+        }
+        if self.layered {
+            defs.push("FFT_LAYERS".into());
+        }
+        defs
+    }
+
+    /// Layout of the main FFT bind group for this format.
+    pub fn layout(&self) -> BindGroupLayoutDescriptor {
+        let format = self.texture_format();
+        if self.storage_buffers {
+            return BindGroupLayoutDescriptor::new(
+                "fft_storage_buffers_bind_group_layout",
+                &fft_packed_entries(storage_buffer_sized(false, None)),
+            );
+        }
+        if self.half {
+            let access = StorageTextureAccess::ReadWrite;
+            let (label, workspace) = if self.layered {
+                (
+                    "fft_half_layers_bind_group_layout",
+                    texture_storage_2d_array(format, access),
+                )
+            } else {
+                (
+                    "fft_half_bind_group_layout",
+                    texture_storage_2d(format, access),
+                )
+            };
+            return BindGroupLayoutDescriptor::new(label, &fft_packed_entries(workspace));
+        }
+        let (label, entries) = if self.volume {
             (
-                uniform_buffer::<GlobalsUniform>(false),
-                uniform_buffer::<FftSettings>(false),
-                storage_buffer_sized(
-                    false,
-                    Some(NonZero::<u64>::new(std::mem::size_of::<FftRoots>() as u64).unwrap()),
-                ),
-                texture_storage_2d(TextureFormat::Rgba32Float, StorageTextureAccess::ReadWrite),
-                texture_storage_2d(TextureFormat::Rgba32Float, StorageTextureAccess::ReadWrite),
-                texture_storage_2d(TextureFormat::Rgba32Float, StorageTextureAccess::ReadWrite),
-                texture_storage_2d(TextureFormat::Rgba32Float, StorageTextureAccess::ReadWrite),
-                texture_storage_2d(TextureFormat::Rgba32Float, StorageTextureAccess::ReadWrite),
-                texture_storage_2d(TextureFormat::Rgba32Float, StorageTextureAccess::ReadWrite),
-                texture_storage_2d(TextureFormat::Rgba32Float, StorageTextureAccess::ReadWrite),
-                texture_storage_2d(TextureFormat::Rgba32Float, StorageTextureAccess::ReadWrite),
-            ),
-        );
+                "fft_volume_bind_group_layout",
+                fft_common_entries(texture_storage_3d, format),
+            )
+        } else if self.layered {
+            (
+                "fft_layers_bind_group_layout",
+                fft_common_entries(texture_storage_2d_array, format),
+            )
+        } else {
+            (
+                "fft_common_bind_group_layout",
+                fft_common_entries(texture_storage_2d, format),
+            )
+        };
+        BindGroupLayoutDescriptor::new(label, &entries)
+    }
 
-        let resolve_entries = BindGroupLayoutEntries::sequential(
+    /// Layout of the resolve bind group for this format. Half and storage-buffer formats read
+    /// **C** and **B** at bindings 1 and 3. Layered formats resolve into array textures.
+    pub fn resolve_layout(&self) -> BindGroupLayoutDescriptor {
+        let texture = |format, access| {
+            if self.layered {
+                texture_storage_2d_array(format, access)
+            } else {
+                texture_storage_2d(format, access)
+            }
+        };
+        let read = || {
+            if self.storage_buffers {
+                storage_buffer_read_only_sized(false, None)
+            } else {
+                texture(self.texture_format(), StorageTextureAccess::ReadOnly)
+            }
+        };
+        let write = || texture(TextureFormat::Rgba32Float, StorageTextureAccess::WriteOnly);
+        if self.half || self.storage_buffers {
+            let entries = BindGroupLayoutEntries::with_indices(
+                ShaderStages::COMPUTE,
+                (
+                    (0, uniform_buffer::<FftSettings>(true)),
+                    (1, read()),
+                    (3, read()),
+                    (4, write()),
+                    (5, write()),
+                ),
+            );
+            return BindGroupLayoutDescriptor::new(
+                "fft_resolve_outputs_packed_bind_group_layout",
+                &entries,
+            );
+        }
+        let entries = BindGroupLayoutEntries::sequential(
             ShaderStages::COMPUTE,
             (
-                uniform_buffer::<FftSettings>(false),
-                texture_storage_2d(TextureFormat::Rgba32Float, StorageTextureAccess::ReadOnly),
-                texture_storage_2d(TextureFormat::Rgba32Float, StorageTextureAccess::ReadOnly),
-                texture_storage_2d(TextureFormat::Rgba32Float, StorageTextureAccess::ReadOnly),
-                texture_storage_2d(TextureFormat::Rgba32Float, StorageTextureAccess::WriteOnly),
-                texture_storage_2d(TextureFormat::Rgba32Float, StorageTextureAccess::WriteOnly),
+                uniform_buffer::<FftSettings>(true),
+                read(),
+                read(),
+                read(),
+                write(),
+                write(),
             ),
         );
+        BindGroupLayoutDescriptor::new("fft_resolve_outputs_bind_group_layout", &entries)
+    }
+}
 
+/// Layouts of [`FftWorkspaceFormat::RGBA`] sources, which custom passes over the FFT bindings
+/// (the domain plugins and examples) build against. Other formats come from
+/// [`FftWorkspaceFormat::layout`].
+#[derive(Resource)]
+pub struct FftBindGroupLayouts {
+    pub common: BindGroupLayoutDescriptor,
+    /// Same bindings as [`Self::common`] with 3D workspace textures, for
+    /// [`FftDimension::ThreeD`] sources. Shaders select it with the `FFT_VOLUME` shader def.
+    pub volume: BindGroupLayoutDescriptor,
+    /// Same bindings as [`Self::common`] with 2D array workspace textures, for sources with
+    /// several [`FftSource::layers`]. Shaders select it with the `FFT_LAYERS` shader def.
+    pub layers: BindGroupLayoutDescriptor,
+    pub resolve_outputs: BindGroupLayoutDescriptor,
+}
+
+/// Entries of [`FftWorkspaceFormat::layout`] for f32 workspaces; `workspace` builds the binding
+/// for each **A**–**D** texture in `format`.
+fn fft_common_entries(
+    workspace: fn(TextureFormat, StorageTextureAccess) -> BindGroupLayoutEntryBuilder,
+    format: TextureFormat,
+) -> BindGroupLayoutEntries<13> {
+    let texture = || workspace(format, StorageTextureAccess::ReadWrite);
+    BindGroupLayoutEntries::sequential(
+        ShaderStages::COMPUTE,
+        (
+            uniform_buffer::<GlobalsUniform>(false),
+            uniform_buffer::<FftSettings>(true),
+            storage_buffer_sized(false, None),
+            texture(),
+            texture(),
+            texture(),
+            texture(),
+            texture(),
+            texture(),
+            texture(),
+            texture(),
+            storage_buffer_read_only_sized(false, None),
+            storage_buffer_sized(false, None),
+        ),
+    )
+}
+
+/// [`fft_common_entries`] for workspaces that keep real and imaginary parts together
+/// ([`super::FftPrecision::Half`] textures or [`FftBackend::StorageBuffers`]): one `workspace`
+/// binding per buffer at bindings 3, 5, 7 and 9, with the `_im` slots left out.
+fn fft_packed_entries(workspace: BindGroupLayoutEntryBuilder) -> BindGroupLayoutEntries<9> {
+    BindGroupLayoutEntries::with_indices(
+        ShaderStages::COMPUTE,
+        (
+            (0, uniform_buffer::<GlobalsUniform>(false)),
+            (1, uniform_buffer::<FftSettings>(true)),
+            (2, storage_buffer_sized(false, None)),
+            (3, workspace),
+            (5, workspace),
+            (7, workspace),
+            (9, workspace),
+            (11, storage_buffer_read_only_sized(false, None)),
+            (12, storage_buffer_sized(false, None)),
+        ),
+    )
+}
+
+impl FromWorld for FftBindGroupLayouts {
+    fn from_world(_world: &mut World) -> Self {
+        let volume = FftWorkspaceFormat {
+            volume: true,
+            ..FftWorkspaceFormat::RGBA
+        };
+        let layers = FftWorkspaceFormat {
+            layered: true,
+            ..FftWorkspaceFormat::RGBA
+        };
         Self {
-            common: BindGroupLayoutDescriptor::new("fft_common_bind_group_layout", &entries),
-            resolve_outputs: BindGroupLayoutDescriptor::new(
-                "fft_resolve_outputs_bind_group_layout",
-                &resolve_entries,
-            ),
+            common: FftWorkspaceFormat::RGBA.layout(),
+            volume: volume.layout(),
+            layers: layers.layout(),
+            resolve_outputs: FftWorkspaceFormat::RGBA.resolve_layout(),
         }
     }
 }
 
-#[derive(Resource)]
-pub struct FftPipelines {
-    pub forward_br_horizontal: CachedComputePipelineId,
-    pub forward_br_vertical: CachedComputePipelineId,
+/// Line kernels for 2D and 1D sources, built once per workspace format.
+pub struct FftLinePipelines {
+    /// Bit-reverse permute between any two buffers along either axis, driven by push constants.
+    pub bit_reverse: CachedComputePipelineId,
     pub radix2_dit: CachedComputePipelineId,
+    pub stockham_radix2: CachedComputePipelineId,
+    pub stockham_radix4: CachedComputePipelineId,
     pub fft_copy: CachedComputePipelineId,
-    pub inverse_br_horizontal: CachedComputePipelineId,
-    pub inverse_br_vertical: CachedComputePipelineId,
+    /// Whole-line kernel that keeps every stage in workgroup memory. `None` when the device
+    /// cannot hold even a short line; see [`shared_line_capacity`].
+    pub shared_line: Option<CachedComputePipelineId>,
+    pub bluestein_premultiply: CachedComputePipelineId,
+    pub bluestein_multiply: CachedComputePipelineId,
+    pub bluestein_postmultiply: CachedComputePipelineId,
     pub resolve_spectrum: CachedComputePipelineId,
     pub resolve_spatial: CachedComputePipelineId,
 }
 
-impl FromWorld for FftPipelines {
-    fn from_world(world: &mut World) -> Self {
-        let pipeline_cache = world.resource::<PipelineCache>();
-        let layouts = world.resource::<FftBindGroupLayouts>();
-        let asset_server = world.resource::<AssetServer>();
-        let fft = asset_server.load("fft.wgsl");
-        let ifft = asset_server.load("ifft.wgsl");
-        let resolve_shader = super::shaders::RESOLVE_OUTPUTS.clone();
+/// Kernels for volume formats. Volumes only run the radix-2 DIT chain and skip the resolve
+/// passes.
+pub struct FftVolumePipelines {
+    pub bit_reverse: CachedComputePipelineId,
+    pub radix2_dit: CachedComputePipelineId,
+    pub fft_copy: CachedComputePipelineId,
+}
 
-        let base_shader_defs = vec![ShaderDefVal::UInt("CHANNELS".into(), 4)];
+/// Pipelines compiled for one [`FftWorkspaceFormat`].
+pub enum FftFormatPipelines {
+    Lines(FftLinePipelines),
+    Volume(FftVolumePipelines),
+}
 
-        let push_constant_range_20 = PushConstantRange {
-            stages: ShaderStages::COMPUTE,
-            range: 0..20,
-        };
+/// FFT kernels specialized per [`FftWorkspaceFormat`]. Four-channel f32 workspaces of the
+/// plugin's [`FftBackend`] are queued up front; other formats are queued the first frame an
+/// entity needs them.
+#[derive(Resource)]
+pub struct FftPipelines {
+    formats: HashMap<FftWorkspaceFormat, FftFormatPipelines>,
+    /// Longest axis [`FftLinePipelines::shared_line`] accepts. Longer axes fall back to
+    /// per-stage dispatches.
+    pub shared_line_max_len: u32,
+    texel_support: FftTexelSupport,
+    backend: FftBackend,
+    fft_shader: Handle<Shader>,
+    /// `None` when the device supports push constants.
+    constant_slots: Option<FftConstantSlots>,
+}
 
-        let forward_br_horizontal =
-            pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
-                label: Some("fft_forward_br_horizontal".into()),
-                layout: vec![layouts.common.clone()],
-                push_constant_ranges: vec![],
-                shader: fft.clone(),
-                shader_defs: base_shader_defs.clone(),
-                entry_point: Some("fft_forward_br_horizontal".into()),
-                zero_initialize_workgroup_memory: false,
-            });
+impl FftPipelines {
+    /// Workspace format of an entity with the extracted `settings`.
+    pub fn format(&self, settings: &FftSettings) -> FftWorkspaceFormat {
+        FftWorkspaceFormat::from_settings(settings, &self.texel_support, self.backend)
+    }
 
-        let forward_br_vertical =
-            pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
-                label: Some("fft_forward_br_vertical".into()),
-                layout: vec![layouts.common.clone()],
-                push_constant_ranges: vec![],
-                shader: fft.clone(),
-                shader_defs: base_shader_defs.clone(),
-                entry_point: Some("fft_forward_br_vertical".into()),
-                zero_initialize_workgroup_memory: false,
-            });
+    /// Pipelines for `format`, `None` until [`prepare_fft_pipelines`] has queued them.
+    pub fn get(&self, format: &FftWorkspaceFormat) -> Option<&FftFormatPipelines> {
+        self.formats.get(format)
+    }
 
-        let radix2_dit = pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
-            label: Some("fft_radix2_dit".into()),
-            layout: vec![layouts.common.clone()],
-            push_constant_ranges: vec![push_constant_range_20.clone()],
-            shader: fft.clone(),
-            shader_defs: base_shader_defs.clone(),
-            entry_point: Some("fft_radix2_dit".into()),
-            zero_initialize_workgroup_memory: false,
-        });
+    /// Line kernels matching the workspace format of `settings`.
+    pub fn line_kernels(&self, settings: &FftSettings) -> Option<&FftLinePipelines> {
+        match self.get(&self.format(settings))? {
+            FftFormatPipelines::Lines(lines) => Some(lines),
+            FftFormatPipelines::Volume(_) => None,
+        }
+    }
 
-        let fft_copy = pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
-            label: Some("fft_copy_buffer".into()),
-            layout: vec![layouts.common.clone()],
-            push_constant_ranges: vec![push_constant_range_20],
-            shader: fft.clone(),
-            shader_defs: base_shader_defs.clone(),
-            entry_point: Some("fft_copy_buffer".into()),
-            zero_initialize_workgroup_memory: false,
-        });
+    /// Radix-2 kernels for a volume with the extracted `settings`.
+    pub fn volume_kernels(&self, settings: &FftSettings) -> Option<&FftVolumePipelines> {
+        match self.get(&self.format(settings))? {
+            FftFormatPipelines::Volume(volume) => Some(volume),
+            FftFormatPipelines::Lines(_) => None,
+        }
+    }
 
-        let inverse_br_horizontal =
-            pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
-                label: Some("ifft_br_horizontal".into()),
-                layout: vec![layouts.common.clone()],
-                push_constant_ranges: vec![],
-                shader: ifft.clone(),
-                shader_defs: base_shader_defs.clone(),
-                entry_point: Some("ifft_br_horizontal".into()),
-                zero_initialize_workgroup_memory: false,
-            });
+    /// Uniform slots that replace push constants on devices without them.
+    pub(crate) fn constant_slots(&self) -> Option<&FftConstantSlots> {
+        self.constant_slots.as_ref()
+    }
 
-        let inverse_br_vertical =
+    /// Queues the kernels of `format` unless they already exist.
+    fn queue(&mut self, pipeline_cache: &PipelineCache, format: FftWorkspaceFormat) {
+        if self.formats.contains_key(&format) {
+            return;
+        }
+        let layout = format.layout();
+        let mut shader_defs = format.shader_defs();
+        let (kernel_layout, push_constant_ranges) = if self.constant_slots.is_some() {
+            shader_defs.push("FFT_UNIFORM_CONSTANTS".into());
+            (vec![layout, FftConstantSlots::layout()], vec![])
+        } else {
+            let range = PushConstantRange {
+                stages: ShaderStages::COMPUTE,
+                range: 0..FftConstantSlots::CONSTANTS_BYTES as u32,
+            };
+            (vec![layout], vec![range])
+        };
+        let kernel = |label: String, entry_point: &'static str, shader_defs: Vec<ShaderDefVal>| {
             pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
-                label: Some("ifft_br_vertical".into()),
-                layout: vec![layouts.common.clone()],
-                push_constant_ranges: vec![],
-                shader: ifft.clone(),
-                shader_defs: base_shader_defs,
-                entry_point: Some("ifft_br_vertical".into()),
+                label: Some(label.into()),
+                layout: kernel_layout.clone(),
+                push_constant_ranges: push_constant_ranges.clone(),
+                shader: self.fft_shader.clone(),
+                shader_defs,
+                entry_point: Some(entry_point.into()),
                 zero_initialize_workgroup_memory: false,
+            })
+        };
+        let prefix = match (format.volume, format.layered) {
+            (true, _) => "fft_volume_",
+            (false, true) => "fft_layers_",
+            (false, false) => "",
+        };
+        let line_kernel = |entry_point: &'static str| {
+            kernel(
+                format!("{prefix}{entry_point}"),
+                entry_point,
+                shader_defs.clone(),
+            )
+        };
+
+        let pipelines = if format.volume {
+            FftFormatPipelines::Volume(FftVolumePipelines {
+                bit_reverse: line_kernel("fft_bit_reverse"),
+                radix2_dit: line_kernel("fft_radix2_dit"),
+                fft_copy: line_kernel("fft_copy_buffer"),
+            })
+        } else {
+            let shared_line = (self.shared_line_max_len > 0).then(|| {
+                let mut shader_defs = shader_defs.clone();
+                shader_defs.push(ShaderDefVal::UInt(
+                    "FFT_SHARED_LINE_LEN".into(),
+                    self.shared_line_max_len,
+                ));
+                kernel("fft_shared_line".into(), "fft_shared_line", shader_defs)
             });
+            let resolve_layout = format.resolve_layout();
+            let resolve_kernel = |label: &'static str, entry_point: &'static str| {
+                pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
+                    label: Some(label.into()),
+                    layout: vec![resolve_layout.clone()],
+                    push_constant_ranges: vec![],
+                    shader: super::shaders::RESOLVE_OUTPUTS.clone(),
+                    shader_defs: shader_defs.clone(),
+                    entry_point: Some(entry_point.into()),
+                    zero_initialize_workgroup_memory: false,
+                })
+            };
+            FftFormatPipelines::Lines(FftLinePipelines {
+                bit_reverse: line_kernel("fft_bit_reverse"),
+                radix2_dit: line_kernel("fft_radix2_dit"),
+                stockham_radix2: line_kernel("fft_stockham_radix2"),
+                stockham_radix4: line_kernel("fft_stockham_radix4"),
+                fft_copy: line_kernel("fft_copy_buffer"),
+                shared_line,
+                bluestein_premultiply: line_kernel("fft_bluestein_premultiply"),
+                bluestein_multiply: line_kernel("fft_bluestein_multiply"),
+                bluestein_postmultiply: line_kernel("fft_bluestein_postmultiply"),
+                resolve_spectrum: resolve_kernel(
+                    "fft_resolve_spectrum_pipeline",
+                    "resolve_power_spectrum_from_c",
+                ),
+                resolve_spatial: resolve_kernel(
+                    "fft_resolve_spatial_pipeline",
+                    "resolve_spatial_from_b",
+                ),
+            })
+        };
+        self.formats.insert(format, pipelines);
+    }
+}
 
-        let resolve_spectrum = pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
-            label: Some("fft_resolve_spectrum_pipeline".into()),
-            layout: vec![layouts.resolve_outputs.clone()],
-            push_constant_ranges: vec![],
-            shader: resolve_shader.clone(),
-            shader_defs: vec![],
-            entry_point: Some("resolve_power_spectrum_from_c".into()),
-            zero_initialize_workgroup_memory: false,
-        });
+/// Workgroup size of `fft_shared_line`.
+const SHARED_LINE_INVOCATIONS: u32 = 256;
+
+/// Bytes per `c32_n` sample in workgroup memory or the Bluestein workspace (two `vec4<f32>`).
+const SAMPLE_BYTES: u32 = 32;
+
+/// Longest power-of-two line `fft_shared_line` can keep in workgroup memory under `limits`,
+/// capped by the twiddle table. Returns `0` when the device cannot run the kernel at all.
+pub fn shared_line_capacity(limits: &WgpuLimits) -> u32 {
+    if limits.max_compute_invocations_per_workgroup < SHARED_LINE_INVOCATIONS
+        || limits.max_compute_workgroup_size_x < SHARED_LINE_INVOCATIONS
+    {
+        return 0;
+    }
+    let samples = limits.max_compute_workgroup_storage_size / SAMPLE_BYTES;
+    if samples < 2 {
+        return 0;
+    }
+    (1u32 << samples.ilog2()).min(FFT_MAX_TRANSFORM_LEN)
+}
 
-        let resolve_spatial = pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
-            label: Some("fft_resolve_spatial_pipeline".into()),
-            layout: vec![layouts.resolve_outputs.clone()],
-            push_constant_ranges: vec![],
-            shader: resolve_shader,
-            shader_defs: vec![],
-            entry_point: Some("resolve_spatial_from_b".into()),
-            zero_initialize_workgroup_memory: false,
+impl FromWorld for FftPipelines {
+    fn from_world(world: &mut World) -> Self {
+        let shared_line_max_len = shared_line_capacity(&world.resource::<RenderDevice>().limits());
+        let texel_support = *world.resource::<FftTexelSupport>();
+        let backend = *world.resource::<FftBackend>();
+        let fft_shader = world.resource::<AssetServer>().load("fft.wgsl");
+        let render_device = world.resource::<RenderDevice>();
+        let pipeline_cache = world.resource::<PipelineCache>();
+        let constant_slots = (!render_device
+            .features()
+            .contains(WgpuFeatures::PUSH_CONSTANTS))
+        .then(|| {
+            FftConstantSlots::new(
+                render_device,
+                pipeline_cache,
+                world.resource::<RenderQueue>(),
+                FftConstantSlots::INITIAL_CAPACITY,
+            )
         });
+        let mut pipelines = Self {
+            formats: HashMap::default(),
+            shared_line_max_len,
+            texel_support,
+            backend,
+            fft_shader,
+            constant_slots,
+        };
+        let format = FftWorkspaceFormat {
+            storage_buffers: backend == FftBackend::StorageBuffers,
+            ..FftWorkspaceFormat::RGBA
+        };
+        pipelines.queue(pipeline_cache, format);
+        pipelines
+    }
+}
+
+/// Queues [`FftPipelines`] for workspace formats no earlier entity used.
+pub(crate) fn prepare_fft_pipelines(
+    mut pipelines: ResMut<FftPipelines>,
+    pipeline_cache: Res<PipelineCache>,
+    query: Query<&FftSettings>,
+) {
+    for settings in &query {
+        let format = pipelines.format(settings);
+        pipelines.queue(&pipeline_cache, format);
+    }
+}
+
+/// Per-dispatch FFT constants in a dynamic-offset uniform buffer, used in place of push
+/// constants when the device lacks [`WgpuFeatures::PUSH_CONSTANTS`], as under WebGPU. Every
+/// kernel dispatch of a frame writes its constants into its own slot, so the queued writes all
+/// land before the frame's command buffers run.
+pub(crate) struct FftConstantSlots {
+    buffer: Buffer,
+    bind_group: BindGroup,
+    /// Slot spacing: the device's `min_uniform_buffer_offset_alignment`.
+    stride: u32,
+    capacity: u32,
+    /// Slots handed out this frame. May pass `capacity`; the next frame grows the buffer.
+    used: AtomicU32,
+    queue: RenderQueue,
+}
+
+impl FftConstantSlots {
+    /// Size of `FftPushConstants` in `fft.wgsl`.
+    pub(crate) const CONSTANTS_BYTES: u64 = 28;
+    const INITIAL_CAPACITY: u32 = 256;
+
+    /// Group 1 of the FFT kernels when they read their constants from a uniform.
+    fn layout() -> BindGroupLayoutDescriptor {
+        BindGroupLayoutDescriptor::new(
+            "fft_constants_bind_group_layout",
+            &BindGroupLayoutEntries::single(
+                ShaderStages::COMPUTE,
+                uniform_buffer_sized(true, NonZeroU64::new(Self::CONSTANTS_BYTES)),
+            ),
+        )
+    }
 
+    fn new(
+        render_device: &RenderDevice,
+        pipeline_cache: &PipelineCache,
+        queue: &RenderQueue,
+        capacity: u32,
+    ) -> Self {
+        let stride = render_device.limits().min_uniform_buffer_offset_alignment;
+        let buffer = render_device.create_buffer(&BufferDescriptor {
+            label: Some("fft_constant_slots"),
+            size: u64::from(stride) * u64::from(capacity),
+            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let bind_group = render_device.create_bind_group(
+            "fft_constants_bind_group",
+            &pipeline_cache.get_bind_group_layout(&Self::layout()),
+            &BindGroupEntries::single(BufferBinding {
+                buffer: &buffer,
+                offset: 0,
+                size: NonZeroU64::new(Self::CONSTANTS_BYTES),
+            }),
+        );
         Self {
-            forward_br_horizontal,
-            forward_br_vertical,
-            radix2_dit,
-            fft_copy,
-            inverse_br_horizontal,
-            inverse_br_vertical,
-            resolve_spectrum,
-            resolve_spatial,
+            buffer,
+            bind_group,
+            stride,
+            capacity,
+            used: AtomicU32::new(0),
+            queue: queue.clone(),
+        }
+    }
+
+    /// Bind group to set at group 1 with the offset from [`Self::push`].
+    pub(crate) fn bind_group(&self) -> &BindGroup {
+        &self.bind_group
+    }
+
+    /// Writes `constants` into the next free slot and returns its dynamic offset, or `None` once
+    /// this frame's slots are used up. The next frame grows the buffer.
+    pub(crate) fn push(&self, constants: &[u8]) -> Option<u32> {
+        let slot = self.used.fetch_add(1, Ordering::Relaxed);
+        if slot >= self.capacity {
+            once!(warn!(
+                "FFT constant slots overflowed ({} per frame); growing the buffer and running the \
+                 affected entities again next frame",
+                self.capacity
+            ));
+            return None;
         }
+        let offset = slot * self.stride;
+        self.queue
+            .write_buffer(&self.buffer, u64::from(offset), constants);
+        Some(offset)
+    }
+}
+
+/// Releases last frame's [`FftConstantSlots`] and grows the buffer when a frame needed more
+/// slots than it had.
+pub(crate) fn prepare_fft_constant_slots(
+    mut pipelines: ResMut<FftPipelines>,
+    render_device: Res<RenderDevice>,
+    pipeline_cache: Res<PipelineCache>,
+) {
+    let Some(slots) = pipelines.constant_slots.as_mut() else {
+        return;
+    };
+    let used = std::mem::take(slots.used.get_mut());
+    if used > slots.capacity {
+        let capacity = used.next_power_of_two();
+        *slots = FftConstantSlots::new(&render_device, &pipeline_cache, &slots.queue, capacity);
     }
 }
 
@@ -200,7 +702,18 @@ impl FromWorld for FftPipelines {
 /// Resolve passes fill [`Self::spatial_output`] (after inverse FFT from **B**) and
 /// [`Self::power_spectrum`] (after the spectrum stage from **C**). The
 /// `buffer_*` handles are ping-pong storage used inside the FFT graph and are mainly interesting
-/// when you author custom compute that plugs into those bindings.
+/// when you author custom compute that plugs into those bindings. For
+/// [`FftDimension::ThreeD`] sources the buffers are 3D and the two resolved images stay blank.
+/// Sources with several [`FftSource::layers`] get `D2Array` buffers and resolved images with one
+/// layer per grid.
+/// For [`super::FftPrecision::Half`] sources each `_re` handle is a packed `*32Uint` texture and
+/// the matching `_im` handle points at the same image. Workspace texels follow
+/// [`FftWorkspaceFormat::texture_format`], so sources with fewer [`FftSource::channels`] may use
+/// one- or two-component formats. Under [`FftBackend::StorageBuffers`] the `buffer_*` handles
+/// are default handles and the workspace lives in [`FftStorageBuffers`].
+///
+/// [`prepare_fft_textures`] replaces every image when the [`FftSource`] grid or workspace format
+/// changes.
 #[derive(Component, ExtractComponent, Clone)]
 pub struct FftTextures {
     pub buffer_a_re: Handle<Image>,
@@ -215,43 +728,118 @@ pub struct FftTextures {
     pub spatial_output: Handle<Image>,
     /// Log-magnitude spectrum with the DC term moved to the middle for easier viewing.
     pub power_spectrum: Handle<Image>,
+    /// Format the `buffer_*` images were allocated with.
+    format: FftWorkspaceFormat,
+    /// Workspace extent the `buffer_*` images were allocated with.
+    extent: Extent3d,
+    /// Signal size [`Self::spatial_output`] was allocated with.
+    signal: UVec2,
 }
 
+/// Allocates [`FftTextures`] for new [`FftSource`] entities, and again for entities whose
+/// [`FftWorkspaceFormat`], workspace extent or signal size no longer matches the allocated
+/// images, for example after an edit to [`FftSource::channels`] or [`FftSource::precision`].
 pub fn prepare_fft_textures(
     mut commands: Commands,
     mut images: ResMut<Assets<Image>>,
-    query: Query<(Entity, &FftSource), Without<FftTextures>>,
+    texel_support: Res<FftTexelSupport>,
+    backend: Res<FftBackend>,
+    query: Query<(Entity, &FftSource, Option<&FftTextures>)>,
 ) {
-    for (entity, source) in &query {
-        let mut image = Image::new_fill(
-            Extent3d {
-                width: source.size.x,
-                height: source.size.y,
-                depth_or_array_layers: 1,
-            },
-            TextureDimension::D2,
-            &[0; 16],
-            TextureFormat::Rgba32Float,
-            RenderAssetUsages::default(),
-        );
+    for (entity, source, existing) in &query {
+        let format = FftWorkspaceFormat::from_source(source, &texel_support, *backend);
+        let extent = fft_workspace_extent(source);
+        if existing.is_some_and(|textures| {
+            textures.format == format && textures.extent == extent && textures.signal == source.size
+        }) {
+            continue;
+        }
+        let texel = vec![0; format.texel_bytes()];
+        let layers = if format.layered { source.layers } else { 1 };
+        let image = |size: UVec2, texture_format: TextureFormat, texel: &[u8]| {
+            let mut image = Image::new_fill(
+                Extent3d {
+                    width: size.x,
+                    height: size.y,
+                    depth_or_array_layers: layers,
+                },
+                TextureDimension::D2,
+                texel,
+                texture_format,
+                RenderAssetUsages::default(),
+            );
+            image.texture_descriptor.usage = TextureUsages::STORAGE_BINDING
+                | TextureUsages::TEXTURE_BINDING
+                | TextureUsages::COPY_DST
+                | TextureUsages::COPY_SRC
+                | TextureUsages::RENDER_ATTACHMENT;
+            if format.layered {
+                image.texture_view_descriptor = Some(TextureViewDescriptor {
+                    dimension: Some(TextureViewDimension::D2Array),
+                    ..default()
+                });
+            }
+            image
+        };
+        let workspace_size = source.size + source.padding;
 
-        image.texture_descriptor.usage = TextureUsages::STORAGE_BINDING
-            | TextureUsages::TEXTURE_BINDING
-            | TextureUsages::COPY_DST
-            | TextureUsages::COPY_SRC
-            | TextureUsages::RENDER_ATTACHMENT;
+        let workspace = || {
+            if !format.volume {
+                return image(workspace_size, format.texture_format(), &texel);
+            }
+            let mut volume = Image::new_fill(
+                fft_workspace_extent(source),
+                TextureDimension::D3,
+                &texel,
+                format.texture_format(),
+                RenderAssetUsages::default(),
+            );
+            volume.texture_descriptor.usage = TextureUsages::STORAGE_BINDING
+                | TextureUsages::TEXTURE_BINDING
+                | TextureUsages::COPY_DST
+                | TextureUsages::COPY_SRC;
+            volume
+        };
 
-        let buffer_a_re = images.add(image.clone());
-        let buffer_a_im = images.add(image.clone());
-        let buffer_b_re = images.add(image.clone());
-        let buffer_b_im = images.add(image.clone());
-        let buffer_c_re = images.add(image.clone());
-        let buffer_c_im = images.add(image.clone());
-        let buffer_d_re = images.add(image.clone());
-        let buffer_d_im = images.add(image.clone());
+        let (buffer_a_re, buffer_a_im, buffer_b_re, buffer_b_im);
+        let (buffer_c_re, buffer_c_im, buffer_d_re, buffer_d_im);
+        if format.storage_buffers {
+            // The workspace lives in render-world `FftStorageBuffers`; no images back it.
+            buffer_a_re = Handle::default();
+            buffer_a_im = Handle::default();
+            buffer_b_re = Handle::default();
+            buffer_b_im = Handle::default();
+            buffer_c_re = Handle::default();
+            buffer_c_im = Handle::default();
+            buffer_d_re = Handle::default();
+            buffer_d_im = Handle::default();
+        } else if format.half {
+            // One packed texture per buffer; the `_im` handles alias it.
+            let workspace = workspace();
+            buffer_a_re = images.add(workspace.clone());
+            buffer_b_re = images.add(workspace.clone());
+            buffer_c_re = images.add(workspace.clone());
+            buffer_d_re = images.add(workspace);
+            buffer_a_im = buffer_a_re.clone();
+            buffer_b_im = buffer_b_re.clone();
+            buffer_c_im = buffer_c_re.clone();
+            buffer_d_im = buffer_d_re.clone();
+        } else {
+            let workspace = workspace();
+            buffer_a_re = images.add(workspace.clone());
+            buffer_a_im = images.add(workspace.clone());
+            buffer_b_re = images.add(workspace.clone());
+            buffer_b_im = images.add(workspace.clone());
+            buffer_c_re = images.add(workspace.clone());
+            buffer_c_im = images.add(workspace.clone());
+            buffer_d_re = images.add(workspace.clone());
+            buffer_d_im = images.add(workspace);
+        }
 
-        let spatial_output = images.add(image.clone());
-        let power_spectrum = images.add(image.clone());
+        // The spatial result is cropped to the signal; the spectrum keeps every padded bin.
+        let spatial_output = images.add(image(source.size, TextureFormat::Rgba32Float, &[0; 16]));
+        let power_spectrum =
+            images.add(image(workspace_size, TextureFormat::Rgba32Float, &[0; 16]));
 
         commands.entity(entity).insert(FftTextures {
             buffer_a_re,
@@ -264,20 +852,52 @@ pub fn prepare_fft_textures(
             buffer_d_im,
             spatial_output,
             power_spectrum,
+            format,
+            extent,
+            signal: source.size,
         });
     }
 }
 
 #[derive(Component)]
 pub struct FftBindGroups {
+    /// Matches [`FftWorkspaceFormat::layout`] for the entity's format, which is
+    /// [`FftBindGroupLayouts::common`] for four-channel f32 sources.
     pub common: BindGroup,
+    /// Dynamic offset of the entity's [`FftSettings`] within the shared uniform buffer, from its
+    /// [`DynamicUniformIndex`].
+    pub settings_offset: u32,
+}
+
+impl FftBindGroups {
+    /// Binds [`Self::common`] at `index` with the entity's settings offset.
+    pub fn set(&self, pass: &mut ComputePass, index: u32) {
+        pass.set_bind_group(index, &self.common, &[self.settings_offset]);
+    }
 }
 
 #[derive(Component)]
 pub(crate) struct FftResolveBindGroups {
     pub group: BindGroup,
+    /// As [`FftBindGroups::settings_offset`].
+    pub settings_offset: u32,
 }
 
+type PrepareFftBindGroupsQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static FftTextures,
+        &'static FftBluesteinBuffers,
+        &'static FftRootsBuffer,
+        &'static FftSettings,
+        &'static DynamicUniformIndex<FftSettings>,
+        Option<&'static FftStorageBuffers>,
+    ),
+    Without<FftSkipStockPipeline>,
+>;
+
 #[allow(clippy::too_many_arguments)]
 /// Rebuilds the main FFT bind groups once per frame.
 ///
@@ -288,12 +908,11 @@ pub fn prepare_fft_bind_groups(
     mut commands: Commands,
     render_device: Res<RenderDevice>,
     pipeline_cache: Res<PipelineCache>,
-    layouts: Res<FftBindGroupLayouts>,
+    pipelines: Res<FftPipelines>,
     fft_uniforms: Res<ComponentUniforms<FftSettings>>,
-    fft_roots_buffer: Res<FftRootsBuffer>,
     globals_buffer: Res<GlobalsBuffer>,
     gpu_images: Res<RenderAssets<GpuImage>>,
-    query: Query<(Entity, &FftTextures, &FftSettings), Without<FftSkipStockPipeline>>,
+    query: PrepareFftBindGroupsQuery,
 ) {
     // `ComponentUniforms<FftSettings>` exists only when at least one entity has an extracted
     // `FftSettings` (from `FftSource`). Apps with no FFT entities hit this path every frame; that
@@ -303,50 +922,102 @@ pub fn prepare_fft_bind_groups(
         return;
     };
 
-    let Some(roots_binding) = fft_roots_buffer.buffer.binding() else {
-        trace!("Skipping entity FftBindGroups: FftRootsBuffer not ready for binding");
-        return;
-    };
-
     let Some(globals_binding) = globals_buffer.buffer.binding() else {
         trace!("Skipping entity FftBindGroups: GlobalsBuffer not ready for binding");
         return;
     };
 
-    for (entity, textures, _settings) in &query {
-        let Some(buffer_a_re) = gpu_images.get(&textures.buffer_a_re) else {
+    for (entity, textures, bluestein, roots, settings, settings_index, storage) in &query {
+        let settings_offset = settings_index.index();
+        let Some(roots_binding) = roots.buffer.binding() else {
             continue;
         };
-        let Some(buffer_a_im) = gpu_images.get(&textures.buffer_a_im) else {
-            continue;
-        };
-        let Some(buffer_b_re) = gpu_images.get(&textures.buffer_b_re) else {
-            continue;
-        };
-        let Some(buffer_b_im) = gpu_images.get(&textures.buffer_b_im) else {
-            continue;
-        };
-        let Some(buffer_c_re) = gpu_images.get(&textures.buffer_c_re) else {
-            continue;
-        };
-        let Some(buffer_c_im) = gpu_images.get(&textures.buffer_c_im) else {
+        let format = pipelines.format(settings);
+        let layout = pipeline_cache.get_bind_group_layout(&format.layout());
+        if format.storage_buffers {
+            let Some(storage) = storage else {
+                continue;
+            };
+            let [a, b, c, d] = &storage.buffers;
+            let common = render_device.create_bind_group(
+                "fft_storage_buffers_bind_group",
+                &layout,
+                &BindGroupEntries::with_indices((
+                    (0, globals_binding.clone()),
+                    (1, settings_binding.clone()),
+                    (2, roots_binding),
+                    (3, a.as_entire_binding()),
+                    (5, b.as_entire_binding()),
+                    (7, c.as_entire_binding()),
+                    (9, d.as_entire_binding()),
+                    (11, bluestein.kernel_binding()),
+                    (12, bluestein.workspace.as_entire_binding()),
+                )),
+            );
+            commands.entity(entity).insert(FftBindGroups {
+                common,
+                settings_offset,
+            });
             continue;
-        };
-        let Some(buffer_d_re) = gpu_images.get(&textures.buffer_d_re) else {
+        }
+        // Textures reallocated for a new grid or format become ready a frame or more later;
+        // until then the entity has no bind groups rather than ones that disagree with the
+        // pipeline it now selects.
+        let workspace = |handle| workspace_image(&gpu_images, handle, format, settings);
+        let (
+            Some(buffer_a_re),
+            Some(buffer_a_im),
+            Some(buffer_b_re),
+            Some(buffer_b_im),
+            Some(buffer_c_re),
+            Some(buffer_c_im),
+            Some(buffer_d_re),
+            Some(buffer_d_im),
+        ) = (
+            workspace(&textures.buffer_a_re),
+            workspace(&textures.buffer_a_im),
+            workspace(&textures.buffer_b_re),
+            workspace(&textures.buffer_b_im),
+            workspace(&textures.buffer_c_re),
+            workspace(&textures.buffer_c_im),
+            workspace(&textures.buffer_d_re),
+            workspace(&textures.buffer_d_im),
+        )
+        else {
+            commands.entity(entity).remove::<FftBindGroups>();
             continue;
         };
-        let Some(buffer_d_im) = gpu_images.get(&textures.buffer_d_im) else {
+
+        if format.half {
+            let common = render_device.create_bind_group(
+                "fft_half_bind_group",
+                &layout,
+                &BindGroupEntries::with_indices((
+                    (0, globals_binding.clone()),
+                    (1, settings_binding.clone()),
+                    (2, roots_binding),
+                    (3, &buffer_a_re.texture_view),
+                    (5, &buffer_b_re.texture_view),
+                    (7, &buffer_c_re.texture_view),
+                    (9, &buffer_d_re.texture_view),
+                    (11, bluestein.kernel_binding()),
+                    (12, bluestein.workspace.as_entire_binding()),
+                )),
+            );
+            commands.entity(entity).insert(FftBindGroups {
+                common,
+                settings_offset,
+            });
             continue;
-        };
+        }
 
-        let common_layout = pipeline_cache.get_bind_group_layout(&layouts.common);
         let common = render_device.create_bind_group(
             "fft_bind_group",
-            &common_layout,
+            &layout,
             &BindGroupEntries::sequential((
                 globals_binding.clone(),
                 settings_binding.clone(),
-                roots_binding.clone(),
+                roots_binding,
                 &buffer_a_re.texture_view,
                 &buffer_a_im.texture_view,
                 &buffer_b_re.texture_view,
@@ -355,15 +1026,45 @@ pub fn prepare_fft_bind_groups(
                 &buffer_c_im.texture_view,
                 &buffer_d_re.texture_view,
                 &buffer_d_im.texture_view,
+                bluestein.kernel_binding(),
+                bluestein.workspace.as_entire_binding(),
             )),
         );
 
-        commands.entity(entity).insert(FftBindGroups { common });
+        commands.entity(entity).insert(FftBindGroups {
+            common,
+            settings_offset,
+        });
     }
 }
 
-type PrepareFftResolveBindGroupsQuery<'w, 's> =
-    Query<'w, 's, (Entity, &'static FftTextures), With<FftBindGroups>>;
+/// The workspace image behind `handle`, once it is uploaded with the texture format and size
+/// `settings` expect.
+fn workspace_image<'a>(
+    gpu_images: &'a RenderAssets<GpuImage>,
+    handle: &Handle<Image>,
+    format: FftWorkspaceFormat,
+    settings: &FftSettings,
+) -> Option<&'a GpuImage> {
+    gpu_images.get(handle).filter(|image| {
+        image.texture_format == format.texture_format()
+            && image.size.width == settings.size.x
+            && image.size.height == settings.size.y
+    })
+}
+
+type PrepareFftResolveBindGroupsQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static FftTextures,
+        &'static FftSettings,
+        &'static DynamicUniformIndex<FftSettings>,
+        Option<&'static FftStorageBuffers>,
+    ),
+    With<FftBindGroups>,
+>;
 
 #[allow(clippy::too_many_arguments)]
 /// Rebuilds resolve bind groups every frame so `texture_view`s stay aligned with [`prepare_fft_bind_groups`].
@@ -371,7 +1072,7 @@ pub(crate) fn prepare_fft_resolve_bind_groups(
     mut commands: Commands,
     render_device: Res<RenderDevice>,
     pipeline_cache: Res<PipelineCache>,
-    layouts: Res<FftBindGroupLayouts>,
+    pipelines: Res<FftPipelines>,
     fft_uniforms: Res<ComponentUniforms<FftSettings>>,
     gpu_images: Res<RenderAssets<GpuImage>>,
     query: PrepareFftResolveBindGroupsQuery,
@@ -380,103 +1081,503 @@ pub(crate) fn prepare_fft_resolve_bind_groups(
         return;
     };
 
-    for (entity, textures) in &query {
-        let Some(c_re) = gpu_images.get(&textures.buffer_c_re) else {
+    for (entity, textures, settings, settings_index, storage) in &query {
+        let settings_offset = settings_index.index();
+        // The resolve shader reads 2D workspaces; volumes leave their resolved images blank.
+        if settings.is_volume() {
             continue;
-        };
-        let Some(c_im) = gpu_images.get(&textures.buffer_c_im) else {
+        }
+        let Some(power) = gpu_images.get(&textures.power_spectrum) else {
             continue;
         };
-        let Some(b_re) = gpu_images.get(&textures.buffer_b_re) else {
+        let Some(spatial) = gpu_images.get(&textures.spatial_output) else {
             continue;
         };
-        let Some(power) = gpu_images.get(&textures.power_spectrum) else {
+        let format = pipelines.format(settings);
+        let layout = pipeline_cache.get_bind_group_layout(&format.resolve_layout());
+        if format.storage_buffers {
+            let Some(storage) = storage else {
+                continue;
+            };
+            let group = render_device.create_bind_group(
+                "fft_resolve_outputs_storage_buffers_bind_group",
+                &layout,
+                &BindGroupEntries::with_indices((
+                    (0, settings_binding.clone()),
+                    (1, storage.buffers[BUF_C].as_entire_binding()),
+                    (3, storage.buffers[BUF_B].as_entire_binding()),
+                    (4, &power.texture_view),
+                    (5, &spatial.texture_view),
+                )),
+            );
+            commands.entity(entity).insert(FftResolveBindGroups {
+                group,
+                settings_offset,
+            });
             continue;
-        };
-        let Some(spatial) = gpu_images.get(&textures.spatial_output) else {
+        }
+        let workspace = |handle| workspace_image(&gpu_images, handle, format, settings);
+        let (Some(c_re), Some(c_im), Some(b_re)) = (
+            workspace(&textures.buffer_c_re),
+            workspace(&textures.buffer_c_im),
+            workspace(&textures.buffer_b_re),
+        ) else {
+            commands.entity(entity).remove::<FftResolveBindGroups>();
             continue;
         };
 
-        let layout = pipeline_cache.get_bind_group_layout(&layouts.resolve_outputs);
-        let group = render_device.create_bind_group(
-            "fft_resolve_outputs_bind_group",
-            &layout,
-            &BindGroupEntries::sequential((
-                settings_binding.clone(),
-                &c_re.texture_view,
-                &c_im.texture_view,
-                &b_re.texture_view,
-                &power.texture_view,
-                &spatial.texture_view,
-            )),
-        );
+        let group = if format.half {
+            render_device.create_bind_group(
+                "fft_resolve_outputs_half_bind_group",
+                &layout,
+                &BindGroupEntries::with_indices((
+                    (0, settings_binding.clone()),
+                    (1, &c_re.texture_view),
+                    (3, &b_re.texture_view),
+                    (4, &power.texture_view),
+                    (5, &spatial.texture_view),
+                )),
+            )
+        } else {
+            render_device.create_bind_group(
+                "fft_resolve_outputs_bind_group",
+                &layout,
+                &BindGroupEntries::sequential((
+                    settings_binding.clone(),
+                    &c_re.texture_view,
+                    &c_im.texture_view,
+                    &b_re.texture_view,
+                    &power.texture_view,
+                    &spatial.texture_view,
+                )),
+            )
+        };
 
-        commands
-            .entity(entity)
-            .insert(FftResolveBindGroups { group });
+        commands.entity(entity).insert(FftResolveBindGroups {
+            group,
+            settings_offset,
+        });
     }
 }
 
-#[derive(Resource)]
+/// Per-entity twiddle table, bound at binding 2 of [`FftBindGroupLayouts::common`] (and
+/// [`FftBindGroupLayouts::volume`]). The stock table is generated at `2^(order + 1)` entries for
+/// the entity's longest axis ([`FftSettings::max_order`]); a custom [`FftRoots`] replaces it.
+#[derive(Component)]
 pub struct FftRootsBuffer {
-    pub buffer: StorageBuffer<FftRoots>,
+    pub buffer: StorageBuffer<Vec<c32>>,
+    /// Order of the generated stock table, `None` while a custom table is bound.
+    stock_order: Option<u32>,
 }
 
-impl FromWorld for FftRootsBuffer {
-    fn from_world(world: &mut World) -> Self {
-        let data = world
-            .query_filtered::<&FftRoots, With<FftSettings>>()
-            .iter(world)
-            .next()
-            .map_or_else(
-                || FftRoots {
-                    roots: [c32::new(0.0, 0.0); 8192],
-                },
-                |roots| *roots,
-            );
+/// Creates [`FftRootsBuffer`] for new entities and refills it only when the longest axis or the
+/// custom [`FftRoots`] change.
+pub(crate) fn prepare_fft_roots_buffer(
+    mut commands: Commands,
+    device: Res<RenderDevice>,
+    queue: Res<RenderQueue>,
+    mut query: Query<(
+        Entity,
+        &FftSettings,
+        Option<&FftRoots>,
+        Option<&mut FftRootsBuffer>,
+    )>,
+) {
+    for (entity, settings, custom, existing) in &mut query {
+        let stock_order = custom.is_none().then(|| settings.max_order());
+        let up_to_date = existing.as_ref().is_some_and(|existing| match custom {
+            Some(custom) => {
+                existing.stock_order.is_none() && *existing.buffer.get() == custom.roots
+            }
+            None => existing.stock_order == stock_order,
+        });
+        if up_to_date {
+            continue;
+        }
 
-        Self {
-            buffer: StorageBuffer::from(data),
+        let roots = match custom {
+            Some(custom) => custom.roots.clone(),
+            None => forward_fft_twiddle_table(settings.max_order()),
+        };
+        match existing {
+            Some(mut existing) => {
+                existing.buffer.set(roots);
+                existing.buffer.write_buffer(&device, &queue);
+                existing.stock_order = stock_order;
+            }
+            None => {
+                let mut buffer = StorageBuffer::from(roots);
+                buffer.set_label(Some("fft_roots"));
+                buffer.write_buffer(&device, &queue);
+                commands.entity(entity).insert(FftRootsBuffer {
+                    buffer,
+                    stock_order,
+                });
+            }
         }
     }
 }
 
-pub(crate) fn prepare_fft_roots_buffer(
+/// Per-entity storage for Bluestein axes, bound after the **A**–**D** textures in
+/// [`FftBindGroupLayouts::common`].
+///
+/// `kernel` holds the chirp spectrum of each non-power-of-two axis (rows, then columns) and
+/// `workspace` two transform-length slots per grid line. Power-of-two grids get one-element
+/// placeholders so every FFT bind group has the same shape.
+#[derive(Component)]
+pub struct FftBluesteinBuffers {
+    pub kernel: StorageBuffer<Vec<c32>>,
+    pub workspace: Buffer,
+    size: UVec2,
+    axes: u32,
+    layers: u32,
+}
+
+impl FftBluesteinBuffers {
+    pub fn kernel_binding(&self) -> BindingResource<'_> {
+        self.kernel
+            .binding()
+            .expect("Bluestein kernel buffer is written on creation")
+    }
+}
+
+/// Bytes the Bluestein workspace may take: WebGPU's default `max_storage_buffer_binding_size`.
+/// Axes with more lines than fit run their Bluestein passes in batches.
+const BLUESTEIN_WORKSPACE_BUDGET: u64 = 128 << 20;
+
+/// Lines across `axis` that one batch of Bluestein passes transforms, so the batch's padded lines
+/// fit [`BLUESTEIN_WORKSPACE_BUDGET`]. Every layer of those lines is transformed with them.
+pub(super) fn bluestein_batch_lines(settings: &FftSettings, axis: usize) -> u32 {
+    let line_bytes = u64::from(settings.fft_size[axis])
+        * u64::from(settings.layers())
+        * 2
+        * u64::from(SAMPLE_BYTES);
+    let lines = settings.size[1 - axis];
+    (BLUESTEIN_WORKSPACE_BUDGET / line_bytes).clamp(1, u64::from(lines)) as u32
+}
+
+/// Bytes of Bluestein workspace for `settings`: two slots of [`SAMPLE_BYTES`] per transform
+/// sample on every line of the larger [`bluestein_batch_lines`] batch, across all layers.
+fn bluestein_workspace_size(settings: &FftSettings) -> u64 {
+    let pass = |axis: usize| {
+        if axis as u32 >= settings.transformed_axes() || settings.size[axis].is_power_of_two() {
+            0
+        } else {
+            u64::from(settings.fft_size[axis])
+                * u64::from(bluestein_batch_lines(settings, axis))
+                * u64::from(settings.layers())
+        }
+    };
+    (pass(0).max(pass(1)) * 2 * u64::from(SAMPLE_BYTES)).max(u64::from(SAMPLE_BYTES))
+}
+
+/// Creates [`FftBluesteinBuffers`] for new entities and rebuilds them when the grid size, the
+/// layer count or [`super::FftDimension`] changes.
+pub(crate) fn prepare_fft_bluestein_buffers(
+    mut commands: Commands,
     device: Res<RenderDevice>,
     queue: Res<RenderQueue>,
-    query: Query<(&FftRoots, &FftSettings), With<FftSettings>>,
-    mut fft_roots_buffer: ResMut<FftRootsBuffer>,
+    query: Query<(Entity, &FftSettings, Option<&FftBluesteinBuffers>)>,
 ) {
-    // One shared GPU storage buffer backs every FFT bind group. All entities must use the same
-    // twiddle table (typical when every `FftSource` shares the forward layout for its `orders`).
-    let Ok((roots, _)) = query.single() else {
-        return;
+    for (entity, settings, existing) in &query {
+        if existing.is_some_and(|buffers| {
+            buffers.size == settings.size
+                && buffers.axes == settings.transformed_axes()
+                && buffers.layers == settings.layers()
+        }) {
+            continue;
+        }
+        let workspace_size = bluestein_workspace_size(settings);
+        if workspace_size > u64::from(device.limits().max_storage_buffer_binding_size) {
+            once!(error!(
+                "FFT grid {} needs a {workspace_size}-byte Bluestein workspace, more than this device can bind",
+                settings.size
+            ));
+            commands.entity(entity).remove::<FftBluesteinBuffers>();
+            continue;
+        }
+
+        let mut kernel = bluestein_kernel_spectrum(settings.size.x, settings.fft_size.x);
+        if settings.transformed_axes() > 1 {
+            kernel.extend(bluestein_kernel_spectrum(
+                settings.size.y,
+                settings.fft_size.y,
+            ));
+        }
+        if kernel.is_empty() {
+            kernel.push(c32::new(0.0, 0.0));
+        }
+        let mut kernel = StorageBuffer::from(kernel);
+        kernel.set_label(Some("fft_bluestein_kernel"));
+        kernel.write_buffer(&device, &queue);
+
+        let workspace = device.create_buffer(&BufferDescriptor {
+            label: Some("fft_bluestein_workspace"),
+            size: workspace_size,
+            usage: BufferUsages::STORAGE,
+            mapped_at_creation: false,
+        });
+
+        commands.entity(entity).insert(FftBluesteinBuffers {
+            kernel,
+            workspace,
+            size: settings.size,
+            axes: settings.transformed_axes(),
+            layers: settings.layers(),
+        });
+    }
+}
+
+/// Indices of **A**, **B**, **C** and **D** in [`FftStorageBuffers::buffers`], matching the buffer
+/// ids the kernels take.
+const BUF_A: usize = 0;
+const BUF_B: usize = 1;
+const BUF_C: usize = 2;
+
+/// Workspace buffers **A**–**D** of an entity under [`FftBackend::StorageBuffers`], in place of
+/// the `buffer_*` images on [`FftTextures`]. Each holds two `vec4<f32>` (real, then imaginary)
+/// per workspace sample in row-major order.
+#[derive(Component)]
+pub struct FftStorageBuffers {
+    pub buffers: [Buffer; 4],
+    size: UVec2,
+    depth: u32,
+}
+
+/// Input samples for a storage-buffer workspace, laid out like [`FftStorageBuffers`] and written
+/// by [`copy_input_textures_to_fft_buffers`] for upload on the render world. Each one is uploaded
+/// once: both worlds remove it after the frame that carries it.
+#[derive(Component, ExtractComponent, Clone)]
+pub struct FftStorageUpload {
+    /// Index of the target in [`FftStorageBuffers::buffers`]: **A** for spatial inputs, **C**
+    /// for spectra.
+    pub buffer: usize,
+    pub bytes: Arc<[u8]>,
+}
+
+/// Creates [`FftStorageBuffers`] for storage-buffer entities, rebuilds them when the workspace
+/// extent changes, and writes any [`FftStorageUpload`] into its target, consuming it.
+pub(crate) fn prepare_fft_storage_buffers(
+    mut commands: Commands,
+    device: Res<RenderDevice>,
+    queue: Res<RenderQueue>,
+    pipelines: Res<FftPipelines>,
+    query: Query<(
+        Entity,
+        &FftSettings,
+        Option<&FftStorageBuffers>,
+        Option<&FftStorageUpload>,
+    )>,
+) {
+    for (entity, settings, existing, upload) in &query {
+        if !pipelines.format(settings).storage_buffers {
+            continue;
+        }
+        let samples = u64::from(settings.size.x) * u64::from(settings.size.y);
+        let size = samples * u64::from(settings.depth) * u64::from(SAMPLE_BYTES);
+        let created;
+        let buffers = match existing {
+            Some(existing)
+                if existing.size == settings.size && existing.depth == settings.depth =>
+            {
+                &existing.buffers
+            }
+            _ => {
+                if size > u64::from(device.limits().max_storage_buffer_binding_size) {
+                    once!(error!(
+                        "FFT workspace {} needs {size}-byte storage buffers, more than this device can bind",
+                        settings.size
+                    ));
+                    commands.entity(entity).remove::<FftStorageBuffers>();
+                    continue;
+                }
+                let buffer = |label: &'static str| {
+                    device.create_buffer(&BufferDescriptor {
+                        label: Some(label),
+                        size,
+                        usage: BufferUsages::STORAGE | BufferUsages::COPY_DST,
+                        mapped_at_creation: false,
+                    })
+                };
+                created = [
+                    buffer("fft_buffer_a"),
+                    buffer("fft_buffer_b"),
+                    buffer("fft_buffer_c"),
+                    buffer("fft_buffer_d"),
+                ];
+                commands.entity(entity).insert(FftStorageBuffers {
+                    buffers: created.clone(),
+                    size: settings.size,
+                    depth: settings.depth,
+                });
+                &created
+            }
+        };
+        if let Some(upload) = upload {
+            if upload.bytes.len() as u64 == size {
+                queue.write_buffer(&buffers[upload.buffer], 0, &upload.bytes);
+            }
+            commands.entity(entity).remove::<FftStorageUpload>();
+        }
+    }
+}
+
+/// Extent an [`FftInputTexture`] must have: the signal without [`FftSource::padding`], with
+/// one slice per volume depth or [`FftSource::layers`].
+fn fft_input_extent(source: &FftSource) -> Extent3d {
+    Extent3d {
+        width: source.size.x,
+        height: source.size.y,
+        depth_or_array_layers: if source.dimension == FftDimension::ThreeD {
+            source.depth
+        } else {
+            source.layers.max(1)
+        },
+    }
+}
+
+/// Extent of the workspace buffers: the input plus its zero border.
+fn fft_workspace_extent(source: &FftSource) -> Extent3d {
+    Extent3d {
+        width: source.size.x + source.padding.x,
+        height: source.size.y + source.padding.y,
+        ..fft_input_extent(source)
+    }
+}
+
+/// Packs `Rgba32Float` real and optional imaginary texels into the `*32Uint` layout of
+/// [`super::FftPrecision::Half`], keeping the first `texel_width` channels of each texel: each
+/// channel holds `pack2x16float(re, im)`, real in the low half. Returns `None` when the two inputs
+/// differ in length.
+pub(super) fn pack_half_texels(
+    re: &[u8],
+    im: Option<&[u8]>,
+    texel_width: usize,
+) -> Option<Vec<u8>> {
+    if im.is_some_and(|im| im.len() != re.len()) {
+        return None;
+    }
+    let read = |bytes: &[u8], i: usize| {
+        f32::from_le_bytes([bytes[i], bytes[i + 1], bytes[i + 2], bytes[i + 3]])
     };
+    let mut packed = Vec::with_capacity(re.len() / 16 * texel_width * 4);
+    for texel in (0..re.len() - re.len() % 16).step_by(16) {
+        for i in (texel..texel + texel_width * 4).step_by(4) {
+            let re = half::f16::from_f32(read(re, i)).to_bits() as u32;
+            let im = im.map_or(0, |im| half::f16::from_f32(read(im, i)).to_bits() as u32);
+            packed.extend_from_slice(&(re | (im << 16)).to_le_bytes());
+        }
+    }
+    Some(packed)
+}
 
-    once!(info!("Prepared FFT roots buffer"));
+/// Interleaves `Rgba32Float` real and optional imaginary texels into the sample layout of
+/// [`FftStorageBuffers`]: each real texel followed by its imaginary texel, or zeros. Returns
+/// `None` when the two inputs differ in length.
+pub(super) fn interleave_texels(re: &[u8], im: Option<&[u8]>) -> Option<Vec<u8>> {
+    if im.is_some_and(|im| im.len() != re.len()) {
+        return None;
+    }
+    let mut samples = Vec::with_capacity(re.len() * 2);
+    for (i, texel) in re.chunks_exact(16).enumerate() {
+        samples.extend_from_slice(texel);
+        match im {
+            Some(im) => samples.extend_from_slice(&im[i * 16..i * 16 + 16]),
+            None => samples.extend_from_slice(&[0; 16]),
+        }
+    }
+    Some(samples)
+}
 
-    fft_roots_buffer.buffer.set(*roots);
-    fft_roots_buffer.buffer.write_buffer(&device, &queue);
+/// Keeps the first `texel_width` channels of each `Rgba32Float` texel, the layout of
+/// `R32Float` and `Rg32Float` workspaces.
+pub(super) fn narrow_texels(bytes: &[u8], texel_width: usize) -> Vec<u8> {
+    bytes
+        .chunks_exact(16)
+        .flat_map(|texel| &texel[..texel_width * 4])
+        .copied()
+        .collect()
+}
+
+/// Copies `src` (laid out over `src_extent`) into the top-left corner of `dst` (laid out over
+/// `dst_extent`) and zeroes the rest. Returns `false` when the lengths disagree with the extents.
+fn copy_into_workspace(
+    src: &[u8],
+    src_extent: Extent3d,
+    dst: &mut [u8],
+    dst_extent: Extent3d,
+) -> bool {
+    let texels = |e: Extent3d| (e.width * e.height * e.depth_or_array_layers) as usize;
+    let texel_bytes = dst.len() / texels(dst_extent).max(1);
+    if src.len() != texels(src_extent) * texel_bytes
+        || dst.len() != texels(dst_extent) * texel_bytes
+    {
+        return false;
+    }
+    if src_extent == dst_extent {
+        dst.clone_from_slice(src);
+        return true;
+    }
+    dst.fill(0);
+    let src_row = src_extent.width as usize * texel_bytes;
+    let dst_row = dst_extent.width as usize * texel_bytes;
+    for z in 0..src_extent.depth_or_array_layers as usize {
+        for y in 0..src_extent.height as usize {
+            let s = (z * src_extent.height as usize + y) * src_row;
+            let d = (z * dst_extent.height as usize + y) * dst_row;
+            dst[d..d + src_row].copy_from_slice(&src[s..s + src_row]);
+        }
+    }
+    true
 }
 
-/// Copies [`FftInputTexture`] data into the FFT working images on the CPU.
+type FftInputCopyQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static FftTextures,
+        &'static FftInputTexture,
+        &'static FftSource,
+        Option<&'static FftTriggerState>,
+        Has<FftStorageUpload>,
+    ),
+>;
+
+/// Copies [`FftInputTexture`] data into the FFT working images on the CPU, in the top-left
+/// corner of the workspace when [`FftSource::padding`] adds a zero border. Inputs stay
+/// `Rgba32Float`; [`super::FftPrecision::Half`] workspaces receive them packed to f16, and
+/// narrow [`FftWorkspaceFormat`]s only their first channels. Under
+/// [`FftBackend::StorageBuffers`] the samples go into an [`FftStorageUpload`] instead.
 ///
-/// When no imaginary texture is attached the corresponding buffer is cleared to zero.
+/// When no imaginary texture is attached the corresponding buffer is cleared to zero. Entities
+/// idle under their [`super::FftTrigger`] this frame are skipped. Uploads extracted last frame,
+/// or left behind by a removed input, are dropped.
 pub(crate) fn copy_input_textures_to_fft_buffers(
+    mut commands: Commands,
     mut images: ResMut<Assets<Image>>,
-    query: Query<(&FftTextures, &FftInputTexture, &FftSource)>,
+    texel_support: Res<FftTexelSupport>,
+    backend: Res<FftBackend>,
+    query: FftInputCopyQuery,
+    orphaned_uploads: Query<Entity, (With<FftStorageUpload>, Without<FftInputTexture>)>,
 ) {
-    for (textures, input, source) in &query {
+    for entity in &orphaned_uploads {
+        commands.entity(entity).remove::<FftStorageUpload>();
+    }
+    for (entity, textures, input, source, trigger, uploaded) in &query {
+        if uploaded {
+            commands.entity(entity).remove::<FftStorageUpload>();
+        }
+        if trigger.is_some_and(FftTriggerState::is_idle) {
+            continue;
+        }
         let Some(src_re) = images.get(&input.real) else {
             continue;
         };
         let src_re_data = src_re.data.clone();
 
-        let expected_extent = Extent3d {
-            width: source.size.x,
-            height: source.size.y,
-            depth_or_array_layers: 1,
-        };
+        let expected_extent = fft_input_extent(source);
+        let workspace_extent = fft_workspace_extent(source);
         if src_re.texture_descriptor.size != expected_extent {
             warn!(
                 "Input real texture size {:?} does not match FFT size {:?}, skipping copy",
@@ -490,37 +1591,96 @@ pub(crate) fn copy_input_textures_to_fft_buffers(
             FftInputDomain::Spatial => (&textures.buffer_a_re, &textures.buffer_a_im),
         };
 
-        if let (Some(dst_re), Some(src_bytes)) =
-            (images.get_mut(dst_re_handle), src_re_data.as_ref())
-            && let Some(dst_bytes) = dst_re.data.as_mut()
-        {
-            if dst_bytes.len() == src_bytes.len() {
-                dst_bytes.clone_from_slice(src_bytes);
-            } else {
+        let format = FftWorkspaceFormat::from_source(source, &texel_support, *backend);
+        let src_im_data = || {
+            input
+                .imag
+                .as_ref()
+                .and_then(|handle| images.get(handle))
+                .and_then(|img| img.data.clone())
+        };
+        if format.storage_buffers {
+            let src_im_data = src_im_data();
+            let interleaved = src_re_data
+                .as_deref()
+                .and_then(|re| interleave_texels(re, src_im_data.as_deref()));
+            let texels = workspace_extent.width
+                * workspace_extent.height
+                * workspace_extent.depth_or_array_layers;
+            let mut bytes = vec![0; texels as usize * SAMPLE_BYTES as usize];
+            match interleaved {
+                Some(interleaved)
+                    if copy_into_workspace(
+                        &interleaved,
+                        expected_extent,
+                        &mut bytes,
+                        workspace_extent,
+                    ) =>
+                {
+                    let buffer = match source.input_domain {
+                        FftInputDomain::Spectrum => BUF_C,
+                        FftInputDomain::Spatial => BUF_A,
+                    };
+                    commands.entity(entity).insert(FftStorageUpload {
+                        buffer,
+                        bytes: bytes.into(),
+                    });
+                }
+                _ => warn!(
+                    "Input data does not match the {}-byte storage-buffer workspace, skipping copy",
+                    bytes.len()
+                ),
+            }
+            continue;
+        }
+        if format.half {
+            let src_im_data = src_im_data();
+            let packed = src_re_data.as_deref().and_then(|re| {
+                pack_half_texels(re, src_im_data.as_deref(), format.texel_width as usize)
+            });
+            if let (Some(dst), Some(packed)) = (images.get_mut(dst_re_handle), packed)
+                && let Some(dst_bytes) = dst.data.as_mut()
+                && !copy_into_workspace(&packed, expected_extent, dst_bytes, workspace_extent)
+            {
                 warn!(
-                    "Input real texture data length {} does not match destination {}",
-                    src_bytes.len(),
+                    "Packed input data length {} does not match destination {}",
+                    packed.len(),
                     dst_bytes.len()
                 );
-                continue;
             }
+            continue;
+        }
+        let narrow = |data: Option<Vec<u8>>| match format.texel_width {
+            4 => data,
+            width => data.map(|bytes| narrow_texels(&bytes, width as usize)),
+        };
+        let src_re_data = narrow(src_re_data);
+
+        if let (Some(dst_re), Some(src_bytes)) =
+            (images.get_mut(dst_re_handle), src_re_data.as_ref())
+            && let Some(dst_bytes) = dst_re.data.as_mut()
+            && !copy_into_workspace(src_bytes, expected_extent, dst_bytes, workspace_extent)
+        {
+            warn!(
+                "Input real texture data length {} does not match destination {}",
+                src_bytes.len(),
+                dst_bytes.len()
+            );
+            continue;
         }
 
         if let Some(imag_handle) = &input.imag {
-            let src_im_data = images.get(imag_handle).and_then(|img| img.data.clone());
+            let src_im_data = narrow(images.get(imag_handle).and_then(|img| img.data.clone()));
             if let (Some(dst_im), Some(src_bytes)) =
                 (images.get_mut(dst_im_handle), src_im_data.as_ref())
                 && let Some(dst_bytes) = dst_im.data.as_mut()
+                && !copy_into_workspace(src_bytes, expected_extent, dst_bytes, workspace_extent)
             {
-                if dst_bytes.len() == src_bytes.len() {
-                    dst_bytes.clone_from_slice(src_bytes);
-                } else {
-                    warn!(
-                        "Input imag texture data length {} does not match destination {}",
-                        src_bytes.len(),
-                        dst_bytes.len()
-                    );
-                }
+                warn!(
+                    "Input imag texture data length {} does not match destination {}",
+                    src_bytes.len(),
+                    dst_bytes.len()
+                );
             }
         } else if let Some(dst_im) = images.get_mut(dst_im_handle)
             && let Some(dst_bytes) = dst_im.data.as_mut()
@@ -529,3 +1689,128 @@ pub(crate) fn copy_input_textures_to_fft_buffers(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        BLUESTEIN_WORKSPACE_BUDGET, FftStorageUpload, FftTexelSupport, FftTextures,
+        FftWorkspaceFormat, bluestein_batch_lines, bluestein_workspace_size,
+        copy_input_textures_to_fft_buffers, prepare_fft_textures,
+    };
+    use crate::fft::{FftBackend, FftSettings, FftSource};
+    use bevy::{
+        ecs::system::RunSystemOnce,
+        platform::sync::Arc,
+        prelude::*,
+        render::render_resource::{
+            BindGroupLayoutDescriptor, BindingType, BufferBindingType, TextureFormat,
+        },
+    };
+
+    fn has_dynamic_uniform(layout: &BindGroupLayoutDescriptor, binding: u32) -> bool {
+        layout.entries.iter().any(|entry| {
+            entry.binding == binding
+                && matches!(
+                    entry.ty,
+                    BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: true,
+                        ..
+                    }
+                )
+        })
+    }
+
+    #[test]
+    fn settings_bindings_take_a_dynamic_offset() {
+        let formats = [
+            FftWorkspaceFormat::RGBA,
+            FftWorkspaceFormat {
+                half: true,
+                ..FftWorkspaceFormat::RGBA
+            },
+            FftWorkspaceFormat {
+                layered: true,
+                ..FftWorkspaceFormat::RGBA
+            },
+            FftWorkspaceFormat {
+                storage_buffers: true,
+                ..FftWorkspaceFormat::RGBA
+            },
+        ];
+        for format in formats {
+            assert!(has_dynamic_uniform(&format.layout(), 1), "{format:?}");
+            assert!(
+                has_dynamic_uniform(&format.resolve_layout(), 0),
+                "{format:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn storage_uploads_without_an_input_are_dropped() {
+        let mut world = World::new();
+        world.init_resource::<Assets<Image>>();
+        world.insert_resource(FftTexelSupport::default());
+        world.insert_resource(FftBackend::StorageBuffers);
+        let entity = world
+            .spawn(FftStorageUpload {
+                buffer: 0,
+                bytes: Arc::from([0u8; 16]),
+            })
+            .id();
+        world
+            .run_system_once(copy_input_textures_to_fft_buffers)
+            .unwrap();
+        assert!(world.get::<FftStorageUpload>(entity).is_none());
+    }
+
+    #[test]
+    fn textures_follow_channel_edits() {
+        let mut world = World::new();
+        world.init_resource::<Assets<Image>>();
+        world.insert_resource(FftTexelSupport {
+            rg32_float: true,
+            ..default()
+        });
+        world.insert_resource(FftBackend::Textures);
+        let entity = world
+            .spawn(FftSource::forward_then_inverse(UVec2::splat(16)))
+            .id();
+        let format = |world: &World| {
+            let textures = world.get::<FftTextures>(entity).unwrap();
+            let images = world.resource::<Assets<Image>>();
+            let image = images.get(&textures.buffer_a_re).unwrap();
+            (
+                textures.buffer_a_re.clone(),
+                image.texture_descriptor.format,
+            )
+        };
+        world.run_system_once(prepare_fft_textures).unwrap();
+        let (rgba, texture_format) = format(&world);
+        assert_eq!(texture_format, TextureFormat::Rgba32Float);
+        world.run_system_once(prepare_fft_textures).unwrap();
+        assert_eq!(format(&world).0, rgba);
+        world.get_mut::<FftSource>(entity).unwrap().channels = 2;
+        world.run_system_once(prepare_fft_textures).unwrap();
+        assert_eq!(format(&world).1, TextureFormat::Rg32Float);
+    }
+
+    #[test]
+    fn full_hd_bluestein_workspace_fits_in_batches() {
+        let settings =
+            FftSettings::from_fft_source(&FftSource::forward_then_inverse(UVec2::new(1920, 1080)));
+        assert_eq!(settings.fft_size, UVec2::splat(4096));
+        // 4096-point lines of two 32-byte slots: 512 of them fill the budget, so the 1080 rows
+        // run in three batches and the 1920 columns in four.
+        assert_eq!(bluestein_batch_lines(&settings, 0), 512);
+        assert_eq!(bluestein_batch_lines(&settings, 1), 512);
+        assert_eq!(bluestein_workspace_size(&settings), 128 << 20);
+        assert!(bluestein_workspace_size(&settings) <= BLUESTEIN_WORKSPACE_BUDGET);
+
+        let small =
+            FftSettings::from_fft_source(&FftSource::forward_then_inverse(UVec2::new(480, 270)));
+        assert_eq!(bluestein_batch_lines(&small, 0), 270);
+        assert_eq!(bluestein_workspace_size(&small), 1024 * 480 * 2 * 32);
+    }
+}
@@ -0,0 +1,316 @@
+//! Decides per frame whether the stock graph transforms an [`FftSource`] entity, and reports
+//! finished runs back to the main world as [`FftRunComplete`].
+//!
+//! The main world picks the frames in [`update_fft_triggers`] and extracts the decision as
+//! [`FftTriggered`]. After the render graph, [`report_fft_runs`] sends each scheduled run back
+//! through [`FftRunReports`], marking runs whose kernels or bind groups were not ready so the
+//! main world schedules them again.
+
+use std::sync::{Arc, Mutex};
+
+use bevy::{
+    asset::AssetEvent,
+    diagnostic::FrameCount,
+    ecs::{query::QueryItem, system::lifetimeless::Read},
+    platform::collections::HashSet,
+    prelude::*,
+    render::{extract_component::ExtractComponent, sync_world::MainEntity},
+};
+
+use super::{FftInputTexture, FftSource, resources::FftBindGroups};
+
+/// When the stock graph transforms an [`FftSource`] entity. Entities without it run every
+/// frame. Idle frames skip the FFT and resolve passes as well as the CPU upload of
+/// [`FftInputTexture`], so buffers and resolved images keep the last run's results.
+#[derive(Component, Clone, Copy, Default, Debug, PartialEq, Eq, Reflect)]
+#[require(FftTriggerState)]
+pub enum FftTrigger {
+    /// Runs every frame, like entities without a trigger, but reports [`FftRunComplete`].
+    #[default]
+    EveryFrame,
+    /// Runs once, as soon as the entity's buffers and kernels are ready. Setting the trigger to
+    /// `Once` again schedules another run.
+    Once,
+    /// Runs once, then again whenever [`FftSource`] or [`FftInputTexture`] changes or one of
+    /// the input images is modified.
+    OnChange,
+    /// Runs only when an [`FftRunRequest`] is inserted.
+    Manual,
+}
+
+/// Requests one more run of an entity with an [`FftTrigger`]. Removed once the run is
+/// scheduled.
+#[derive(Component, Clone, Copy, Default, Debug, Reflect)]
+pub struct FftRunRequest;
+
+/// Triggered on the [`FftSource`] entity once the stock graph has transformed it for an
+/// [`FftTrigger`].
+#[derive(EntityEvent, Clone, Copy, Debug)]
+pub struct FftRunComplete {
+    pub entity: Entity,
+    /// [`FrameCount`] of the frame whose render graph ran the transform.
+    pub frame: u32,
+}
+
+/// Main-world bookkeeping for an [`FftTrigger`].
+#[derive(Component, Clone, Copy, Default, Debug)]
+pub(crate) struct FftTriggerState {
+    /// A run is owed and not yet scheduled.
+    pending: bool,
+    /// A scheduled run has not been reported back yet.
+    in_flight: bool,
+    /// Frame scheduled to run this frame, `None` while idle.
+    run_frame: Option<u32>,
+}
+
+impl FftTriggerState {
+    /// Whether the stock passes skip the entity this frame.
+    pub(crate) fn is_idle(&self) -> bool {
+        self.run_frame.is_none()
+    }
+}
+
+/// Render-world copy of this frame's [`FftTriggerState`] decision.
+#[derive(Component, Clone, Copy, Debug)]
+pub(super) struct FftTriggered {
+    frame: Option<u32>,
+}
+
+impl FftTriggered {
+    /// Whether the stock passes skip an entity carrying `triggered` this frame.
+    pub(super) fn is_idle(triggered: Option<&Self>) -> bool {
+        triggered.is_some_and(|triggered| triggered.frame.is_none())
+    }
+}
+
+impl ExtractComponent for FftTriggered {
+    type QueryData = Read<FftTriggerState>;
+    type QueryFilter = ();
+    type Out = FftTriggered;
+
+    fn extract_component(item: QueryItem<'_, '_, Self::QueryData>) -> Option<Self::Out> {
+        Some(FftTriggered {
+            frame: item.run_frame,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct FftRunReport {
+    entity: Entity,
+    frame: u32,
+    ran: bool,
+}
+
+/// Run reports from the render world, drained by the main world. Both worlds hold a clone.
+#[derive(Resource, Clone, Default)]
+pub(super) struct FftRunReports(Arc<Mutex<Vec<FftRunReport>>>);
+
+/// Render-world entities whose FFT kernels were not ready when the graph reached them.
+#[derive(Resource, Default)]
+pub(super) struct FftSkippedRuns(Mutex<Vec<Entity>>);
+
+impl FftSkippedRuns {
+    pub(super) fn push(&self, entity: Entity) {
+        self.0.lock().unwrap().push(entity);
+    }
+
+    /// Whether an earlier node of this frame's graph already skipped `entity`.
+    pub(super) fn contains(&self, entity: Entity) -> bool {
+        self.0.lock().unwrap().contains(&entity)
+    }
+}
+
+/// Images whose contents changed since `events` was last read.
+pub(super) fn modified_images(
+    events: &mut MessageReader<AssetEvent<Image>>,
+) -> HashSet<AssetId<Image>> {
+    events
+        .read()
+        .filter_map(|event| match event {
+            AssetEvent::Modified { id } | AssetEvent::LoadedWithDependencies { id } => Some(*id),
+            _ => None,
+        })
+        .collect()
+}
+
+type FftTriggerQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        Ref<'static, FftTrigger>,
+        &'static mut FftTriggerState,
+        Ref<'static, FftSource>,
+        Option<Ref<'static, FftInputTexture>>,
+        Has<FftRunRequest>,
+    ),
+>;
+
+/// Applies the reports of earlier frames, then schedules this frame's runs.
+pub(super) fn update_fft_triggers(
+    mut commands: Commands,
+    frame: Res<FrameCount>,
+    reports: Res<FftRunReports>,
+    mut image_events: MessageReader<AssetEvent<Image>>,
+    mut triggers: FftTriggerQuery,
+) {
+    for report in std::mem::take(&mut *reports.0.lock().unwrap()) {
+        let Ok((.., mut state, _, _, _)) = triggers.get_mut(report.entity) else {
+            continue;
+        };
+        state.in_flight = false;
+        if report.ran {
+            commands.trigger(FftRunComplete {
+                entity: report.entity,
+                frame: report.frame,
+            });
+        } else {
+            state.pending = true;
+        }
+    }
+
+    let modified = modified_images(&mut image_events);
+    for (entity, trigger, mut state, source, input, requested) in &mut triggers {
+        let changed = || {
+            source.is_changed()
+                || input.as_ref().is_some_and(|input| {
+                    input.is_changed()
+                        || modified.contains(&input.real.id())
+                        || input
+                            .imag
+                            .as_ref()
+                            .is_some_and(|imag| modified.contains(&imag.id()))
+                })
+        };
+        // Inserting or switching the trigger counts as the first frame of `Once` and `OnChange`.
+        let owed = match *trigger {
+            FftTrigger::EveryFrame => true,
+            FftTrigger::Once => trigger.is_changed(),
+            FftTrigger::OnChange => trigger.is_changed() || changed(),
+            FftTrigger::Manual => false,
+        };
+        if requested {
+            commands.entity(entity).remove::<FftRunRequest>();
+        }
+        state.pending |= owed || requested;
+        let run = *trigger == FftTrigger::EveryFrame || (state.pending && !state.in_flight);
+        if run {
+            state.pending = false;
+            state.in_flight = true;
+        }
+        state.run_frame = run.then_some(frame.0);
+    }
+}
+
+/// Reports every run scheduled for this frame once the render graph has finished.
+pub(super) fn report_fft_runs(
+    reports: Res<FftRunReports>,
+    skipped: Res<FftSkippedRuns>,
+    query: Query<(Entity, &MainEntity, &FftTriggered, Has<FftBindGroups>)>,
+) {
+    let skipped = std::mem::take(&mut *skipped.0.lock().unwrap());
+    let mut reports = reports.0.lock().unwrap();
+    for (entity, main_entity, triggered, ready) in &query {
+        let Some(frame) = triggered.frame else {
+            continue;
+        };
+        reports.push(FftRunReport {
+            entity: main_entity.id(),
+            frame,
+            ran: ready && !skipped.contains(&entity),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::{message::Messages, system::SystemId};
+
+    use super::*;
+
+    #[derive(Resource)]
+    struct UpdateTriggers(SystemId);
+
+    fn world() -> World {
+        let mut world = World::new();
+        world.init_resource::<FrameCount>();
+        world.init_resource::<FftRunReports>();
+        world.init_resource::<Messages<AssetEvent<Image>>>();
+        let system = world.register_system(update_fft_triggers);
+        world.insert_resource(UpdateTriggers(system));
+        world
+    }
+
+    /// Runs one frame of [`update_fft_triggers`] and returns the frame it scheduled, if any.
+    fn step(world: &mut World) -> Option<u32> {
+        let system = world.resource::<UpdateTriggers>().0;
+        world.run_system(system).unwrap();
+        world.resource_mut::<FrameCount>().0 += 1;
+        let mut states = world.query::<&FftTriggerState>();
+        states.single(world).unwrap().run_frame
+    }
+
+    fn report(world: &mut World, entity: Entity, frame: u32, ran: bool) {
+        let reports = world.resource::<FftRunReports>().clone();
+        reports
+            .0
+            .lock()
+            .unwrap()
+            .push(FftRunReport { entity, frame, ran });
+    }
+
+    #[test]
+    fn once_waits_for_a_successful_report() {
+        let mut world = world();
+        let entity = world.spawn((FftSource::default(), FftTrigger::Once)).id();
+        assert_eq!(step(&mut world), Some(0));
+        // Still in flight: nothing new is scheduled.
+        assert_eq!(step(&mut world), None);
+        // The kernels were not ready, so the run is scheduled again.
+        report(&mut world, entity, 0, false);
+        assert_eq!(step(&mut world), Some(2));
+        report(&mut world, entity, 2, true);
+        assert_eq!(step(&mut world), None);
+        assert_eq!(step(&mut world), None);
+    }
+
+    #[test]
+    fn manual_runs_only_on_request() {
+        let mut world = world();
+        let entity = world.spawn((FftSource::default(), FftTrigger::Manual)).id();
+        assert_eq!(step(&mut world), None);
+        world.entity_mut(entity).insert(FftRunRequest);
+        assert_eq!(step(&mut world), Some(1));
+        assert!(!world.entity(entity).contains::<FftRunRequest>());
+        report(&mut world, entity, 1, true);
+        assert_eq!(step(&mut world), None);
+    }
+
+    #[test]
+    fn on_change_follows_source_edits() {
+        let mut world = world();
+        let entity = world
+            .spawn((FftSource::default(), FftTrigger::OnChange))
+            .id();
+        assert_eq!(step(&mut world), Some(0));
+        report(&mut world, entity, 0, true);
+        assert_eq!(step(&mut world), None);
+        world
+            .get_mut::<FftSource>(entity)
+            .unwrap()
+            .spatial_display_gain = 2.0;
+        assert_eq!(step(&mut world), Some(2));
+    }
+
+    #[test]
+    fn switching_to_once_runs_again() {
+        let mut world = world();
+        let entity = world.spawn((FftSource::default(), FftTrigger::Manual)).id();
+        assert_eq!(step(&mut world), None);
+        *world.get_mut::<FftTrigger>(entity).unwrap() = FftTrigger::Once;
+        assert_eq!(step(&mut world), Some(1));
+        report(&mut world, entity, 1, true);
+        assert_eq!(step(&mut world), None);
+    }
+}
@@ -6,9 +6,9 @@
 //!
 //! Import the usual surface from [`prelude`]: [`fft::FftPlugin`], [`fft::FftSource`], extracted types such as
 //! [`fft::FftSettings`] and [`fft::FftTextures`], graph splice helpers, [`fft::FftInputTexture`] and
-//! [`fft::prepare_fft_bind_groups`] for the `fft` example, plus [`ocean`], [`ewave`], and [`shallow_water`]
-//! surface types. Twiddle helpers, [`fft::FftSpectrumPassthroughNode`], manual FFT dispatch, and other
-//! internals stay on [`fft`] and [`fft::resources`].
+//! [`fft::prepare_fft_bind_groups`] for the `fft` example, [`bloom`] camera types, plus [`ocean`], [`ewave`],
+//! and [`shallow_water`] surface types. Twiddle helpers, [`fft::FftSpectrumPassthroughNode`], manual FFT
+//! dispatch, and other internals stay on [`fft`] and [`fft::resources`].
 //!
 //! **Main world vs render world.** [`fft::FftSource`] is the component you spawn and edit in the
 //! main app. Each frame it is extracted into [`fft::FftSettings`], [`fft::FftRoots`], and related
@@ -22,11 +22,15 @@
 //! [`bevy::pbr::StandardMaterial`] plus [`ocean::OceanSurfaceExtension`]) and displaces a mesh using
 //! [`fft::FftTextures::spatial_output`]. Register [`fft::FftPlugin`] before [`ocean::OceanPlugin`] or
 //! [`ewave::EwavePlugin`] so each domain plugin’s `finish` runs after FFT render setup (see those modules).
-//! The GPU twiddle table uses one shared
-//! [`fft::resources::FftRootsBuffer`] for all [`fft::FftSource`] entities. Every active transform
-//! must use the same roots layout (typical when all grids share the stock forward table for their `orders`).
+//! Each [`fft::FftSource`] uploads its twiddle table into its own
+//! [`fft::resources::FftRootsBuffer`], so an ocean, a bloom FFT and an analysis FFT of different
+//! sizes can run in the same frame.
+//!
+//! The [`bloom`] module adds [`bloom::FftBloom`], a camera post-process that convolves the HDR view
+//! with a wide scatter kernel through an FFT workspace it manages per camera.
 //! Broader ocean and bloom plans live in **`ROADMAP.md`**.
 
+pub mod bloom;
 pub mod complex;
 pub mod ewave;
 pub mod fft;
@@ -12,10 +12,11 @@
 //! Treat Bevy X, Z, and up (Y) as meters. [`OceanSimSettings::tile_size`] is the periodic patch size in meters
 //! on the horizontal plane (shaders use Δk = 2π / tile_size).
 //!
-//! The GPU inverse FFT applies a 1/N factor per axis so the 2D result matches the usual inverse of an
-//! unnormalized DFT. Skipping that would break the frequency-to-spatial relationship. For a physically
+//! The ocean grid keeps the default [`FftNormalization::Backward`](crate::fft::FftNormalization::Backward),
+//! so the GPU inverse FFT applies a 1/N factor per axis and the 2D result matches the usual inverse of an
+//! unnormalized DFT. Switching conventions would break the frequency-to-spatial relationship. For a physically
 //! meaningful sea state in meters, calibrate variance in `init_h0` (and any global gain) so heights are
-//! correct after this normalization, rather than editing `inv_dim` in `ifft.wgsl`.
+//! correct after this normalization, rather than changing the convention on the ocean's `FftSource`.
 
 mod render;
 
@@ -47,7 +48,7 @@ use render::{
 };
 
 use crate::fft::{
-    FftPlugin, FftSystemSet, prepare_fft_bind_groups, splice_after_resolve_outputs,
+    FftBackend, FftPlugin, FftSystemSet, prepare_fft_bind_groups, splice_after_resolve_outputs,
     splice_spectrum_pass,
 };
 
@@ -230,7 +231,11 @@ impl Plugin for OceanPlugin {
     fn finish(&self, app: &mut App) {
         assert!(
             app.is_plugin_added::<FftPlugin>(),
-            "OceanPlugin requires FftPlugin to be registered first (e.g. add_plugins((FftPlugin, OceanPlugin)))."
+            "OceanPlugin requires FftPlugin to be registered first (e.g. add_plugins((FftPlugin::default(), OceanPlugin)))."
+        );
+        assert!(
+            app.world().get_resource::<FftBackend>() == Some(&FftBackend::Textures),
+            "OceanPlugin writes the FFT workspace textures and requires FftBackend::Textures."
         );
         if app.get_sub_app_mut(RenderApp).is_none() {
             return;
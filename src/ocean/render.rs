@@ -14,6 +14,7 @@ use bevy::{
     prelude::*,
     reflect::Reflect,
     render::{
+        diagnostic::RecordDiagnostics,
         extract_component::{ComponentUniforms, ExtractComponent},
         render_asset::RenderAssets,
         render_graph::{Node, NodeRunError, RenderGraphContext, RenderLabel},
@@ -22,13 +23,14 @@ use bevy::{
             *,
         },
         renderer::RenderDevice,
+        sync_world::MainEntity,
         texture::GpuImage,
     },
     shader::ShaderDefVal,
 };
 
 use crate::fft::{
-    FftSettings, FftSource,
+    FftSettings, FftSource, FftTimedPass,
     resources::{FftBindGroupLayouts, FftBindGroups, FftTextures},
 };
 
@@ -323,13 +325,17 @@ impl FromWorld for OceanComputePipelines {
     }
 }
 
+type PrepareOceanH0ImageQuery<'w, 's> = Query<
+    'w,
+    's,
+    (Entity, &'static FftSource),
+    (With<crate::ocean::OceanSimSettings>, Without<OceanH0Image>),
+>;
+
 pub(super) fn prepare_ocean_h0_image(
     mut commands: Commands,
     mut images: ResMut<Assets<Image>>,
-    query: Query<
-        (Entity, &FftSource),
-        (With<crate::ocean::OceanSimSettings>, Without<OceanH0Image>),
-    >,
+    query: PrepareOceanH0ImageQuery,
 ) {
     for (entity, source) in &query {
         let mut image = Image::new_fill(
@@ -381,13 +387,17 @@ pub(super) fn sync_ocean_dynamic_uniform(
     }
 }
 
+type PrepareOceanFoamMaskImageQuery<'w, 's> = Query<
+    'w,
+    's,
+    (Entity, &'static FftSource),
+    (With<crate::ocean::OceanSimSettings>, Without<OceanFoamMask>),
+>;
+
 pub(super) fn prepare_ocean_foam_mask_image(
     mut commands: Commands,
     mut images: ResMut<Assets<Image>>,
-    query: Query<
-        (Entity, &FftSource),
-        (With<crate::ocean::OceanSimSettings>, Without<OceanFoamMask>),
-    >,
+    query: PrepareOceanFoamMaskImageQuery,
 ) {
     for (entity, source) in &query {
         let extent = Extent3d {
@@ -483,6 +493,7 @@ type PrepareOceanBgQuery<'w, 's> = Query<
     ),
 >;
 
+#[allow(clippy::too_many_arguments)]
 pub(super) fn prepare_ocean_compute_bind_groups(
     mut commands: Commands,
     render_device: Res<RenderDevice>,
@@ -600,6 +611,7 @@ pub(super) fn prepare_ocean_foam_bind_groups(
 
 pub struct OceanSpectrumNode {
     query: QueryState<(
+        &'static MainEntity,
         &'static FftBindGroups,
         &'static OceanComputeBindGroups,
         &'static FftSettings,
@@ -629,6 +641,7 @@ impl Node for OceanSpectrumNode {
         let pl = world.resource::<OceanComputePipelines>();
         let cache = world.resource::<PipelineCache>();
         let tracker = world.resource::<OceanInitTracker>();
+        let diagnostics = render_context.diagnostic_recorder();
 
         let Some(init_pl) = cache.get_compute_pipeline(pl.init) else {
             return Ok(());
@@ -640,7 +653,7 @@ impl Node for OceanSpectrumNode {
         let wg = 8u32;
         let enc = render_context.command_encoder();
 
-        for (fft_bg, ocean_bg, settings, h0_uni) in self.query.iter_manual(world) {
+        for (main_entity, fft_bg, ocean_bg, settings, h0_uni) in self.query.iter_manual(world) {
             let nx = settings.size.x.div_ceil(wg);
             let ny = settings.size.y.div_ceil(wg);
 
@@ -667,11 +680,14 @@ impl Node for OceanSpectrumNode {
                     label: Some("ocean_spectrum_to_c_pass"),
                     timestamp_writes: None,
                 });
+                let span =
+                    diagnostics.pass_span(&mut pass, FftTimedPass::Spectrum.span(main_entity.id()));
                 pass.set_pipeline(spec_pl);
-                pass.set_bind_group(0, &fft_bg.common, &[]);
+                fft_bg.set(&mut pass, 0);
                 pass.set_bind_group(1, &ocean_bg.spectrum_dynamic, &[]);
                 pass.set_bind_group(2, &ocean_bg.spectrum_h0_read, &[]);
                 pass.dispatch_workgroups(nx, ny, 1);
+                span.end(&mut pass);
             }
         }
 